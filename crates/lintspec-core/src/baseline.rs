@@ -0,0 +1,284 @@
+//! Baseline of known diagnostics
+//!
+//! A baseline records the diagnostics of a codebase at some point in time, so that lintspec can be adopted on an
+//! existing project without fixing all the findings first. When linting with a baseline, the diagnostics which are
+//! recorded in it are hidden and only the new ones are reported.
+//!
+//! The entries are identified by the file path, the parent contract, the item name, the rule and the param or return
+//! name (if any), but not by their position in the file. This way, unrelated edits don't invalidate the baseline.
+//! The entries which don't match any diagnostic anymore were fixed and can be removed from the baseline.
+use std::{
+    collections::{HashMap, HashSet},
+    fmt, fs,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    definitions::Parent,
+    error::{ErrorKind, Result},
+    lint::{Diagnostic, FileDiagnostics, Rule},
+    prelude::OrPanic as _,
+};
+
+/// A diagnostic recorded in the baseline
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct BaselineEntry {
+    /// The path to the file, relative to the root directory and with forward slashes
+    pub path: String,
+
+    /// The name of the parent contract, interface or library, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<String>,
+
+    /// The name of the source item
+    pub item: String,
+
+    /// The rule which was violated
+    pub rule: Rule,
+
+    /// The name of the param or return value which the diagnostic refers to, if any
+    ///
+    /// For unnamed return values, this is the position of the return value (e.g. `#1`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub param: Option<String>,
+}
+
+impl BaselineEntry {
+    /// Create the entry corresponding to a diagnostic
+    fn new(path: &str, parent: Option<&Parent>, item: &str, diag: &Diagnostic) -> Self {
+        Self {
+            path: path.to_string(),
+            parent: parent.map(ToString::to_string),
+            item: item.to_string(),
+            rule: diag.rule,
+            param: diag.param.clone(),
+        }
+    }
+}
+
+impl fmt::Display for BaselineEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: ", self.path)?;
+        if let Some(parent) = &self.parent {
+            write!(f, "{parent}.")?;
+        }
+        write!(f, "{} {}", self.item, self.rule)?;
+        if let Some(param) = &self.param {
+            write!(f, " ({param})")?;
+        }
+        Ok(())
+    }
+}
+
+/// A set of known diagnostics
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct Baseline {
+    /// The recorded diagnostics, sorted
+    pub entries: Vec<BaselineEntry>,
+}
+
+impl Baseline {
+    /// Record the diagnostics for a list of files
+    ///
+    /// The paths are made relative to the root directory if possible.
+    #[must_use]
+    pub fn new(diagnostics: &[FileDiagnostics], root_path: impl AsRef<Path>) -> Self {
+        let mut entries: Vec<_> = diagnostics
+            .iter()
+            .flat_map(|file_diags| {
+                let path = relative_path(&file_diags.path, root_path.as_ref());
+                file_diags.items.iter().flat_map(move |item| {
+                    let path = path.clone();
+                    item.diags.iter().map(move |diag| {
+                        BaselineEntry::new(&path, item.parent.as_ref(), item.name, diag)
+                    })
+                })
+            })
+            .collect();
+        entries.sort_unstable();
+        Self { entries }
+    }
+
+    /// Read a baseline from a JSON file
+    pub fn read(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let contents = fs::read_to_string(path).map_err(|err| ErrorKind::IOError {
+            path: path.to_path_buf(),
+            err,
+        })?;
+        serde_json::from_str(&contents).map_err(|err| {
+            ErrorKind::BaselineParsingError {
+                path: path.to_path_buf(),
+                err,
+            }
+            .into()
+        })
+    }
+
+    /// Write the baseline to a JSON file
+    pub fn write(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        let mut contents =
+            serde_json::to_string_pretty(self).or_panic("baseline should be serializable");
+        contents.push('\n');
+        fs::write(path, contents).map_err(|err| {
+            ErrorKind::IOError {
+                path: path.to_path_buf(),
+                err,
+            }
+            .into()
+        })
+    }
+
+    /// Remove the diagnostics which are recorded in the baseline
+    ///
+    /// Each entry hides at most one diagnostic. Items and files which have no diagnostic left are removed.
+    ///
+    /// The `linted` paths are the files which were analyzed. The entries which refer to one of those files (or to a
+    /// file which doesn't exist anymore) and which didn't match any diagnostic are returned: they correspond to
+    /// diagnostics which were fixed since the baseline was recorded.
+    pub fn filter(
+        &self,
+        diagnostics: &mut Vec<FileDiagnostics>,
+        linted: &[PathBuf],
+        root_path: impl AsRef<Path>,
+    ) -> Vec<&BaselineEntry> {
+        let root_path = root_path.as_ref();
+        let mut remaining: HashMap<&BaselineEntry, usize> = HashMap::new();
+        for entry in &self.entries {
+            *remaining.entry(entry).or_default() += 1;
+        }
+        for file_diags in diagnostics.iter_mut() {
+            let path = relative_path(&file_diags.path, root_path);
+            for item in &mut file_diags.items {
+                let (parent, name) = (item.parent.as_ref(), item.name);
+                item.diags.retain(|diag| {
+                    let entry = BaselineEntry::new(&path, parent, name, diag);
+                    match remaining.get_mut(&entry) {
+                        Some(count) if *count > 0 => {
+                            *count -= 1;
+                            false
+                        }
+                        _ => true,
+                    }
+                });
+            }
+            file_diags.items.retain(|i| !i.diags.is_empty());
+        }
+        diagnostics.retain(|f| !f.items.is_empty());
+
+        let linted: HashSet<_> = linted.iter().map(|p| relative_path(p, root_path)).collect();
+        let mut fixed = Vec::new();
+        for entry in &self.entries {
+            let Some(count) = remaining.get_mut(entry) else {
+                continue;
+            };
+            if *count == 0 {
+                continue;
+            }
+            if linted.contains(&entry.path) || !root_path.join(&entry.path).exists() {
+                *count -= 1;
+                fixed.push(entry);
+            }
+        }
+        fixed
+    }
+}
+
+/// Format a path relative to the root directory (if possible), with forward slashes
+fn relative_path(path: &Path, root_path: &Path) -> String {
+    path.strip_prefix(root_path)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .replace('\\', "/")
+}
+
+#[cfg(test)]
+mod tests {
+    use similar_asserts::assert_eq;
+
+    use crate::{
+        definitions::ItemType, lint::ItemDiagnostics, parser::DocumentId, textindex::TextIndex,
+    };
+
+    use super::*;
+
+    fn diag(rule: Rule, message: &str) -> Diagnostic {
+        Diagnostic::new(rule, TextIndex::ZERO..TextIndex::ZERO, message)
+    }
+
+    fn file_diags(path: &str, diags: Vec<Diagnostic>) -> FileDiagnostics {
+        FileDiagnostics {
+            path: PathBuf::from(path),
+            document_id: DocumentId::new(),
+            items: vec![
+                ItemDiagnostics::builder()
+                    .parent(Parent::Contract("Foo"))
+                    .item_type(ItemType::ExternalFunction)
+                    .name("bar")
+                    .span(TextIndex::ZERO..TextIndex::ZERO)
+                    .diags(diags)
+                    .build(),
+            ],
+        }
+    }
+
+    #[test]
+    fn test_entry_param() {
+        let diag = diag(Rule::MissingReturn, "any message").with_param("#1");
+        let entry = BaselineEntry::new("src/Foo.sol", None, "bar", &diag);
+        assert_eq!(entry.param.as_deref(), Some("#1"));
+        assert_eq!(entry.to_string(), "src/Foo.sol: bar missing-return (#1)");
+    }
+
+    #[test]
+    fn test_filter() {
+        let diagnostics = vec![file_diags(
+            "/project/src/Foo.sol",
+            vec![
+                diag(Rule::MissingParam, "@param a is missing").with_param("a"),
+                diag(Rule::MissingNotice, "@notice is missing"),
+            ],
+        )];
+        let baseline = Baseline::new(&diagnostics, "/project");
+        assert_eq!(baseline.entries.len(), 2);
+        assert_eq!(
+            baseline.entries[0].to_string(),
+            "src/Foo.sol: Foo.bar missing-param (a)"
+        );
+        assert_eq!(
+            baseline.entries[1].to_string(),
+            "src/Foo.sol: Foo.bar missing-notice"
+        );
+
+        // the notice was fixed, a new param was added
+        let mut diagnostics = vec![file_diags(
+            "/project/src/Foo.sol",
+            vec![
+                diag(Rule::MissingParam, "@param a is missing").with_param("a"),
+                diag(Rule::MissingParam, "@param b is missing").with_param("b"),
+            ],
+        )];
+        let linted = vec![PathBuf::from("/project/src/Foo.sol")];
+        let fixed = baseline.filter(&mut diagnostics, &linted, "/project");
+        assert_eq!(fixed, vec![&baseline.entries[1]]);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].items[0].diags.len(), 1);
+        assert_eq!(
+            diagnostics[0].items[0].diags[0].message,
+            "@param b is missing"
+        );
+
+        // all diagnostics are in the baseline
+        let mut diagnostics = vec![file_diags(
+            "/project/src/Foo.sol",
+            vec![diag(Rule::MissingParam, "@param a is missing").with_param("a")],
+        )];
+        baseline.filter(&mut diagnostics, &linted, "/project");
+        assert!(diagnostics.is_empty());
+    }
+}
@@ -0,0 +1,439 @@
+//! Persistent cache of the diagnostics
+//!
+//! The diagnostics of each file are stored in a cache directory, so that the files which didn't change since the
+//! last run don't need to be parsed again. An entry is only used if the hash of the file contents, the hash of the
+//! validation options and the lintspec version all match the ones which were recorded in it.
+//!
+//! Since the `@inheritdoc` checks depend on the imported files, an entry also records the hash of each (directly or
+//! transitively) imported file, and is discarded if one of them changed. Imports which could not be resolved when the
+//! entry was created are not tracked.
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs, io,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use serde::{Deserialize, Serialize};
+use twox_hash::XxHash3_128;
+
+use crate::{
+    definitions::{ItemType, Parent},
+    error::{ErrorKind, Result},
+    inheritance::Inheritance,
+    interner::INTERNER,
+    lint::{Diagnostic, FileDiagnostics, ItemDiagnostics, ValidationOptions, lint_document},
+    parser::{DocumentId, Parse},
+    prelude::OrPanic as _,
+    project::Project,
+    textindex::TextRange,
+};
+
+/// The default path of the cache directory, relative to the current directory
+pub const CACHE_DIR: &str = ".lintspec-cache";
+
+/// The parent of a cached item, with its kind (the serialized [`Parent`] only contains the name)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+enum CachedParent {
+    Contract(String),
+    Interface(String),
+    Library(String),
+}
+
+/// The diagnostics for a single source item, as stored in the cache
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CachedItem {
+    parent: Option<CachedParent>,
+    item_type: ItemType,
+    name: String,
+    span: TextRange,
+    diags: Vec<Diagnostic>,
+}
+
+impl From<&ItemDiagnostics> for CachedItem {
+    fn from(value: &ItemDiagnostics) -> Self {
+        Self {
+            parent: value.parent.as_ref().map(|parent| match parent {
+                Parent::Contract(name) => CachedParent::Contract((*name).to_string()),
+                Parent::Interface(name) => CachedParent::Interface((*name).to_string()),
+                Parent::Library(name) => CachedParent::Library((*name).to_string()),
+            }),
+            item_type: value.item_type,
+            name: value.name.to_string(),
+            span: value.span.clone(),
+            diags: value.diags.clone(),
+        }
+    }
+}
+
+impl From<CachedItem> for ItemDiagnostics {
+    fn from(value: CachedItem) -> Self {
+        let intern = |name: &str| INTERNER.get_or_intern(name).resolve_with(&INTERNER);
+        Self {
+            parent: value.parent.map(|parent| match parent {
+                CachedParent::Contract(name) => Parent::Contract(intern(&name)),
+                CachedParent::Interface(name) => Parent::Interface(intern(&name)),
+                CachedParent::Library(name) => Parent::Library(intern(&name)),
+            }),
+            item_type: value.item_type,
+            name: intern(&value.name),
+            span: value.span,
+            diags: value.diags,
+        }
+    }
+}
+
+/// The cached diagnostics of a file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Entry {
+    /// The version of lintspec which produced the diagnostics
+    version: String,
+
+    /// The hash of the validation options
+    options: String,
+
+    /// The hash of the file contents
+    hash: String,
+
+    /// The hash of each imported file
+    dependencies: BTreeMap<PathBuf, String>,
+
+    /// The diagnostics of the file, grouped by source item
+    items: Vec<CachedItem>,
+}
+
+/// A persistent cache of the diagnostics, stored in a directory
+///
+/// The directory is created when the first entry is written. Each file has at most one entry, which is replaced
+/// whenever the file is linted again. The hashes of the imported files are computed once and reused for the lifetime
+/// of the cache, so a new one should be created after the files were modified (e.g. by applying fixes).
+#[derive(Debug)]
+pub struct Cache {
+    /// The cache directory
+    dir: PathBuf,
+
+    /// The version of lintspec, entries created by another version are ignored
+    version: String,
+
+    /// The validation options used to lint the files which are not in the cache
+    options: ValidationOptions,
+
+    /// The hash of the validation options
+    options_hash: String,
+
+    /// The hashes of the files which were already read (`None` if the file couldn't be read)
+    hashes: Mutex<HashMap<PathBuf, Option<String>>>,
+}
+
+impl Cache {
+    /// Create a cache in the given directory, for the given validation options and lintspec version
+    #[must_use]
+    pub fn new(
+        dir: impl Into<PathBuf>,
+        options: &ValidationOptions,
+        version: impl Into<String>,
+    ) -> Self {
+        let serialized =
+            serde_json::to_string(options).or_panic("validation options should be serializable");
+        Self {
+            dir: dir.into(),
+            version: version.into(),
+            options: options.clone(),
+            options_hash: hash(serialized.as_bytes()),
+            hashes: Mutex::default(),
+        }
+    }
+
+    /// Lint a Solidity file, or retrieve its diagnostics from the cache if it didn't change
+    ///
+    /// This is the same as [`lint`](crate::lint::lint) with the options of the cache. If the diagnostics come from the
+    /// cache, the document was not parsed and its contents can't be retrieved from the parser. Failing to write the
+    /// cache entry is not an error.
+    pub fn lint(
+        &self,
+        mut parser: impl Parse,
+        path: impl AsRef<Path>,
+        project: Option<&Project>,
+        keep_contents: bool,
+    ) -> Result<Option<FileDiagnostics>> {
+        let path = path.as_ref();
+        let source = fs::read_to_string(path).map_err(|err| ErrorKind::IOError {
+            path: path.to_path_buf(),
+            err,
+        })?;
+        let hash = hash(source.as_bytes());
+        let entry_path = self.entry_path(path);
+        if let Some(items) = self.lookup(&entry_path, &hash) {
+            return Ok((!items.is_empty()).then(|| FileDiagnostics {
+                path: path.to_path_buf(),
+                document_id: DocumentId::new(),
+                items,
+            }));
+        }
+
+        let document = parser.parse_document(source.as_bytes(), Some(path), keep_contents)?;
+        let imports = document.imports.clone();
+        // the imported files are only parsed if needed, they are the dependencies of the cache entry
+        let mut units = Vec::new();
+        let diagnostics = lint_document(
+            path,
+            &source,
+            document,
+            || {
+                units = project
+                    .map(|p| p.imported_units(&parser, path, &imports))
+                    .unwrap_or_default();
+                let mut inheritance = Inheritance::default();
+                for unit in &units {
+                    inheritance.extend(&unit.definitions);
+                }
+                inheritance
+            },
+            &self.options,
+        );
+
+        let entry = Entry {
+            version: self.version.clone(),
+            options: self.options_hash.clone(),
+            hash,
+            dependencies: units
+                .iter()
+                .filter_map(|u| Some((u.path.clone(), self.file_hash(&u.path)?)))
+                .collect(),
+            items: diagnostics
+                .iter()
+                .flat_map(|d| d.items.iter().map(CachedItem::from))
+                .collect(),
+        };
+        // the cache only speeds up the next runs, so the diagnostics are returned even if it can't be written
+        let _ = self.write_entry(&entry_path, &entry);
+        Ok(diagnostics)
+    }
+
+    /// Remove a cache directory and all its entries
+    ///
+    /// Returns `false` if the directory didn't exist.
+    pub fn clean(dir: impl AsRef<Path>) -> Result<bool> {
+        let dir = dir.as_ref();
+        if !dir.exists() {
+            return Ok(false);
+        }
+        fs::remove_dir_all(dir).map_err(|err| ErrorKind::IOError {
+            path: dir.to_path_buf(),
+            err,
+        })?;
+        Ok(true)
+    }
+
+    /// The path of the entry for a file, named after the hash of its path
+    fn entry_path(&self, path: &Path) -> PathBuf {
+        let name = hash(path.to_string_lossy().as_bytes());
+        self.dir.join(format!("{name}.json"))
+    }
+
+    /// Retrieve the cached diagnostics of a file, if the entry is still valid
+    fn lookup(&self, entry_path: &Path, hash: &str) -> Option<Vec<ItemDiagnostics>> {
+        let entry: Entry = serde_json::from_slice(&fs::read(entry_path).ok()?).ok()?;
+        if entry.version != self.version || entry.options != self.options_hash || entry.hash != hash
+        {
+            return None;
+        }
+        if entry
+            .dependencies
+            .iter()
+            .any(|(path, hash)| self.file_hash(path).as_ref() != Some(hash))
+        {
+            return None;
+        }
+        Some(entry.items.into_iter().map(Into::into).collect())
+    }
+
+    /// Compute the hash of a file's contents, or retrieve it if it was already computed
+    fn file_hash(&self, path: &Path) -> Option<String> {
+        if let Some(hash) = self
+            .hashes
+            .lock()
+            .or_panic("mutex should not be poisoned")
+            .get(path)
+        {
+            return hash.clone();
+        }
+        let hash = fs::read(path).ok().map(|contents| hash(&contents));
+        self.hashes
+            .lock()
+            .or_panic("mutex should not be poisoned")
+            .insert(path.to_path_buf(), hash.clone());
+        hash
+    }
+
+    /// Write an entry to the cache directory, creating it if needed
+    fn write_entry(&self, entry_path: &Path, entry: &Entry) -> io::Result<()> {
+        if !self.dir.is_dir() {
+            fs::create_dir_all(&self.dir)?;
+            // the cache should not be committed
+            fs::write(self.dir.join(".gitignore"), "*\n")?;
+        }
+        // the entry is written to a temporary file first, so that a concurrent run never reads a partial entry
+        let tmp = entry_path.with_extension(format!("{:08x}.tmp", fastrand::u32(..)));
+        fs::write(&tmp, serde_json::to_vec(entry)?)?;
+        fs::rename(tmp, entry_path)
+    }
+}
+
+/// Compute the hexadecimal representation of the 128-bit XXH3 hash of some bytes
+fn hash(bytes: &[u8]) -> String {
+    format!("{:032x}", XxHash3_128::oneshot(bytes))
+}
+
+#[cfg(test)]
+#[cfg(feature = "solar")]
+mod tests {
+    use temp_dir::TempDir;
+
+    use crate::{lint::lint, parser::solar::SolarParser};
+
+    use super::*;
+
+    const SOURCE: &str = "contract Foo {
+    /// @notice Does something
+    function foo(uint256 a) external returns (uint256) {}
+}
+";
+
+    #[test]
+    fn test_cache() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.child("Foo.sol");
+        fs::write(&path, SOURCE).unwrap();
+        let options = ValidationOptions::default();
+        let cache = Cache::new(dir.child(CACHE_DIR), &options, "1.0.0");
+        let entry_path = cache.entry_path(&path);
+        assert!(
+            cache
+                .lookup(&entry_path, &hash(SOURCE.as_bytes()))
+                .is_none()
+        );
+
+        let diags = cache
+            .lint(SolarParser::default(), &path, None, false)
+            .unwrap()
+            .unwrap();
+        let expected = lint(SolarParser::default(), &path, &options, false)
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            serde_json::to_string(&diags).unwrap(),
+            serde_json::to_string(&expected).unwrap()
+        );
+        assert!(dir.child(CACHE_DIR).join(".gitignore").is_file());
+
+        // the entry is used by another run with the same settings
+        let cache = Cache::new(dir.child(CACHE_DIR), &options, "1.0.0");
+        let items = cache.lookup(&entry_path, &hash(SOURCE.as_bytes())).unwrap();
+        assert_eq!(
+            serde_json::to_string(&items).unwrap(),
+            serde_json::to_string(&expected.items).unwrap()
+        );
+        let cached = cache
+            .lint(SolarParser::default(), &path, None, false)
+            .unwrap()
+            .unwrap();
+        assert_eq!(cached.items[0].parent, Some(Parent::Contract("Foo")));
+        assert_eq!(
+            serde_json::to_string(&cached).unwrap(),
+            serde_json::to_string(&expected).unwrap()
+        );
+
+        // the entry is invalidated by a change of the contents, the options or the version
+        assert!(
+            cache
+                .lookup(&entry_path, &hash(b"contract Foo {}"))
+                .is_none()
+        );
+        let other_options = ValidationOptions::builder().inheritdoc(false).build();
+        let cache = Cache::new(dir.child(CACHE_DIR), &other_options, "1.0.0");
+        assert!(
+            cache
+                .lookup(&entry_path, &hash(SOURCE.as_bytes()))
+                .is_none()
+        );
+        let cache = Cache::new(dir.child(CACHE_DIR), &options, "1.0.1");
+        assert!(
+            cache
+                .lookup(&entry_path, &hash(SOURCE.as_bytes()))
+                .is_none()
+        );
+
+        assert!(Cache::clean(dir.child(CACHE_DIR)).unwrap());
+        assert!(!Cache::clean(dir.child(CACHE_DIR)).unwrap());
+    }
+
+    #[test]
+    fn test_cache_stray_comments() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.child("Foo.sol");
+        let source = "/// @notice Stray\n\ncontract Foo {}\n";
+        fs::write(&path, source).unwrap();
+        let options = ValidationOptions::builder().stray_comments(true).build();
+        let cache = Cache::new(dir.child(CACHE_DIR), &options, "1.0.0");
+        let expected = cache
+            .lint(SolarParser::default(), &path, None, false)
+            .unwrap()
+            .unwrap();
+        assert!(
+            expected
+                .items
+                .iter()
+                .any(|item| item.item_type == ItemType::Comment)
+        );
+
+        // the comment items are read back from the entry
+        let cache = Cache::new(dir.child(CACHE_DIR), &options, "1.0.0");
+        let items = cache
+            .lookup(&cache.entry_path(&path), &hash(source.as_bytes()))
+            .unwrap();
+        assert_eq!(
+            serde_json::to_string(&items).unwrap(),
+            serde_json::to_string(&expected.items).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_cache_dependencies() {
+        let dir = TempDir::new().unwrap();
+        let base = dir.child("Base.sol");
+        let path = dir.child("Foo.sol");
+        fs::write(
+            &base,
+            "interface IBase {\n    /// @notice Foo\n    function foo() external;\n}\n",
+        )
+        .unwrap();
+        fs::write(
+            &path,
+            "import './Base.sol';\ncontract Foo is IBase {\n    /// @inheritdoc IBase\n    function foo() external {}\n}\n",
+        )
+        .unwrap();
+        let options = ValidationOptions::default();
+        let project = Project::load(dir.path());
+        let cache = Cache::new(dir.child(CACHE_DIR), &options, "1.0.0");
+        let diags = cache.lint(SolarParser::default(), &path, Some(&project), false);
+        assert!(diags.unwrap().is_none());
+        let source = fs::read_to_string(&path).unwrap();
+        let entry_path = cache.entry_path(&path);
+        let cache = Cache::new(dir.child(CACHE_DIR), &options, "1.0.0");
+        assert!(
+            cache
+                .lookup(&entry_path, &hash(source.as_bytes()))
+                .is_some()
+        );
+
+        // the entry is invalidated when the imported file changes
+        fs::write(&base, "interface IBase {}\n").unwrap();
+        let cache = Cache::new(dir.child(CACHE_DIR), &options, "1.0.0");
+        assert!(
+            cache
+                .lookup(&entry_path, &hash(source.as_bytes()))
+                .is_none()
+        );
+    }
+}
@@ -0,0 +1,222 @@
+//! Lines changed relative to a git ref
+//!
+//! The changed lines are obtained from the hunks of `git diff <ref>`, which compares the working tree with the ref.
+//! Untracked files (which are not ignored) are considered entirely changed. This allows to only report the diagnostics
+//! for the items which were modified, for instance in a pull request.
+use std::{
+    collections::HashMap,
+    ops::Range,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use crate::{
+    error::{ErrorKind, Result},
+    lint::{FileDiagnostics, ItemDiagnostics},
+    textindex::TextRange,
+};
+
+/// The lines which were changed in each file
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ChangedLines {
+    /// The ranges of changed lines (0-based) for each modified file, or `None` if the whole file is new
+    files: HashMap<PathBuf, Option<Vec<Range<u32>>>>,
+}
+
+impl ChangedLines {
+    /// Retrieve the lines changed since a git ref, for the repository containing the `cwd` directory
+    ///
+    /// The paths are canonicalized.
+    pub fn from_git(git_ref: &str, cwd: impl AsRef<Path>) -> Result<Self> {
+        let cwd = cwd.as_ref();
+        let root = git(cwd, &["rev-parse", "--show-toplevel"])?;
+        let root = dunce::canonicalize(root.trim()).map_err(|err| ErrorKind::IOError {
+            path: PathBuf::from(root.trim()),
+            err,
+        })?;
+        let diff = git(
+            &root,
+            &[
+                "-c",
+                "core.quotePath=false",
+                "diff",
+                "--no-color",
+                "--no-ext-diff",
+                "--unified=0",
+                git_ref,
+                "--",
+            ],
+        )?;
+        let mut changed = Self::from_diff(&diff, &root);
+        let untracked = git(
+            &root,
+            &[
+                "ls-files",
+                "--others",
+                "--exclude-standard",
+                "-z",
+                "--full-name",
+            ],
+        )?;
+        for path in untracked.split('\0').filter(|p| !p.is_empty()) {
+            changed.files.insert(root.join(path), None);
+        }
+        Ok(changed)
+    }
+
+    /// Parse the output of `git diff --unified=0`, where the paths are relative to the `root` directory
+    #[must_use]
+    pub fn from_diff(diff: &str, root: impl AsRef<Path>) -> Self {
+        let root = root.as_ref();
+        let mut files: HashMap<PathBuf, Option<Vec<Range<u32>>>> = HashMap::new();
+        let mut current: Option<&mut Vec<Range<u32>>> = None;
+        for line in diff.lines() {
+            if let Some(path) = line.strip_prefix("+++ ") {
+                // deleted files are reported as `/dev/null`
+                current = path
+                    .trim_end_matches('\t')
+                    .strip_prefix("b/")
+                    .and_then(|path| {
+                        files
+                            .entry(root.join(path))
+                            .or_insert_with(|| Some(Vec::new()))
+                            .as_mut()
+                    });
+            } else if let Some(hunk) = line.strip_prefix("@@ ")
+                && let Some(lines) = current.as_mut()
+                && let Some(range) = parse_hunk(hunk)
+            {
+                lines.push(range);
+            }
+        }
+        Self { files }
+    }
+
+    /// Check whether a file was changed
+    #[must_use]
+    pub fn contains_file(&self, path: impl AsRef<Path>) -> bool {
+        self.files.contains_key(path.as_ref())
+    }
+
+    /// Check whether a span in a file overlaps the changed lines
+    #[must_use]
+    pub fn overlaps(&self, path: impl AsRef<Path>, span: &TextRange) -> bool {
+        match self.files.get(path.as_ref()) {
+            Some(None) => true,
+            Some(Some(lines)) => lines
+                .iter()
+                .any(|l| l.start <= span.end.line && span.start.line < l.end),
+            None => false,
+        }
+    }
+
+    /// Remove the items which don't overlap the changed lines, as well as the files which don't have items anymore
+    ///
+    /// An item is kept if either its span or the span of one of its diagnostics (e.g. in the doc-comment) overlaps the
+    /// changed lines.
+    pub fn filter(&self, diagnostics: &mut Vec<FileDiagnostics>) {
+        for file_diags in diagnostics.iter_mut() {
+            let path = &file_diags.path;
+            file_diags
+                .items
+                .retain(|item| self.item_overlaps(path, item));
+        }
+        diagnostics.retain(|f| !f.items.is_empty());
+    }
+
+    /// Check whether an item or one of its diagnostics overlaps the changed lines
+    fn item_overlaps(&self, path: &Path, item: &ItemDiagnostics) -> bool {
+        self.overlaps(path, &item.span) || item.diags.iter().any(|d| self.overlaps(path, &d.span))
+    }
+}
+
+/// Run a git command in a directory and return its standard output
+fn git(dir: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .map_err(|err| ErrorKind::IOError {
+            path: PathBuf::from("git"),
+            err,
+        })?;
+    if !output.status.success() {
+        return Err(ErrorKind::GitError {
+            message: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        }
+        .into());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Parse the range of lines (0-based) in the new file from a hunk header like `-12,3 +14,5 @@`
+///
+/// A hunk which only removes lines marks the line preceding the removal as changed.
+fn parse_hunk(hunk: &str) -> Option<Range<u32>> {
+    let new = hunk.split_whitespace().find_map(|s| s.strip_prefix('+'))?;
+    let (start, count) = match new.split_once(',') {
+        Some((start, count)) => (start.parse::<u32>().ok()?, count.parse::<u32>().ok()?),
+        None => (new.parse::<u32>().ok()?, 1),
+    };
+    let start = start.saturating_sub(1);
+    Some(start..start + count.max(1))
+}
+
+#[cfg(test)]
+mod tests {
+    use similar_asserts::assert_eq;
+
+    use crate::textindex::TextIndex;
+
+    use super::*;
+
+    fn span(start_line: u32, end_line: u32) -> TextRange {
+        TextIndex {
+            line: start_line,
+            ..TextIndex::ZERO
+        }..TextIndex {
+            line: end_line,
+            ..TextIndex::ZERO
+        }
+    }
+
+    #[test]
+    fn test_parse_hunk() {
+        assert_eq!(parse_hunk("-12,3 +14,5 @@ contract Foo {"), Some(13..18));
+        assert_eq!(parse_hunk("-12 +14 @@"), Some(13..14));
+        assert_eq!(parse_hunk("-12,2 +11,0 @@"), Some(10..11));
+        assert_eq!(parse_hunk("-0,0 +1,3 @@"), Some(0..3));
+        assert_eq!(parse_hunk("garbage"), None);
+    }
+
+    #[test]
+    fn test_from_diff() {
+        let diff = "diff --git a/src/Foo.sol b/src/Foo.sol
+index 1234567..89abcde 100644
+--- a/src/Foo.sol
++++ b/src/Foo.sol
+@@ -3 +3 @@ contract Foo {
+-    function foo() external {}
++    function foo(uint256 a) external {}
+@@ -10,0 +11,2 @@ contract Foo {
++    /// @notice Bar
++    function bar() external {}
+diff --git a/src/Old.sol b/src/Old.sol
+deleted file mode 100644
+--- a/src/Old.sol
++++ /dev/null
+@@ -1,2 +0,0 @@
+-contract Old {
+-}
+";
+        let changed = ChangedLines::from_diff(diff, "/project");
+        assert!(changed.contains_file("/project/src/Foo.sol"));
+        assert!(!changed.contains_file("/project/src/Old.sol"));
+        assert!(changed.overlaps("/project/src/Foo.sol", &span(2, 2)));
+        assert!(changed.overlaps("/project/src/Foo.sol", &span(0, 20)));
+        assert!(changed.overlaps("/project/src/Foo.sol", &span(11, 11)));
+        assert!(!changed.overlaps("/project/src/Foo.sol", &span(5, 9)));
+        assert!(!changed.overlaps("/project/src/Foo.sol", &span(13, 15)));
+        assert!(!changed.overlaps("/project/src/Bar.sol", &span(2, 2)));
+    }
+}
@@ -1,13 +1,19 @@
 //! Tool configuration parsing and validation
-use std::path::PathBuf;
+use std::{collections::BTreeMap, path::PathBuf};
 
-use derive_more::IsVariant;
+use derive_more::{Display, FromStr, IsVariant};
 use figment::{
     Figment, Metadata, Profile, Provider,
-    providers::{Env, Format as _, Toml},
+    providers::{Env, Format as _, Serialized, Toml},
     value::{Dict, Map},
 };
-use serde::{Deserialize, Serialize};
+use regex::Regex;
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de::Error as _};
+
+use crate::{
+    definitions::{ItemType, Parent},
+    lint::Rule,
+};
 
 /// The requirement for a specific tag in the natspec comment
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, IsVariant)]
@@ -44,8 +50,188 @@ impl Req {
     }
 }
 
+/// The severity of a diagnostic
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, Display, FromStr, IsVariant,
+)]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+#[serde(rename_all = "lowercase")]
+#[display(rename_all = "lowercase")]
+pub enum Severity {
+    /// The diagnostic is an error, which makes the tool exit with a non-zero code
+    #[default]
+    Error,
+
+    /// The diagnostic is a warning, which is reported but doesn't make the tool fail
+    Warning,
+
+    /// The diagnostic is informational
+    Info,
+}
+
+/// Severity overrides for the rules
+///
+/// By default, all diagnostics have the [`Severity::Error`] severity.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, bon::Builder)]
+#[non_exhaustive]
+pub struct SeverityConfig {
+    /// Severity of the rules, for all item types
+    #[builder(default)]
+    pub rules: BTreeMap<Rule, Severity>,
+
+    /// Severity of the rules for specific item types
+    ///
+    /// This takes precedence over the `rules` setting.
+    #[serde(deserialize_with = "deserialize_item_types")]
+    #[builder(default)]
+    pub items: BTreeMap<ItemType, BTreeMap<Rule, Severity>>,
+}
+
+impl SeverityConfig {
+    /// Retrieve the severity of a rule for a given item type
+    #[must_use]
+    pub fn get(&self, item_type: ItemType, rule: Rule) -> Severity {
+        self.items
+            .get(&item_type)
+            .and_then(|rules| rules.get(&rule))
+            .or_else(|| self.rules.get(&rule))
+            .copied()
+            .unwrap_or_default()
+    }
+}
+
+/// A regular expression matched against the name of an item
+///
+/// The pattern is not anchored, use `^` and `$` to match the start or end of the name.
+#[derive(Debug, Clone)]
+pub struct NamePattern(Regex);
+
+impl NamePattern {
+    /// Compile a name pattern
+    pub fn new(pattern: &str) -> Result<Self, regex::Error> {
+        Ok(Self(Regex::new(pattern)?))
+    }
+
+    /// Check if a name matches the pattern
+    #[must_use]
+    pub fn is_match(&self, name: &str) -> bool {
+        self.0.is_match(name)
+    }
+
+    /// The source of the pattern
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        self.0.as_str()
+    }
+}
+
+impl PartialEq for NamePattern {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl Eq for NamePattern {}
+
+impl Serialize for NamePattern {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for NamePattern {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let pattern = String::deserialize(deserializer)?;
+        Self::new(&pattern).map_err(D::Error::custom)
+    }
+}
+
+/// Deserialize a map keyed by item type, rejecting the [`ItemType::Comment`] type which can't be configured
+fn deserialize_item_types<'de, D, V>(deserializer: D) -> Result<BTreeMap<ItemType, V>, D::Error>
+where
+    D: Deserializer<'de>,
+    V: Deserialize<'de>,
+{
+    let items = BTreeMap::<ItemType, V>::deserialize(deserializer)?;
+    if items.contains_key(&ItemType::Comment) {
+        return Err(D::Error::custom(
+            "the `comment` item type can't be configured",
+        ));
+    }
+    Ok(items)
+}
+
+/// Lists of patterns to include or exclude some names
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, bon::Builder)]
+#[non_exhaustive]
+pub struct NamePatterns {
+    /// Only the names which match one of these patterns are included (all names if empty)
+    #[serde(default)]
+    #[builder(default)]
+    pub include: Vec<NamePattern>,
+
+    /// The names which match one of these patterns are excluded, even if they are included above
+    #[serde(default)]
+    #[builder(default)]
+    pub exclude: Vec<NamePattern>,
+}
+
+impl NamePatterns {
+    /// Check if a name is included and not excluded
+    #[must_use]
+    pub fn allows(&self, name: &str) -> bool {
+        (self.include.is_empty() || self.include.iter().any(|p| p.is_match(name)))
+            && !self.exclude.iter().any(|p| p.is_match(name))
+    }
+}
+
+/// Filters on the names of the items which are checked
+///
+/// The items which are filtered out don't produce any diagnostic and are not counted in the coverage.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, bon::Builder)]
+#[non_exhaustive]
+pub struct FilterConfig {
+    /// Only the items whose name matches one of these patterns are checked (all items if empty)
+    #[serde(default)]
+    #[builder(default)]
+    pub include: Vec<NamePattern>,
+
+    /// The items whose name matches one of these patterns are not checked
+    #[serde(default)]
+    #[builder(default)]
+    pub exclude: Vec<NamePattern>,
+
+    /// Patterns for the names of specific item types, in addition to the ones above
+    #[serde(default, deserialize_with = "deserialize_item_types")]
+    #[builder(default)]
+    pub items: BTreeMap<ItemType, NamePatterns>,
+
+    /// Patterns for the name of the parent contract, interface or library of the items
+    ///
+    /// The contracts, interfaces and libraries themselves are also filtered by these patterns.
+    #[serde(default)]
+    #[builder(default)]
+    pub parents: NamePatterns,
+}
+
+impl FilterConfig {
+    /// Check if an item should be checked, according to its type, name and parent
+    #[must_use]
+    pub fn is_checked(&self, item_type: ItemType, parent: Option<&Parent>, name: &str) -> bool {
+        let parent_name = match (item_type, parent) {
+            (ItemType::Contract | ItemType::Interface | ItemType::Library, _) => Some(name),
+            (_, Some(Parent::Contract(p) | Parent::Interface(p) | Parent::Library(p))) => Some(*p),
+            (_, None) => None,
+        };
+        (self.include.is_empty() || self.include.iter().any(|p| p.is_match(name)))
+            && !self.exclude.iter().any(|p| p.is_match(name))
+            && self.items.get(&item_type).is_none_or(|p| p.allows(name))
+            && parent_name.is_none_or(|p| self.parents.allows(p))
+    }
+}
+
 /// Validation rules for a function natspec comment
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, bon::Builder)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, bon::Builder)]
 #[non_exhaustive]
 pub struct FunctionRules {
     /// Requirement for the `@notice` tag
@@ -64,6 +250,11 @@ pub struct FunctionRules {
     #[serde(rename = "return")]
     #[builder(default = Req::Required)]
     pub returns: Req,
+
+    /// Requirement for each `@custom:<tag>` tag, keyed by the tag without the `custom:` prefix
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    #[builder(default)]
+    pub custom: BTreeMap<String, Req>,
 }
 
 impl Default for FunctionRules {
@@ -73,6 +264,7 @@ impl Default for FunctionRules {
             dev: Req::default(),
             param: Req::Required,
             returns: Req::Required,
+            custom: BTreeMap::default(),
         }
     }
 }
@@ -114,6 +306,11 @@ pub struct WithReturnsRules {
     #[serde(rename = "return")]
     #[builder(default = Req::Required)]
     pub returns: Req,
+
+    /// Requirement for each `@custom:<tag>` tag, keyed by the tag without the `custom:` prefix
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    #[builder(default)]
+    pub custom: BTreeMap<String, Req>,
 }
 
 impl Default for WithReturnsRules {
@@ -122,6 +319,7 @@ impl Default for WithReturnsRules {
             notice: Req::Required,
             dev: Req::default(),
             returns: Req::Required,
+            custom: BTreeMap::default(),
         }
     }
 }
@@ -134,6 +332,9 @@ pub struct NoticeDevRules {
     pub notice: Req,
     #[builder(default)]
     pub dev: Req,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    #[builder(default)]
+    pub custom: BTreeMap<String, Req>,
 }
 
 impl Default for NoticeDevRules {
@@ -141,6 +342,7 @@ impl Default for NoticeDevRules {
         Self {
             notice: Req::Required,
             dev: Req::default(),
+            custom: BTreeMap::default(),
         }
     }
 }
@@ -157,6 +359,9 @@ pub struct ContractRules {
     pub notice: Req,
     #[builder(default)]
     pub dev: Req,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    #[builder(default)]
+    pub custom: BTreeMap<String, Req>,
 }
 
 /// Validation rules for each state variable visibility (private, internal, public)
@@ -184,6 +389,9 @@ pub struct WithParamsRules {
     pub dev: Req,
     #[builder(default)]
     pub param: Req,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    #[builder(default)]
+    pub custom: BTreeMap<String, Req>,
 }
 
 impl Default for WithParamsRules {
@@ -192,6 +400,7 @@ impl Default for WithParamsRules {
             notice: Req::Required,
             dev: Req::default(),
             param: Req::default(),
+            custom: BTreeMap::default(),
         }
     }
 }
@@ -242,12 +451,52 @@ pub struct BaseConfig {
     #[builder(default)]
     pub notice_or_dev: bool,
 
+    /// The known `@custom:<tag>` tags (without the `custom:` prefix), other custom tags are reported unless they have a
+    /// rule for the item
+    ///
+    /// All custom tags are allowed if this list is empty.
+    #[builder(default)]
+    pub known_custom_tags: Vec<String>,
+
+    /// Report the doc-comments which are not attached to any item, and the regular comments containing `NatSpec` tags
+    #[builder(default)]
+    pub stray_comments: bool,
+
     /// Number of parallel workers/threads, or 0 to use the number of logical cores
     ///
     /// Defaults to 4.
     #[builder(default = 4)]
     pub parallel: usize,
 
+    /// Automatically fix the diagnostics which can be fixed (e.g. insert missing `@param` and `@return` tags)
+    #[builder(default)]
+    pub fix: bool,
+
+    /// When fixing, print a diff of the changes instead of writing to the files
+    #[builder(default)]
+    pub dry_run: bool,
+
+    /// Path to a baseline file, the diagnostics recorded in it are not reported
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub baseline: Option<PathBuf>,
+
+    /// Only report the diagnostics for the items which changed since this git ref
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub changed_since: Option<String>,
+
+    /// Store the diagnostics of each file in a cache, so that the files which didn't change are not parsed again
+    #[builder(default)]
+    pub cache: bool,
+
+    /// Read the Solidity source from stdin instead of files (only available from the command line)
+    #[serde(skip)]
+    #[builder(default)]
+    pub stdin: bool,
+
+    /// The path used to identify the source read from stdin in the output
+    #[serde(skip)]
+    pub stdin_filename: Option<PathBuf>,
+
     /// Skip the detection of the Solidity version and use the latest version supported by `slang_solidity`
     #[cfg_attr(not(feature = "slang"), serde(skip))]
     #[builder(default)]
@@ -262,12 +511,38 @@ impl Default for BaseConfig {
             inheritdoc: true,
             inheritdoc_override: false,
             notice_or_dev: false,
+            known_custom_tags: Vec::default(),
+            stray_comments: false,
             parallel: 4,
+            fix: false,
+            dry_run: false,
+            baseline: None,
+            changed_since: None,
+            cache: false,
+            stdin: false,
+            stdin_filename: None,
             skip_version_detection: false,
         }
     }
 }
 
+/// The format of the diagnostics output
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, Display, FromStr)]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+#[serde(rename_all = "lowercase")]
+#[display(rename_all = "lowercase")]
+pub enum OutputFormat {
+    /// Human-readable text output (pretty or compact)
+    #[default]
+    Text,
+
+    /// JSON output
+    Json,
+
+    /// SARIF 2.1.0 log, for consumption by code scanning tools
+    Sarif,
+}
+
 /// Output config for the tool
 #[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize, bon::Builder)]
 #[non_exhaustive]
@@ -278,9 +553,15 @@ pub struct OutputConfig {
     pub out: Option<PathBuf>,
 
     /// Output diagnostics in JSON format
+    ///
+    /// This is a shorthand for `format = "json"` and takes precedence over the `format` setting.
     #[builder(default)]
     pub json: bool,
 
+    /// Format of the diagnostics output
+    #[builder(default)]
+    pub format: OutputFormat,
+
     /// Compact output (minified JSON or compact text representation)
     #[builder(default)]
     pub compact: bool,
@@ -298,6 +579,18 @@ pub struct OutputConfig {
     pub exit_zero: bool,
 }
 
+impl OutputConfig {
+    /// Retrieve the effective output format, taking into account the `json` shorthand
+    #[must_use]
+    pub fn output_format(&self) -> OutputFormat {
+        if self.json {
+            OutputFormat::Json
+        } else {
+            self.format
+        }
+    }
+}
+
 /// The parsed and validated config for the tool
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, bon::Builder)]
 #[non_exhaustive]
@@ -364,6 +657,19 @@ pub struct Config {
     #[serde(rename = "variable")]
     #[builder(default)]
     pub variables: VariableConfig,
+
+    /// Severity of the diagnostics for each rule
+    #[builder(default)]
+    pub severity: SeverityConfig,
+
+    /// Filters on the names of the items which are checked
+    #[builder(default)]
+    pub filters: FilterConfig,
+
+    /// Rules which apply to some files only, on top of the rules above
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[builder(default)]
+    pub overrides: Vec<Override>,
 }
 
 impl Default for Config {
@@ -382,6 +688,9 @@ impl Default for Config {
             modifiers: WithParamsRules::required(),
             structs: WithParamsRules::default(),
             variables: VariableConfig::default(),
+            severity: SeverityConfig::default(),
+            filters: FilterConfig::default(),
+            overrides: Vec::default(),
         }
     }
 }
@@ -401,10 +710,109 @@ impl Config {
                 match k.as_str() {
                     "LINTSPEC.NOTICE.OR.DEV" => "LINTSPEC.NOTICE_OR_DEV".into(),
                     "LINTSPEC.SKIP.VERSION.DETECTION" => "LINTSPEC.SKIP_VERSION_DETECTION".into(),
+                    "LINTSPEC.DRY.RUN" => "LINTSPEC.DRY_RUN".into(),
+                    "LINTSPEC.CHANGED.SINCE" => "LINTSPEC.CHANGED_SINCE".into(),
                     _ => k.into(),
                 }
             }))
     }
+
+    /// Apply the rules of some overrides on top of this config, in order
+    ///
+    /// The tables of the overrides are merged recursively, so that an override only needs to specify the rules which
+    /// differ. The overrides of this config are not applied.
+    pub fn apply_overrides<'a>(
+        &self,
+        overrides: impl IntoIterator<Item = &'a Override>,
+    ) -> Result<Config, Box<figment::Error>> {
+        let mut base = self.clone();
+        base.overrides.clear();
+        overrides
+            .into_iter()
+            .fold(Figment::from(Serialized::defaults(base)), |figment, o| {
+                figment.merge(Serialized::defaults(&o.rules))
+            })
+            .extract()
+            .map_err(Box::new)
+    }
+}
+
+/// The tables of the config which can be overridden for some files
+pub const OVERRIDABLE_TABLES: &[&str] = &[
+    "constructor",
+    "contract",
+    "interface",
+    "library",
+    "enum",
+    "error",
+    "event",
+    "function",
+    "modifier",
+    "struct",
+    "variable",
+    "severity",
+    "filters",
+];
+
+/// Rules which apply to the files matching some glob patterns
+///
+/// An override can contain any of the [`OVERRIDABLE_TABLES`], with only the rules which differ from the main config.
+/// The patterns are matched against the path of the files relative to the current directory, e.g. `test/**` or
+/// `*.t.sol`. When several overrides match a file, they are applied in order.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "RawOverride")]
+#[non_exhaustive]
+pub struct Override {
+    /// The glob patterns of the files to which the override applies
+    pub files: Vec<String>,
+
+    /// The overridden tables, with the same structure as in the main config
+    #[serde(flatten)]
+    pub rules: Dict,
+}
+
+/// The rules are only compared by value, no floating-point number can be part of a valid override
+impl Eq for Override {}
+
+/// An override before its patterns and rules are validated
+#[derive(Deserialize)]
+struct RawOverride {
+    files: Vec<String>,
+
+    #[serde(flatten)]
+    rules: Dict,
+}
+
+impl TryFrom<RawOverride> for Override {
+    type Error = String;
+
+    fn try_from(value: RawOverride) -> Result<Self, Self::Error> {
+        if value.files.is_empty() {
+            return Err("an override needs at least one pattern in `files`".to_string());
+        }
+        for pattern in &value.files {
+            globset::Glob::new(pattern).map_err(|err| err.to_string())?;
+        }
+        if let Some(key) = value
+            .rules
+            .keys()
+            .find(|k| !OVERRIDABLE_TABLES.contains(&k.as_str()))
+        {
+            return Err(format!(
+                "`{key}` can't be overridden, expected one of {}",
+                OVERRIDABLE_TABLES.join(", ")
+            ));
+        }
+        let res = Override {
+            files: value.files,
+            rules: value.rules,
+        };
+        // the rules are checked by applying them to the default config
+        Config::default()
+            .apply_overrides([&res])
+            .map_err(|err| format!("{} for `{}`", err.kind, err.path.join(".")))?;
+        Ok(res)
+    }
 }
 
 /// Implement [`Provider`] for composability
@@ -441,6 +849,103 @@ mod tests {
         );
         assert_eq!(BaseConfig::default(), BaseConfig::builder().build());
         assert_eq!(OutputConfig::default(), OutputConfig::builder().build());
+        assert_eq!(SeverityConfig::default(), SeverityConfig::builder().build());
+        assert_eq!(FilterConfig::default(), FilterConfig::builder().build());
         assert_eq!(Config::default(), Config::builder().build());
     }
+
+    #[test]
+    fn test_severity() {
+        let config: Config = Figment::from(Config::default())
+            .admerge(Toml::string(
+                r#"
+                [severity.rules]
+                missing-dev = "warning"
+                missing-notice = "info"
+
+                [severity.items.internal_function]
+                missing-dev = "info"
+                "#,
+            ))
+            .extract()
+            .unwrap();
+        let severity = config.severity;
+        assert_eq!(
+            severity.get(ItemType::PublicFunction, Rule::MissingDev),
+            Severity::Warning
+        );
+        assert_eq!(
+            severity.get(ItemType::InternalFunction, Rule::MissingDev),
+            Severity::Info
+        );
+        assert_eq!(
+            severity.get(ItemType::InternalFunction, Rule::MissingNotice),
+            Severity::Info
+        );
+        assert_eq!(
+            severity.get(ItemType::Struct, Rule::MissingParam),
+            Severity::Error
+        );
+    }
+
+    #[test]
+    fn test_filters() {
+        let config: Config = Figment::from(Config::default())
+            .admerge(Toml::string(
+                r#"
+                [filters]
+                exclude = ["^_"]
+
+                [filters.items.public_function]
+                exclude = ["^test", "^invariant_", "^setUp$"]
+
+                [filters.parents]
+                exclude = ["^Mock"]
+                "#,
+            ))
+            .extract()
+            .unwrap();
+        let filters = config.filters;
+        let parent = Parent::Contract("FooTest");
+        assert!(filters.is_checked(ItemType::PublicFunction, Some(&parent), "foo"));
+        assert!(!filters.is_checked(ItemType::PublicFunction, Some(&parent), "testFoo"));
+        assert!(!filters.is_checked(ItemType::PublicFunction, Some(&parent), "setUp"));
+        assert!(filters.is_checked(ItemType::ExternalFunction, Some(&parent), "testFoo"));
+        assert!(!filters.is_checked(ItemType::PrivateFunction, Some(&parent), "_helper"));
+        assert!(!filters.is_checked(ItemType::Struct, None, "_Data"));
+        let parent = Parent::Contract("MockToken");
+        assert!(!filters.is_checked(ItemType::Event, Some(&parent), "Transfer"));
+        assert!(!filters.is_checked(ItemType::Contract, None, "MockToken"));
+        assert!(filters.is_checked(ItemType::Contract, None, "Token"));
+
+        let filters = FilterConfig::builder()
+            .include(vec![NamePattern::new("^foo").unwrap()])
+            .build();
+        assert!(filters.is_checked(ItemType::Error, None, "fooError"));
+        assert!(!filters.is_checked(ItemType::Error, None, "barError"));
+        assert!(
+            Figment::from(Config::default())
+                .admerge(Toml::string("[filters]\nexclude = [\"(\"]"))
+                .extract::<Config>()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_comment_item_type() {
+        assert!(
+            Figment::from(Config::default())
+                .admerge(Toml::string(
+                    "[severity.items.comment]\nmissing-dev = \"info\""
+                ))
+                .extract::<Config>()
+                .is_err()
+        );
+        assert!(
+            Figment::from(Config::default())
+                .admerge(Toml::string("[filters.items.comment]\nexclude = [\"^_\"]"))
+                .extract::<Config>()
+                .is_err()
+        );
+    }
 }
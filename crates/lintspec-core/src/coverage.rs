@@ -0,0 +1,388 @@
+//! Documentation coverage
+//!
+//! An item is considered documented if its `NatSpec` doesn't produce any diagnostic with the current configuration.
+//! The coverage of a codebase is the share of documented items, which can be broken down by file, by contract and by
+//! item type.
+use std::{
+    collections::BTreeMap,
+    fs,
+    ops::AddAssign,
+    path::{Path, PathBuf},
+};
+
+use derive_more::Display;
+use serde::Serialize;
+
+use crate::{
+    definitions::{ItemType, Parent},
+    error::{ErrorKind, Result},
+    lint::{ValidationOptions, validate_definitions},
+    parser::Parse,
+    project::Project,
+    suppression::Suppressions,
+};
+
+/// The format of the coverage report
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Display)]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+#[display(rename_all = "lowercase")]
+pub enum CoverageFormat {
+    /// Human-readable table
+    #[default]
+    Text,
+
+    /// JSON report
+    Json,
+
+    /// JSON endpoint for a shields.io badge
+    Badge,
+}
+
+/// The number of documented items among a set of items
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct Coverage {
+    /// The number of documented items
+    pub documented: usize,
+
+    /// The total number of items
+    pub total: usize,
+}
+
+impl Coverage {
+    /// The percentage of documented items
+    ///
+    /// If there are no items, the coverage is 100%.
+    #[must_use]
+    #[expect(clippy::cast_precision_loss)]
+    pub fn percent(&self) -> f64 {
+        if self.total == 0 {
+            return 100.0;
+        }
+        self.documented as f64 * 100.0 / self.total as f64
+    }
+
+    /// Count an item
+    fn add(&mut self, documented: bool) {
+        self.total += 1;
+        if documented {
+            self.documented += 1;
+        }
+    }
+}
+
+impl AddAssign for Coverage {
+    fn add_assign(&mut self, rhs: Self) {
+        self.documented += rhs.documented;
+        self.total += rhs.total;
+    }
+}
+
+/// Whether a single source item is documented
+#[derive(Debug, Clone, Serialize)]
+#[non_exhaustive]
+pub struct ItemCoverage {
+    /// The parent contract, interface or library, if any
+    pub parent: Option<Parent>,
+
+    /// The type of this source item
+    pub item_type: ItemType,
+
+    /// The name of the item
+    pub name: &'static str,
+
+    /// Whether the item has no diagnostic
+    pub documented: bool,
+}
+
+impl ItemCoverage {
+    /// The name of the contract, interface or library which contains the item, or which is the item itself
+    #[must_use]
+    pub fn contract(&self) -> Option<&'static str> {
+        match (&self.parent, self.item_type) {
+            (Some(Parent::Contract(name) | Parent::Interface(name) | Parent::Library(name)), _) => {
+                Some(name)
+            }
+            (None, ItemType::Contract | ItemType::Interface | ItemType::Library) => Some(self.name),
+            (None, _) => None,
+        }
+    }
+}
+
+/// The coverage of the items of a single Solidity file
+#[derive(Debug, Clone, Serialize)]
+#[non_exhaustive]
+pub struct FileCoverage {
+    /// Path to the file
+    pub path: PathBuf,
+
+    /// The source items, in order of appearance
+    pub items: Vec<ItemCoverage>,
+}
+
+/// Compute the documentation coverage of a file
+///
+/// The items are validated like with [`lint`][crate::lint::lint], but the items without diagnostic are kept. The
+/// `NatSpec` parsing errors are not counted as items.
+pub fn file_coverage(
+    mut parser: impl Parse,
+    path: impl AsRef<Path>,
+    options: &ValidationOptions,
+    project: Option<&Project>,
+) -> Result<FileCoverage> {
+    let path = path.as_ref();
+    let source = fs::read_to_string(path).map_err(|err| ErrorKind::IOError {
+        path: path.to_path_buf(),
+        err,
+    })?;
+    let document = parser.parse_document(source.as_bytes(), Some(path), false)?;
+    let resolved = options.overrides.resolve(path);
+    let options = resolved.as_deref().unwrap_or(options);
+    let suppressions = Suppressions::new(&source, &document.comments);
+    let mut items: Vec<_> = validate_definitions(
+        &source,
+        document.definitions,
+        || {
+            project
+                .map(|p| p.inheritance(&parser, path, &document.imports))
+                .unwrap_or_default()
+        },
+        &suppressions,
+        options,
+    )
+    .into_iter()
+    .map(|(_, item_diags)| item_diags)
+    .filter(|item_diags| {
+        !matches!(
+            item_diags.item_type,
+            ItemType::ParsingError | ItemType::Comment
+        )
+    })
+    .collect();
+    items.sort_unstable_by_key(|i| i.span.start);
+    Ok(FileCoverage {
+        path: path.to_path_buf(),
+        items: items
+            .into_iter()
+            .map(|i| ItemCoverage {
+                parent: i.parent,
+                item_type: i.item_type,
+                name: i.name,
+                documented: i.diags.is_empty(),
+            })
+            .collect(),
+    })
+}
+
+/// The coverage of a contract, interface or library
+#[derive(Debug, Clone, Serialize)]
+#[non_exhaustive]
+pub struct ContractCoverage {
+    /// The name of the contract
+    pub name: &'static str,
+
+    /// The coverage of the contract and its members
+    #[serde(flatten)]
+    pub coverage: Coverage,
+}
+
+/// The coverage of a file, broken down by contract
+#[derive(Debug, Clone, Serialize)]
+#[non_exhaustive]
+pub struct FileCoverageSummary {
+    /// The path to the file, relative to the root directory if possible
+    pub path: String,
+
+    /// The coverage of all the items in the file
+    #[serde(flatten)]
+    pub coverage: Coverage,
+
+    /// The coverage of each contract, interface or library in the file, in order of appearance
+    ///
+    /// The free functions and file-level items are not part of any contract.
+    pub contracts: Vec<ContractCoverage>,
+}
+
+/// The documentation coverage of a set of files
+#[derive(Debug, Clone, Default, Serialize)]
+#[non_exhaustive]
+pub struct CoverageReport {
+    /// The coverage of all the items
+    #[serde(flatten)]
+    pub total: Coverage,
+
+    /// The percentage of documented items
+    pub percent: f64,
+
+    /// The coverage for each type of item
+    pub item_types: BTreeMap<ItemType, Coverage>,
+
+    /// The coverage of each file
+    pub files: Vec<FileCoverageSummary>,
+}
+
+impl CoverageReport {
+    /// Aggregate the coverage of a list of files
+    ///
+    /// The paths are made relative to the root directory if possible.
+    #[must_use]
+    pub fn new(files: &[FileCoverage], root_path: impl AsRef<Path>) -> Self {
+        let root_path = root_path.as_ref();
+        let mut report = Self::default();
+        for file in files {
+            let mut summary = FileCoverageSummary {
+                path: file
+                    .path
+                    .strip_prefix(root_path)
+                    .unwrap_or(&file.path)
+                    .to_string_lossy()
+                    .replace('\\', "/"),
+                coverage: Coverage::default(),
+                contracts: Vec::new(),
+            };
+            for item in &file.items {
+                summary.coverage.add(item.documented);
+                report
+                    .item_types
+                    .entry(item.item_type)
+                    .or_default()
+                    .add(item.documented);
+                let Some(contract) = item.contract() else {
+                    continue;
+                };
+                if let Some(c) = summary.contracts.iter_mut().find(|c| c.name == contract) {
+                    c.coverage.add(item.documented);
+                } else {
+                    let mut coverage = Coverage::default();
+                    coverage.add(item.documented);
+                    summary.contracts.push(ContractCoverage {
+                        name: contract,
+                        coverage,
+                    });
+                }
+            }
+            report.total += summary.coverage;
+            report.files.push(summary);
+        }
+        report.percent = report.total.percent();
+        report
+    }
+
+    /// Create the JSON endpoint data for a [shields.io](https://shields.io/badges/endpoint-badge) badge
+    #[must_use]
+    pub fn badge(&self) -> Badge {
+        let color = match self.percent {
+            p if p >= 90.0 => "brightgreen",
+            p if p >= 80.0 => "green",
+            p if p >= 70.0 => "yellowgreen",
+            p if p >= 60.0 => "yellow",
+            p if p >= 50.0 => "orange",
+            _ => "red",
+        };
+        Badge {
+            schema_version: 1,
+            label: "natspec".to_string(),
+            message: format!("{:.0}%", self.percent.floor()),
+            color: color.to_string(),
+        }
+    }
+}
+
+/// The data for a [shields.io](https://shields.io/badges/endpoint-badge) endpoint badge
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct Badge {
+    pub schema_version: u8,
+    pub label: String,
+    pub message: String,
+    pub color: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use similar_asserts::assert_eq;
+
+    use super::*;
+
+    fn item(
+        parent: Option<Parent>,
+        item_type: ItemType,
+        name: &'static str,
+        documented: bool,
+    ) -> ItemCoverage {
+        ItemCoverage {
+            parent,
+            item_type,
+            name,
+            documented,
+        }
+    }
+
+    #[test]
+    fn test_report() {
+        let files = vec![
+            FileCoverage {
+                path: PathBuf::from("/project/src/Foo.sol"),
+                items: vec![
+                    item(None, ItemType::Contract, "Foo", true),
+                    item(
+                        Some(Parent::Contract("Foo")),
+                        ItemType::ExternalFunction,
+                        "foo",
+                        false,
+                    ),
+                    item(
+                        Some(Parent::Contract("Foo")),
+                        ItemType::ExternalFunction,
+                        "bar",
+                        true,
+                    ),
+                    item(None, ItemType::Error, "Err", false),
+                ],
+            },
+            FileCoverage {
+                path: PathBuf::from("/project/src/Bar.sol"),
+                items: vec![item(None, ItemType::Interface, "IBar", true)],
+            },
+        ];
+        let report = CoverageReport::new(&files, "/project");
+        assert_eq!(
+            report.total,
+            Coverage {
+                documented: 3,
+                total: 5
+            }
+        );
+        assert!((report.percent - 60.0).abs() < f64::EPSILON);
+        assert_eq!(
+            report.item_types[&ItemType::ExternalFunction],
+            Coverage {
+                documented: 1,
+                total: 2
+            }
+        );
+        assert_eq!(report.files.len(), 2);
+        assert_eq!(report.files[0].path, "src/Foo.sol");
+        assert_eq!(report.files[0].contracts.len(), 1);
+        assert_eq!(report.files[0].contracts[0].name, "Foo");
+        assert_eq!(
+            report.files[0].contracts[0].coverage,
+            Coverage {
+                documented: 2,
+                total: 3
+            }
+        );
+        assert_eq!(report.files[1].contracts[0].name, "IBar");
+        let badge = report.badge();
+        assert_eq!(badge.message, "60%");
+        assert_eq!(badge.color, "yellow");
+        let value = serde_json::to_value(&badge).unwrap();
+        assert_eq!(value["schemaVersion"], 1);
+    }
+
+    #[test]
+    fn test_empty() {
+        let report = CoverageReport::new(&[], "/project");
+        assert!((report.percent - 100.0).abs() < f64::EPSILON);
+    }
+}
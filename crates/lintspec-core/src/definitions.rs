@@ -2,6 +2,7 @@
 //!
 //! This module contains structs for each of the source item types that can be documented with `NatSpec`.
 //! The [`Definition`] type provides a unified interface to interact with the various types.
+use comment::CommentDefinition;
 use constructor::ConstructorDefinition;
 use contract::ContractDefinition;
 use derive_more::{Display, From, FromStr, IsVariant, TryInto};
@@ -19,10 +20,12 @@ use crate::{
     definitions::{interface::InterfaceDefinition, library::LibraryDefinition},
     error::ErrorKind,
     interner::Symbol,
-    lint::{Diagnostic, ItemDiagnostics, Validate, ValidationOptions},
+    lint::{CheckUnknownTags, Diagnostic, ItemDiagnostics, Rule, Validate, ValidationOptions},
+    natspec::NatSpec,
     textindex::TextRange,
 };
 
+pub mod comment;
 pub mod constructor;
 pub mod contract;
 pub mod enumeration;
@@ -59,6 +62,46 @@ pub struct Identifier {
     pub span: TextRange,
 }
 
+/// Normalize the source code of a type, so that equivalent types can be compared
+///
+/// The whitespace is removed (except between words), the aliases of elementary types are replaced with their full name
+/// (e.g. `uint` becomes `uint256`) and the qualified names of user-defined types are reduced to their last segment
+/// (e.g. `IFoo.Data` becomes `Data`).
+#[must_use]
+pub fn normalize_type(ty: &str) -> String {
+    let mut out = String::with_capacity(ty.len());
+    let mut word = String::new();
+    let mut space = false;
+    for c in ty.chars().chain(std::iter::once(' ')) {
+        if c.is_alphanumeric() || c == '_' || c == '$' || c == '.' {
+            if word.is_empty() && space && out.ends_with(|c: char| c.is_alphanumeric()) {
+                out.push(' ');
+            }
+            space = false;
+            word.push(c);
+            continue;
+        }
+        if !word.is_empty() {
+            let name = word.rsplit('.').next().unwrap_or_default();
+            out.push_str(match name {
+                "uint" => "uint256",
+                "int" => "int256",
+                "ufixed" => "ufixed128x18",
+                "fixed" => "fixed128x18",
+                name => name,
+            });
+            word.clear();
+        }
+        if c.is_whitespace() {
+            space = true;
+        } else {
+            space = false;
+            out.push(c);
+        }
+    }
+    out
+}
+
 /// The visibility modifier for a function-like item
 ///
 /// If no modifier is present, the default is `Internal`.
@@ -102,6 +145,7 @@ pub enum Definition {
     Modifier(ModifierDefinition),
     Struct(StructDefinition),
     Variable(VariableDeclaration),
+    Comment(CommentDefinition),
     NatspecParsingError(ErrorKind),
 }
 
@@ -123,6 +167,7 @@ impl PartialEq for Definition {
             (Self::Modifier(a), Self::Modifier(b)) => a.span.start == b.span.start,
             (Self::Struct(a), Self::Struct(b)) => a.span.start == b.span.start,
             (Self::Variable(a), Self::Variable(b)) => a.span.start == b.span.start,
+            (Self::Comment(a), Self::Comment(b)) => a.span.start == b.span.start,
             (
                 Self::NatspecParsingError(ErrorKind::NatspecParsingError { span: span_a, .. }),
                 Self::NatspecParsingError(ErrorKind::NatspecParsingError { span: span_b, .. }),
@@ -151,6 +196,7 @@ impl Definition {
             Definition::Modifier(d) => Some(d.span()),
             Definition::Struct(d) => Some(d.span()),
             Definition::Variable(d) => Some(d.span()),
+            Definition::Comment(d) => Some(d.span.clone()),
             Definition::NatspecParsingError(ErrorKind::NatspecParsingError { span, .. }) => {
                 Some(span.clone())
             }
@@ -158,6 +204,25 @@ impl Definition {
         }
     }
 
+    /// Retrieve the [`NatSpec`] of a definition, if any
+    #[must_use]
+    pub fn natspec(&self) -> Option<&NatSpec> {
+        match self {
+            Definition::Contract(d) => d.natspec.as_ref(),
+            Definition::Interface(d) => d.natspec.as_ref(),
+            Definition::Library(d) => d.natspec.as_ref(),
+            Definition::Constructor(d) => d.natspec.as_ref(),
+            Definition::Enumeration(d) => d.natspec.as_ref(),
+            Definition::Error(d) => d.natspec.as_ref(),
+            Definition::Event(d) => d.natspec.as_ref(),
+            Definition::Function(d) => d.natspec.as_ref(),
+            Definition::Modifier(d) => d.natspec.as_ref(),
+            Definition::Struct(d) => d.natspec.as_ref(),
+            Definition::Variable(d) => d.natspec.as_ref(),
+            Definition::Comment(_) | Definition::NatspecParsingError(_) => None,
+        }
+    }
+
     /// Mutably borrow the span of a definition
     pub fn span_mut(&mut self) -> Option<&mut TextRange> {
         match self {
@@ -172,6 +237,7 @@ impl Definition {
             Definition::Modifier(d) => Some(&mut d.span),
             Definition::Struct(d) => Some(&mut d.span),
             Definition::Variable(d) => Some(&mut d.span),
+            Definition::Comment(d) => Some(&mut d.span),
             Definition::NatspecParsingError(ErrorKind::NatspecParsingError { span, .. }) => {
                 Some(span)
             }
@@ -183,7 +249,7 @@ impl Definition {
 impl Validate for Definition {
     /// Validate a definition and generate [`Diagnostic`]s for errors
     fn validate(&self, options: &ValidationOptions) -> ItemDiagnostics {
-        match self {
+        let mut item_diags = match self {
             // if there was an error while parsing the NatSpec comments, a special diagnostic is generated
             Definition::NatspecParsingError(error) => {
                 let (parent, span, message) = match error {
@@ -199,7 +265,7 @@ impl Validate for Definition {
                     item_type: ItemType::ParsingError,
                     name: "",
                     span: span.clone(),
-                    diags: vec![Diagnostic { span, message }],
+                    diags: vec![Diagnostic::new(Rule::ParsingError, span, message)],
                 }
             }
             Definition::Contract(def) => def.validate(options),
@@ -213,7 +279,14 @@ impl Validate for Definition {
             Definition::Modifier(def) => def.validate(options),
             Definition::Struct(def) => def.validate(options),
             Definition::Variable(def) => def.validate(options),
-        }
+            Definition::Comment(def) => def.validate(options),
+        };
+        CheckUnknownTags::builder()
+            .maybe_natspec(self.natspec())
+            .span(&item_diags.span)
+            .build()
+            .check_into(&mut item_diags.diags);
+        item_diags
     }
 }
 
@@ -229,7 +302,20 @@ pub enum ContractType {
 }
 
 /// A type of source item (function, struct, etc.)
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Display, FromStr)]
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    Display,
+    FromStr,
+)]
 #[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
 #[serde(rename_all = "snake_case")]
 #[display(rename_all = "snake_case")]
@@ -252,6 +338,9 @@ pub enum ItemType {
     ExternalFunction,
     Modifier,
     ParsingError,
+    /// A stray comment, which can't be selected in the CLI or the configuration since it's not a source item
+    #[cfg_attr(feature = "clap", value(skip))]
+    Comment,
     Struct,
     #[display("variable")]
     PrivateVariable,
@@ -267,6 +356,22 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn test_normalize_type() {
+        assert_eq!(normalize_type("uint"), "uint256");
+        assert_eq!(normalize_type("uint[ ] "), "uint256[]");
+        assert_eq!(normalize_type("address  payable"), "address payable");
+        assert_eq!(normalize_type("IFoo.Data[2]"), "Data[2]");
+        assert_eq!(
+            normalize_type("mapping(uint => Lib.Foo)"),
+            "mapping(uint256=>Foo)"
+        );
+        assert_eq!(
+            normalize_type("function (int) external returns (bool)"),
+            "function(int256)external returns(bool)"
+        );
+    }
+
     #[test]
     fn test_contract_type_display() {
         assert_eq!(ContractType::Contract.to_string(), "contract");
@@ -313,6 +418,25 @@ mod tests {
         assert!(ContractType::from_str("").is_err());
     }
 
+    #[test]
+    fn test_item_type_comment_not_selectable() {
+        // the type itself round-trips, e.g. in the cache
+        assert_eq!(
+            serde_json::to_string(&ItemType::Comment).unwrap(),
+            r#""comment""#
+        );
+        assert_eq!(
+            serde_json::from_str::<ItemType>(r#""comment""#).unwrap(),
+            ItemType::Comment
+        );
+        #[cfg(feature = "clap")]
+        {
+            use clap::ValueEnum as _;
+            assert!(ItemType::Comment.to_possible_value().is_none());
+            assert!(<ItemType as clap::ValueEnum>::from_str("comment", false).is_err());
+        }
+    }
+
     #[test]
     fn test_item_type_display() {
         assert_eq!(ItemType::Contract.to_string(), "contract");
@@ -324,6 +448,7 @@ mod tests {
         assert_eq!(ItemType::Event.to_string(), "event");
         assert_eq!(ItemType::Modifier.to_string(), "modifier");
         assert_eq!(ItemType::ParsingError.to_string(), "parsing_error");
+        assert_eq!(ItemType::Comment.to_string(), "comment");
         assert_eq!(ItemType::Struct.to_string(), "struct");
         assert_eq!(ItemType::PrivateFunction.to_string(), "function");
         assert_eq!(ItemType::InternalFunction.to_string(), "function");
@@ -354,6 +479,7 @@ mod tests {
             ItemType::from_str("parsing-error").unwrap(),
             ItemType::ParsingError
         );
+        assert_eq!(ItemType::from_str("comment").unwrap(), ItemType::Comment);
         assert_eq!(ItemType::from_str("struct").unwrap(), ItemType::Struct);
         assert_eq!(
             ItemType::from_str("private-function").unwrap(),
@@ -0,0 +1,224 @@
+//! Comments which are not part of a source item's documentation.
+use crate::lint::{Diagnostic, ItemDiagnostics, Rule};
+
+use super::{ItemType, Parent, TextRange, Validate, ValidationOptions};
+
+/// The reason why a comment is reported
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StrayKind {
+    /// A doc-comment (`///` or `/** */`) which is not attached to any source item
+    Orphan,
+
+    /// A doc-comment which is attached to a source item although an empty line separates them
+    Detached,
+
+    /// A regular comment (`//` or `/* */`) which contains `NatSpec` tags, ignored by the compiler
+    Regular,
+}
+
+/// A comment which looks like documentation but is not part of the [`NatSpec`][crate::natspec::NatSpec] of any
+/// source item
+///
+/// Consecutive single-line comments are reported as a single comment. A comment has no name, the diagnostics refer to
+/// the contract, interface or library containing it (if any).
+#[derive(Debug, Clone, bon::Builder)]
+#[non_exhaustive]
+pub struct CommentDefinition {
+    /// The parent contract, interface or library (if any)
+    pub parent: Option<Parent>,
+
+    /// The span of the comment
+    pub span: TextRange,
+
+    /// The reason why the comment is reported
+    pub kind: StrayKind,
+}
+
+impl Validate for CommentDefinition {
+    fn validate(&self, options: &ValidationOptions) -> ItemDiagnostics {
+        let mut out = ItemDiagnostics {
+            parent: self.parent.clone(),
+            item_type: ItemType::Comment,
+            name: "",
+            span: self.span.clone(),
+            diags: vec![],
+        };
+        if !options.stray_comments {
+            return out;
+        }
+        let (rule, message) = match self.kind {
+            StrayKind::Orphan => (
+                Rule::OrphanComment,
+                "this doc-comment is not attached to any item",
+            ),
+            StrayKind::Detached => (
+                Rule::OrphanComment,
+                "this doc-comment is separated from the next item by an empty line",
+            ),
+            StrayKind::Regular => (
+                Rule::TagInComment,
+                "NatSpec tags in a regular comment are ignored, use `///` or `/** */`",
+            ),
+        };
+        out.diags
+            .push(Diagnostic::new(rule, self.span.clone(), message));
+        out
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "solar")]
+mod tests {
+    use similar_asserts::assert_eq;
+
+    use crate::{
+        definitions::Definition,
+        lint::Rule,
+        parser::{Parse as _, solar::SolarParser},
+    };
+
+    use super::*;
+
+    fn parse_file(contents: &str) -> Vec<CommentDefinition> {
+        let mut parser = SolarParser::default();
+        let doc = parser
+            .parse_document(contents.as_bytes(), None::<std::path::PathBuf>, false)
+            .unwrap();
+        doc.definitions
+            .into_iter()
+            .filter_map(Definition::to_comment)
+            .collect()
+    }
+
+    #[test]
+    fn test_attached() {
+        let contents = "/// @notice A contract
+        contract Test {
+            /// @notice A function
+            /// @dev With details
+            function foo() internal {
+                /// @solidity memory-safe-assembly
+                assembly {}
+            }
+
+            /**
+             * @notice A receive function
+             */
+            receive() external payable {}
+
+            // just a comment
+            uint256 internal a;
+        }";
+        let res = parse_file(contents);
+        assert!(res.is_empty(), "{res:#?}");
+    }
+
+    #[test]
+    fn test_orphan() {
+        let contents = "/// @notice Floating
+
+        /// @notice A contract
+        contract Test {
+            /// @notice The using directive
+            using Lib for uint256;
+
+            /// @notice Not attached
+            /**
+             * @notice A function
+             */
+            function foo() internal {}
+
+            /// @notice At the end
+            /// @dev of the contract
+        }";
+        let res = parse_file(contents);
+        assert_eq!(res.len(), 4);
+        assert!(res.iter().all(|c| c.kind == StrayKind::Orphan));
+        assert_eq!(res[0].span.start.line, 0);
+        assert_eq!(res[1].span.start.line, 4);
+        assert_eq!(res[2].span.start.line, 7);
+        // consecutive single-line comments are reported together
+        assert_eq!(res[3].span.start.line, 13);
+        assert_eq!(res[3].span.end.line, 14);
+        // the comments refer to their parent since they have no name
+        assert_eq!(res[0].parent, None);
+        assert_eq!(res[1].parent, Some(Parent::Contract("Test")));
+        let options = ValidationOptions::builder().stray_comments(true).build();
+        let diags = res[0].validate(&options).diags;
+        assert_eq!(diags.len(), 1);
+        assert_eq!(diags[0].rule, Rule::OrphanComment);
+        assert_eq!(
+            diags[0].message,
+            "this doc-comment is not attached to any item"
+        );
+    }
+
+    #[test]
+    fn test_detached() {
+        let contents = "contract Test {
+            /// @notice Separated by an empty line
+            /// @dev Over two lines
+
+            uint256 public z;
+
+            /**
+             * @notice A block comment
+             */
+
+            function foo() external {}
+
+            /// @notice Not separated
+            // lintspec-disable-next-line
+            function bar() external {}
+        }";
+        let res = parse_file(contents);
+        assert_eq!(res.len(), 2);
+        assert!(res.iter().all(|c| c.kind == StrayKind::Detached));
+        assert_eq!(res[0].span.start.line, 1);
+        assert_eq!(res[0].span.end.line, 2);
+        assert_eq!(res[1].span.start.line, 6);
+        let options = ValidationOptions::builder().stray_comments(true).build();
+        let diags = res[0].validate(&options).diags;
+        assert_eq!(diags.len(), 1);
+        assert_eq!(diags[0].rule, Rule::OrphanComment);
+        assert_eq!(
+            diags[0].message,
+            "this doc-comment is separated from the next item by an empty line"
+        );
+    }
+
+    #[test]
+    fn test_regular() {
+        let contents = "contract Test {
+            // @notice A regular comment
+            // @param a The param
+            /* @dev Another one */
+            //// @dev Too many slashes
+            function foo(uint256 a) internal {
+                // @dev inside of the body
+            }
+
+            // See the @notice of the interface
+            function bar() internal {}
+        }";
+        let res = parse_file(contents);
+        assert_eq!(res.len(), 3);
+        assert!(res.iter().all(|c| c.kind == StrayKind::Regular));
+        assert_eq!(res[0].span.start.line, 1);
+        assert_eq!(res[0].span.end.line, 2);
+        assert_eq!(res[1].span.start.line, 3);
+        assert_eq!(res[2].span.start.line, 4);
+        let options = ValidationOptions::builder().stray_comments(true).build();
+        let diags = res[0].validate(&options).diags;
+        assert_eq!(diags.len(), 1);
+        assert_eq!(diags[0].rule, Rule::TagInComment);
+
+        // the diagnostics are disabled by default
+        assert!(
+            res[0]
+                .validate(&ValidationOptions::default())
+                .diags
+                .is_empty()
+        );
+    }
+}
@@ -1,7 +1,7 @@
 //! Parsing and validation of constructors.
 use crate::{
     interner::{INTERNER, Symbol},
-    lint::{CheckNoticeAndDev, CheckParams, ItemDiagnostics},
+    lint::{CheckCustom, CheckNoticeAndDev, CheckParams, ItemDiagnostics},
     natspec::NatSpec,
 };
 
@@ -22,6 +22,10 @@ pub struct ConstructorDefinition {
 
     /// The [`NatSpec`] associated with the constructor, if any
     pub natspec: Option<NatSpec>,
+
+    /// The paths of the errors used by the `revert` statements of the constructor's body
+    #[builder(default)]
+    pub reverts: Vec<String>,
 }
 
 impl SourceItem for ConstructorDefinition {
@@ -67,6 +71,13 @@ impl Validate for ConstructorDefinition {
             .default_span(self.span())
             .build()
             .check_into(&mut out.diags);
+        CheckCustom::builder()
+            .natspec(&self.natspec)
+            .rules(&opts.custom)
+            .known(&options.known_custom_tags)
+            .span(&self.span)
+            .build()
+            .check_into(&mut out.diags);
         out
     }
 }
@@ -207,6 +218,7 @@ mod tests {
                 notice: Req::Required,
                 dev: Req::default(),
                 param: Req::default(),
+                ..Default::default()
             })
             .build();
         let contents = "contract Test {
@@ -1,7 +1,7 @@
 //! Parsing and validation of contract definitions.
 use crate::{
     interner::{INTERNER, Symbol},
-    lint::{CheckAuthor, CheckNoticeAndDev, CheckTitle, ItemDiagnostics},
+    lint::{CheckAuthor, CheckCustom, CheckNoticeAndDev, CheckTitle, ItemDiagnostics},
     natspec::NatSpec,
 };
 
@@ -18,6 +18,10 @@ pub struct ContractDefinition {
     /// The span of the contract definition
     pub span: TextRange,
 
+    /// The names of the direct bases of the contract (inheritance list)
+    #[builder(default)]
+    pub bases: Vec<Symbol>,
+
     /// The [`NatSpec`] associated with the contract definition, if any
     pub natspec: Option<NatSpec>,
 }
@@ -70,6 +74,13 @@ impl Validate for ContractDefinition {
             .span(&self.span)
             .build()
             .check_into(&mut out.diags);
+        CheckCustom::builder()
+            .natspec(&self.natspec)
+            .rules(&opts.custom)
+            .known(&options.known_custom_tags)
+            .span(&self.span)
+            .build()
+            .check_into(&mut out.diags);
         out
     }
 }
@@ -84,6 +95,7 @@ mod tests {
     use crate::{
         config::{ContractRules, Req},
         definitions::Definition,
+        lint::Rule,
         parser::{Parse as _, solar::SolarParser},
     };
 
@@ -145,6 +157,37 @@ mod tests {
         assert!(res.diags.is_empty(), "{:#?}", res.diags);
     }
 
+    #[test]
+    fn test_contract_wrapped_dev() {
+        // the untagged lines continue the `@dev` description and don't count as `@notice`
+        let contents = "/// @title Contract
+        /// @author Me
+        /// @dev This is a long description
+        ///      which is wrapped.
+        contract Test {}";
+        let contract = parse_file(contents);
+        let natspec = contract.natspec.as_ref().unwrap();
+        assert_eq!(natspec.items.len(), 3);
+        assert_eq!(
+            natspec.items[2].comment,
+            "This is a long description\nwhich is wrapped."
+        );
+        let res = contract.validate(&OPTIONS);
+        assert_eq!(res.diags.len(), 1);
+        assert_eq!(res.diags[0].message, "@notice is missing");
+
+        let contents = "/**
+         * @title Contract
+         * @author Me
+         * @dev This is a long description
+         * which is wrapped.
+         */
+        contract Test {}";
+        let res = parse_file(contents).validate(&OPTIONS);
+        assert_eq!(res.diags.len(), 1);
+        assert_eq!(res.diags[0].message, "@notice is missing");
+    }
+
     #[test]
     fn test_contract_inheritdoc() {
         // inheritdoc should be ignored as it doesn't apply to contracts
@@ -158,4 +201,51 @@ mod tests {
         assert_eq!(res.diags.len(), 1);
         assert_eq!(res.diags[0].message, "@title is missing");
     }
+
+    #[test]
+    fn test_contract_custom() {
+        let options = ValidationOptions::builder()
+            .known_custom_tags(vec!["oz-upgrades-unsafe-allow".to_string()])
+            .contracts(
+                ContractRules::builder()
+                    .custom(
+                        [
+                            ("security-contact".to_string(), Req::Required),
+                            ("experimental".to_string(), Req::Forbidden),
+                        ]
+                        .into(),
+                    )
+                    .build(),
+            )
+            .build();
+        let contents = "/// @custom:security-contact security@example.com
+        /// @custom:oz-upgrades-unsafe-allow constructor
+        contract Test {}";
+        let res = parse_file(contents).validate(&options);
+        assert!(res.diags.is_empty(), "{:#?}", res.diags);
+
+        let contents = "/// @custom:experimental
+        /// @custom:oz-upgrade-unsafe-allow constructor
+        contract Test {}";
+        let res = parse_file(contents).validate(&options);
+        assert_eq!(res.diags.len(), 3);
+        assert_eq!(res.diags[0].rule, Rule::MissingCustom);
+        assert_eq!(res.diags[0].message, "@custom:security-contact is missing");
+        assert_eq!(res.diags[1].rule, Rule::ForbiddenCustom);
+        assert_eq!(res.diags[1].message, "@custom:experimental is forbidden");
+        assert_eq!(res.diags[2].rule, Rule::UnknownCustom);
+        assert_eq!(
+            res.diags[2].message,
+            "@custom:oz-upgrade-unsafe-allow is not a known custom tag"
+        );
+        // the diagnostics point to the offending tag
+        let span = &res.diags[2].span;
+        assert!(
+            contents[span.start.utf8..span.end.utf8].starts_with("@custom:oz-upgrade-unsafe-allow")
+        );
+
+        // all custom tags are allowed if the list of known tags is empty
+        let res = parse_file(contents).validate(&ValidationOptions::default());
+        assert!(res.diags.is_empty(), "{:#?}", res.diags);
+    }
 }
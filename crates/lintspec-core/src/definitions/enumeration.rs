@@ -1,7 +1,7 @@
 //! Parsing and validation of enum definitions.
 use crate::{
     interner::{INTERNER, Symbol},
-    lint::{CheckNoticeAndDev, CheckParams, ItemDiagnostics},
+    lint::{CheckCustom, CheckNoticeAndDev, CheckParams, ItemDiagnostics},
     natspec::NatSpec,
 };
 
@@ -71,6 +71,13 @@ impl Validate for EnumDefinition {
             .default_span(self.span())
             .build()
             .check_into(&mut out.diags);
+        CheckCustom::builder()
+            .natspec(&self.natspec)
+            .rules(&opts.custom)
+            .known(&options.known_custom_tags)
+            .span(&self.span)
+            .build()
+            .check_into(&mut out.diags);
         out
     }
 }
@@ -239,6 +246,7 @@ mod tests {
                 notice: Req::Required,
                 dev: Req::default(),
                 param: Req::default(),
+                ..Default::default()
             })
             .build();
         let contents = "contract Test {
@@ -1,7 +1,10 @@
 //! Parsing and validation of function definitions.
 use crate::{
     interner::{INTERNER, Symbol},
-    lint::{CheckNoticeAndDev, CheckParams, CheckReturns, Diagnostic, ItemDiagnostics},
+    lint::{
+        CheckCustom, CheckNoticeAndDev, CheckParams, CheckReturns, Diagnostic, ItemDiagnostics,
+        Rule,
+    },
     natspec::{NatSpec, NatSpecKind},
 };
 
@@ -27,6 +30,10 @@ pub struct FunctionDefinition {
     /// The name and span of the function's parameters
     pub params: Vec<Identifier>,
 
+    /// The normalized types of the function's parameters (see [`normalize_type`][super::normalize_type])
+    #[builder(default)]
+    pub param_types: Vec<String>,
+
     /// The name and span of the function's returns
     pub returns: Vec<Identifier>,
 
@@ -35,6 +42,14 @@ pub struct FunctionDefinition {
 
     /// The attributes of the function (visibility and override)
     pub attributes: Attributes,
+
+    /// The names of the bases listed in the `override(...)` specifier of the function, if any
+    #[builder(default)]
+    pub overrides: Vec<Symbol>,
+
+    /// The paths of the errors used by the `revert` statements of the function's body (e.g. `Errors.Unauthorized`)
+    #[builder(default)]
+    pub reverts: Vec<String>,
 }
 
 impl FunctionDefinition {
@@ -93,10 +108,10 @@ impl Validate for FunctionDefinition {
             return out;
         }
         let opts = match self.attributes.visibility {
-            Visibility::External => options.functions.external,
-            Visibility::Internal => options.functions.internal,
-            Visibility::Private => options.functions.private,
-            Visibility::Public => options.functions.public,
+            Visibility::External => &options.functions.external,
+            Visibility::Internal => &options.functions.internal,
+            Visibility::Private => &options.functions.private,
+            Visibility::Public => &options.functions.public,
         };
         if let Some(natspec) = &self.natspec
             && natspec
@@ -107,10 +122,11 @@ impl Validate for FunctionDefinition {
             // if there is `inheritdoc`, no further validation is required
             return out;
         } else if self.requires_inheritdoc(options) {
-            out.diags.push(Diagnostic {
-                span: self.span(),
-                message: "@inheritdoc is missing".to_string(),
-            });
+            out.diags.push(Diagnostic::new(
+                Rule::MissingInheritdoc,
+                self.span(),
+                "@inheritdoc is missing",
+            ));
             return out;
         }
         CheckNoticeAndDev::builder()
@@ -136,6 +152,13 @@ impl Validate for FunctionDefinition {
             .is_var(false)
             .build()
             .check_into(&mut out.diags);
+        CheckCustom::builder()
+            .natspec(&self.natspec)
+            .rules(&opts.custom)
+            .known(&options.known_custom_tags)
+            .span(&self.span)
+            .build()
+            .check_into(&mut out.diags);
         out
     }
 }
@@ -197,6 +220,17 @@ mod tests {
             "@return missing for unnamed return #1"
         );
         assert_eq!(res.diags[4].message, "@return out is missing");
+        let params: Vec<_> = res.diags.iter().map(|d| d.param.as_deref()).collect();
+        assert_eq!(
+            params,
+            vec![
+                None,
+                Some("param1"),
+                Some("param2"),
+                Some("#1"),
+                Some("out")
+            ]
+        );
     }
 
     #[test]
@@ -287,6 +321,24 @@ mod tests {
         assert_eq!(res.diags[0].message, "too many unnamed returns");
     }
 
+    #[test]
+    fn test_function_wrapped_param() {
+        let contents = "contract Test {
+            /// @notice A function
+            /// @param param1 The first
+            ///        param
+            /// @param param2 Extra
+            function foo(uint256 param1) public { }
+        }";
+        let res = parse_file(contents).validate(&OPTIONS);
+        assert_eq!(res.diags.len(), 1);
+        assert_eq!(res.diags[0].message, "extra @param param2");
+        assert_eq!(res.diags[0].span.start.line, 4);
+        assert_eq!(res.diags[0].span.start.col_utf8, 16);
+        assert_eq!(res.diags[0].span.end.line, 4);
+        assert_eq!(res.diags[0].span.end.col_utf8, 35);
+    }
+
     #[test]
     fn test_function_no_params() {
         let contents = "contract Test {
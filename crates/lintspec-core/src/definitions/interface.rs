@@ -1,7 +1,7 @@
 //! Parsing and validation of interface definitions.
 use crate::{
     interner::{INTERNER, Symbol},
-    lint::{CheckAuthor, CheckNoticeAndDev, CheckTitle, ItemDiagnostics},
+    lint::{CheckAuthor, CheckCustom, CheckNoticeAndDev, CheckTitle, ItemDiagnostics},
     natspec::NatSpec,
 };
 
@@ -18,6 +18,10 @@ pub struct InterfaceDefinition {
     /// The span of the interface definition
     pub span: TextRange,
 
+    /// The names of the direct bases of the interface (inheritance list)
+    #[builder(default)]
+    pub bases: Vec<Symbol>,
+
     /// The [`NatSpec`] associated with the interface definition, if any
     pub natspec: Option<NatSpec>,
 }
@@ -70,6 +74,13 @@ impl Validate for InterfaceDefinition {
             .span(&self.span)
             .build()
             .check_into(&mut out.diags);
+        CheckCustom::builder()
+            .natspec(&self.natspec)
+            .rules(&opts.custom)
+            .known(&options.known_custom_tags)
+            .span(&self.span)
+            .build()
+            .check_into(&mut out.diags);
         out
     }
 }
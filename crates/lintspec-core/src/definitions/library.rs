@@ -1,7 +1,7 @@
 //! Parsing and validation of library definitions.
 use crate::{
     interner::{INTERNER, Symbol},
-    lint::{CheckAuthor, CheckNoticeAndDev, CheckTitle, ItemDiagnostics},
+    lint::{CheckAuthor, CheckCustom, CheckNoticeAndDev, CheckTitle, ItemDiagnostics},
     natspec::NatSpec,
 };
 
@@ -70,6 +70,13 @@ impl Validate for LibraryDefinition {
             .span(&self.span)
             .build()
             .check_into(&mut out.diags);
+        CheckCustom::builder()
+            .natspec(&self.natspec)
+            .rules(&opts.custom)
+            .known(&options.known_custom_tags)
+            .span(&self.span)
+            .build()
+            .check_into(&mut out.diags);
         out
     }
 }
@@ -1,7 +1,7 @@
 //! Parsing and validation of modifier definitions.
 use crate::{
     interner::{INTERNER, Symbol},
-    lint::{CheckNoticeAndDev, CheckParams, Diagnostic, ItemDiagnostics},
+    lint::{CheckCustom, CheckNoticeAndDev, CheckParams, Diagnostic, ItemDiagnostics, Rule},
     natspec::{NatSpec, NatSpecKind},
 };
 
@@ -31,6 +31,10 @@ pub struct ModifierDefinition {
 
     /// The attributes of the modifier (override)
     pub attributes: Attributes,
+
+    /// The paths of the errors used by the `revert` statements of the modifier's body
+    #[builder(default)]
+    pub reverts: Vec<String>,
 }
 
 impl ModifierDefinition {
@@ -80,10 +84,11 @@ impl Validate for ModifierDefinition {
             // if there is `inheritdoc`, no further validation is required
             return out;
         } else if self.requires_inheritdoc(options) {
-            out.diags.push(Diagnostic {
-                span: self.span(),
-                message: "@inheritdoc is missing".to_string(),
-            });
+            out.diags.push(Diagnostic::new(
+                Rule::MissingInheritdoc,
+                self.span(),
+                "@inheritdoc is missing",
+            ));
             return out;
         }
         CheckNoticeAndDev::builder()
@@ -101,6 +106,13 @@ impl Validate for ModifierDefinition {
             .default_span(self.span())
             .build()
             .check_into(&mut out.diags);
+        CheckCustom::builder()
+            .natspec(&self.natspec)
+            .rules(&opts.custom)
+            .known(&options.known_custom_tags)
+            .span(&self.span)
+            .build()
+            .check_into(&mut out.diags);
         out
     }
 }
@@ -1,7 +1,7 @@
 //! Parsing and validation of struct definitions.
 use crate::{
     interner::{INTERNER, Symbol},
-    lint::{CheckNoticeAndDev, CheckParams, ItemDiagnostics},
+    lint::{CheckCustom, CheckNoticeAndDev, CheckParams, ItemDiagnostics},
     natspec::NatSpec,
 };
 
@@ -24,6 +24,10 @@ pub struct StructDefinition {
     /// The name and span of the struct members
     pub members: Vec<Identifier>,
 
+    /// The normalized types of the struct members (see [`normalize_type`][super::normalize_type])
+    #[builder(default)]
+    pub member_types: Vec<String>,
+
     /// The [`NatSpec`] associated with the struct definition, if any
     pub natspec: Option<NatSpec>,
 }
@@ -71,6 +75,13 @@ impl Validate for StructDefinition {
             .default_span(self.span())
             .build()
             .check_into(&mut out.diags);
+        CheckCustom::builder()
+            .natspec(&self.natspec)
+            .rules(&opts.custom)
+            .known(&options.known_custom_tags)
+            .span(&self.span)
+            .build()
+            .check_into(&mut out.diags);
         out
     }
 }
@@ -1,7 +1,7 @@
 //! Parsing and validation of state variable declarations.
 use crate::{
     interner::{INTERNER, Symbol},
-    lint::{CheckNoticeAndDev, CheckReturns, Diagnostic, ItemDiagnostics},
+    lint::{CheckCustom, CheckNoticeAndDev, CheckReturns, Diagnostic, ItemDiagnostics, Rule},
     natspec::{NatSpec, NatSpecKind},
 };
 
@@ -24,11 +24,19 @@ pub struct VariableDeclaration {
     /// The span of the state variable declaration
     pub span: TextRange,
 
+    /// The normalized type of the state variable (see [`normalize_type`][super::normalize_type])
+    #[builder(default)]
+    pub type_name: String,
+
     /// The [`NatSpec`] associated with the state variable declaration, if any
     pub natspec: Option<NatSpec>,
 
     /// The attributes of the state variable (visibility)
     pub attributes: Attributes,
+
+    /// The names of the bases listed in the `override(...)` specifier of the state variable, if any
+    #[builder(default)]
+    pub overrides: Vec<Symbol>,
 }
 
 impl VariableDeclaration {
@@ -67,22 +75,25 @@ impl SourceItem for VariableDeclaration {
 
 impl Validate for VariableDeclaration {
     fn validate(&self, options: &ValidationOptions) -> ItemDiagnostics {
-        let (notice, dev, returns) = match self.attributes.visibility {
+        let (notice, dev, returns, custom) = match self.attributes.visibility {
             Visibility::External => unreachable!("variables cannot be external"),
             Visibility::Internal => (
                 options.variables.internal.notice,
                 options.variables.internal.dev,
                 None,
+                &options.variables.internal.custom,
             ),
             Visibility::Private => (
                 options.variables.private.notice,
                 options.variables.private.dev,
                 None,
+                &options.variables.private.custom,
             ),
             Visibility::Public => (
                 options.variables.public.notice,
                 options.variables.public.dev,
                 Some(options.variables.public.returns),
+                &options.variables.public.custom,
             ),
         };
         let mut out = ItemDiagnostics {
@@ -101,10 +112,11 @@ impl Validate for VariableDeclaration {
             // if there is `inheritdoc`, no further validation is required
             return out;
         } else if options.inheritdoc && self.requires_inheritdoc() {
-            out.diags.push(Diagnostic {
-                span: self.span(),
-                message: "@inheritdoc is missing".to_string(),
-            });
+            out.diags.push(Diagnostic::new(
+                Rule::MissingInheritdoc,
+                self.span(),
+                "@inheritdoc is missing",
+            ));
             return out;
         }
         CheckNoticeAndDev::builder()
@@ -128,6 +140,13 @@ impl Validate for VariableDeclaration {
                 .build()
                 .check_into(&mut out.diags);
         }
+        CheckCustom::builder()
+            .natspec(&self.natspec)
+            .rules(custom)
+            .known(&options.known_custom_tags)
+            .span(&self.span)
+            .build()
+            .check_into(&mut out.diags);
         out
     }
 }
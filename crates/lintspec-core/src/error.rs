@@ -46,6 +46,17 @@ pub enum ErrorKind {
     #[error("IO error for {path:?}: {err}")]
     IOError { path: PathBuf, err: std::io::Error },
 
+    /// The baseline file could not be parsed
+    #[error("invalid baseline file {path:?}: {err}")]
+    BaselineParsingError {
+        path: PathBuf,
+        err: serde_json::Error,
+    },
+
+    /// A git command failed
+    #[error("git error: {message}")]
+    GitError { message: String },
+
     /// An unspecified error happening during parsing
     #[error("unknown error while parsing Solidity")]
     UnknownError,
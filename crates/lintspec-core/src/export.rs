@@ -0,0 +1,1016 @@
+//! Export of the `NatSpec` as solc's userdoc and devdoc
+//!
+//! The documentation of each contract, interface and library is converted to the JSON schema of the `userdoc` and
+//! `devdoc` outputs of the compiler (version 1). The functions, events and errors are keyed by their signature, where
+//! the user-defined types declared in the exported files are replaced with their canonical ABI type (e.g. a struct
+//! becomes a tuple and an enum becomes `uint8`). Like the compiler, the public members of the bases are included and
+//! the tags which are missing from an item with `@inheritdoc` are copied from the base member. The errors also include
+//! the ones raised by the `revert` statements of the contract, which is why several errors can share a signature.
+use std::{
+    collections::{BTreeMap, HashMap, HashSet, VecDeque},
+    path::{Path, PathBuf},
+};
+
+use serde::Serialize;
+
+use crate::{
+    definitions::{Definition, Identifier, Parent, Visibility, error::ErrorDefinition},
+    interner::{INTERNER, Symbol},
+    natspec::{NatSpecItem, NatSpecKind},
+};
+
+/// Maximum depth when following `@inheritdoc` or expanding nested struct types
+const MAX_DEPTH: usize = 16;
+
+/// The user documentation of a contract
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[non_exhaustive]
+pub struct UserDoc {
+    /// Always `user`
+    pub kind: &'static str,
+
+    /// The version of the schema, always 1
+    pub version: u8,
+
+    /// The `@notice` of the contract
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notice: Option<String>,
+
+    /// The functions and public state variables, keyed by signature
+    pub methods: BTreeMap<String, UserDocEntry>,
+
+    /// The events, keyed by signature
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub events: BTreeMap<String, UserDocEntry>,
+
+    /// The errors, keyed by signature
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub errors: BTreeMap<String, Vec<UserDocEntry>>,
+}
+
+impl Default for UserDoc {
+    fn default() -> Self {
+        Self {
+            kind: "user",
+            version: 1,
+            notice: None,
+            methods: BTreeMap::new(),
+            events: BTreeMap::new(),
+            errors: BTreeMap::new(),
+        }
+    }
+}
+
+/// The user documentation of a member
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[non_exhaustive]
+pub struct UserDocEntry {
+    /// The `@notice` of the member
+    pub notice: String,
+}
+
+/// The developer documentation of a contract
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[non_exhaustive]
+pub struct DevDoc {
+    /// Always `dev`
+    pub kind: &'static str,
+
+    /// The version of the schema, always 1
+    pub version: u8,
+
+    /// The `@author` of the contract
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+
+    /// The `@dev` comment of the contract
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details: Option<String>,
+
+    /// The `@title` of the contract
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+
+    /// The `@custom:<tag>` comments of the contract, keyed by `custom:<tag>`
+    #[serde(flatten)]
+    pub custom: BTreeMap<String, String>,
+
+    /// The public and external functions, keyed by signature
+    pub methods: BTreeMap<String, DevDocEntry>,
+
+    /// The events, keyed by signature
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub events: BTreeMap<String, DevDocEntry>,
+
+    /// The errors, keyed by signature
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub errors: BTreeMap<String, Vec<DevDocEntry>>,
+
+    /// The state variables of the contract, keyed by name
+    #[serde(rename = "stateVariables", skip_serializing_if = "BTreeMap::is_empty")]
+    pub state_variables: BTreeMap<String, DevDocEntry>,
+}
+
+impl Default for DevDoc {
+    fn default() -> Self {
+        Self {
+            kind: "dev",
+            version: 1,
+            author: None,
+            details: None,
+            title: None,
+            custom: BTreeMap::new(),
+            methods: BTreeMap::new(),
+            events: BTreeMap::new(),
+            errors: BTreeMap::new(),
+            state_variables: BTreeMap::new(),
+        }
+    }
+}
+
+/// The developer documentation of a member
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[non_exhaustive]
+pub struct DevDocEntry {
+    /// The `@dev` comment of the member
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details: Option<String>,
+
+    /// The `@param` comments, keyed by param name
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub params: BTreeMap<String, String>,
+
+    /// The `@return` comment of a public state variable
+    #[serde(rename = "return", skip_serializing_if = "Option::is_none")]
+    pub r#return: Option<String>,
+
+    /// The `@return` comments, keyed by return name (`_<index>` for unnamed returns)
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub returns: BTreeMap<String, String>,
+
+    /// The `@custom:<tag>` comments, keyed by `custom:<tag>`
+    #[serde(flatten)]
+    pub custom: BTreeMap<String, String>,
+}
+
+impl DevDocEntry {
+    /// Check whether the entry has no documentation
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.details.is_none()
+            && self.params.is_empty()
+            && self.r#return.is_none()
+            && self.returns.is_empty()
+            && self.custom.is_empty()
+    }
+}
+
+/// The userdoc and devdoc of a contract, interface or library
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[non_exhaustive]
+pub struct ContractDocs {
+    pub userdoc: UserDoc,
+    pub devdoc: DevDoc,
+}
+
+/// The documentation of all the contracts in a set of files
+///
+/// Like the compiler's standard JSON output, the contracts are keyed by file path then by contract name.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[non_exhaustive]
+pub struct DocsExport {
+    pub contracts: BTreeMap<String, BTreeMap<String, ContractDocs>>,
+}
+
+impl DocsExport {
+    /// Export the documentation of the contracts defined in a list of files
+    ///
+    /// The paths are made relative to the root directory if possible. The inheritance and the `@inheritdoc` tags can
+    /// only be resolved for the contracts defined in these files.
+    #[must_use]
+    pub fn new(files: &[(PathBuf, Vec<Definition>)], root_path: impl AsRef<Path>) -> Self {
+        let root_path = root_path.as_ref();
+        let index = Index::new(files);
+        let mut export = Self::default();
+        for (path, definitions) in files {
+            let path = path
+                .strip_prefix(root_path)
+                .unwrap_or(path)
+                .to_string_lossy()
+                .replace('\\', "/");
+            for definition in definitions {
+                let Some(name) = contract_name(definition) else {
+                    continue;
+                };
+                export.contracts.entry(path.clone()).or_default().insert(
+                    name.resolve_with(&INTERNER).to_string(),
+                    index.contract_docs(definition, name),
+                );
+            }
+        }
+        export
+    }
+}
+
+/// The contracts and types declared in the exported files
+pub(crate) struct Index<'a> {
+    /// The bases of each contract
+    bases: HashMap<Symbol, &'a [Symbol]>,
+
+    /// The members of each contract, in order of declaration
+    members: HashMap<&'static str, Vec<&'a Definition>>,
+
+    /// The names of the contracts, interfaces and libraries
+    contracts: HashSet<&'static str>,
+
+    /// The names of the enums
+    enums: HashSet<&'static str>,
+
+    /// The normalized member types of each struct
+    structs: HashMap<&'static str, &'a [String]>,
+
+    /// All the errors, in order of declaration
+    errors: Vec<&'a ErrorDefinition>,
+}
+
+impl<'a> Index<'a> {
+    pub(crate) fn new(files: &'a [(PathBuf, Vec<Definition>)]) -> Self {
+        let mut index = Self {
+            bases: HashMap::new(),
+            members: HashMap::new(),
+            contracts: HashSet::new(),
+            enums: HashSet::new(),
+            structs: HashMap::new(),
+            errors: Vec::new(),
+        };
+        for definition in files.iter().flat_map(|(_, definitions)| definitions) {
+            match definition {
+                Definition::Contract(def) => {
+                    index.bases.insert(def.name, &def.bases);
+                }
+                Definition::Interface(def) => {
+                    index.bases.insert(def.name, &def.bases);
+                }
+                Definition::Enumeration(def) => {
+                    index.enums.insert(def.name.resolve_with(&INTERNER));
+                }
+                Definition::Struct(def) => {
+                    index
+                        .structs
+                        .insert(def.name.resolve_with(&INTERNER), &def.member_types);
+                }
+                Definition::Error(def) => {
+                    index.errors.push(def);
+                }
+                _ => {}
+            }
+            if let Some(name) = contract_name(definition) {
+                index.contracts.insert(name.resolve_with(&INTERNER));
+            }
+            if let Some(
+                Parent::Contract(parent) | Parent::Interface(parent) | Parent::Library(parent),
+            ) = parent(definition)
+            {
+                index.members.entry(parent).or_default().push(definition);
+            }
+        }
+        index
+    }
+
+    /// Compute the documentation of a contract
+    fn contract_docs(&self, definition: &Definition, name: Symbol) -> ContractDocs {
+        let mut docs = ContractDocs::default();
+        let items = definition
+            .natspec()
+            .map(|n| n.items.clone())
+            .unwrap_or_default();
+        docs.userdoc.notice = extract(&items, &NatSpecKind::Notice);
+        docs.devdoc.author = extract(&items, &NatSpecKind::Author);
+        docs.devdoc.details = extract(&items, &NatSpecKind::Dev);
+        docs.devdoc.title = extract(&items, &NatSpecKind::Title);
+        docs.devdoc.custom = extract_custom(&items);
+
+        let contract_name = name.resolve_with(&INTERNER);
+        for member in self.members.get(contract_name).into_iter().flatten() {
+            match member {
+                Definition::Constructor(def) => {
+                    let items = def
+                        .natspec
+                        .as_ref()
+                        .map(|n| n.items.clone())
+                        .unwrap_or_default();
+                    if let Some(notice) = extract(&items, &NatSpecKind::Notice) {
+                        docs.userdoc
+                            .methods
+                            .insert("constructor".to_string(), UserDocEntry { notice });
+                    }
+                    let entry = dev_entry(&items, &[]);
+                    if !entry.is_empty() {
+                        docs.devdoc.methods.insert("constructor".to_string(), entry);
+                    }
+                }
+                Definition::Variable(def) => {
+                    let items = self.resolved_items(member, 0);
+                    let mut entry = dev_entry(&items, &[]);
+                    entry.r#return = extract(&items, &NatSpecKind::Return { name: None });
+                    if def.attributes.visibility == Visibility::Public
+                        && let Some(comment) = &entry.r#return
+                    {
+                        entry.returns.insert("_0".to_string(), comment.clone());
+                    }
+                    if !entry.is_empty() {
+                        docs.devdoc
+                            .state_variables
+                            .insert(def.name.resolve_with(&INTERNER).to_string(), entry);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        // the members of the most derived contracts take precedence
+        for contract in self.linearize(name) {
+            let contract_name = contract.resolve_with(&INTERNER);
+            for member in self.members.get(contract_name).into_iter().flatten() {
+                self.add_member(&mut docs, member);
+            }
+        }
+        for error in self.interface_errors(name) {
+            self.add_error(&mut docs, error);
+        }
+        docs
+    }
+
+    /// Add the documentation of a function, public state variable or event, unless its signature was already
+    /// documented by a more derived contract
+    fn add_member(&self, docs: &mut ContractDocs, member: &Definition) {
+        match member {
+            Definition::Function(def)
+                if matches!(
+                    def.attributes.visibility,
+                    Visibility::External | Visibility::Public
+                ) =>
+            {
+                let signature = self.signature(def.name, &def.param_types);
+                let items = self.resolved_items(member, 0);
+                if !docs.userdoc.methods.contains_key(&signature)
+                    && let Some(notice) = extract(&items, &NatSpecKind::Notice)
+                {
+                    docs.userdoc
+                        .methods
+                        .insert(signature.clone(), UserDocEntry { notice });
+                }
+                let entry = dev_entry(&items, &def.returns);
+                if !entry.is_empty() {
+                    docs.devdoc.methods.entry(signature).or_insert(entry);
+                }
+            }
+            Definition::Variable(def) if def.attributes.visibility == Visibility::Public => {
+                let signature = self.signature(def.name, &getter_params(&def.type_name));
+                let items = self.resolved_items(member, 0);
+                if !docs.userdoc.methods.contains_key(&signature)
+                    && let Some(notice) = extract(&items, &NatSpecKind::Notice)
+                {
+                    docs.userdoc
+                        .methods
+                        .insert(signature, UserDocEntry { notice });
+                }
+            }
+            Definition::Event(def) => {
+                let signature = self.signature(def.name, &def.param_types);
+                let items = def
+                    .natspec
+                    .as_ref()
+                    .map(|n| n.items.clone())
+                    .unwrap_or_default();
+                if !docs.userdoc.events.contains_key(&signature)
+                    && let Some(notice) = extract(&items, &NatSpecKind::Notice)
+                {
+                    docs.userdoc
+                        .events
+                        .insert(signature.clone(), UserDocEntry { notice });
+                }
+                let entry = dev_entry(&items, &[]);
+                if !entry.is_empty() {
+                    docs.devdoc.events.entry(signature).or_insert(entry);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Add the documentation of an error
+    ///
+    /// Each error with a given signature has its own entry.
+    fn add_error(&self, docs: &mut ContractDocs, error: &ErrorDefinition) {
+        let signature = self.signature(error.name, &error.param_types);
+        let items = error
+            .natspec
+            .as_ref()
+            .map(|n| n.items.clone())
+            .unwrap_or_default();
+        if let Some(notice) = extract(&items, &NatSpecKind::Notice) {
+            docs.userdoc
+                .errors
+                .entry(signature.clone())
+                .or_default()
+                .push(UserDocEntry { notice });
+        }
+        let entry = dev_entry(&items, &[]);
+        if !entry.is_empty() {
+            docs.devdoc.errors.entry(signature).or_default().push(entry);
+        }
+    }
+
+    /// Find the errors of a contract, in order of declaration
+    ///
+    /// Like the compiler, these are the errors defined in the contract and its bases, and the errors raised by the
+    /// `revert` statements of their functions, modifiers and constructors.
+    fn interface_errors(&self, contract: Symbol) -> Vec<&'a ErrorDefinition> {
+        let scope = self.linearize(contract);
+        let mut used = Vec::new();
+        for base in &scope {
+            for member in self
+                .members
+                .get(base.resolve_with(&INTERNER))
+                .into_iter()
+                .flatten()
+            {
+                let reverts = match member {
+                    Definition::Error(def) => {
+                        used.push(def);
+                        continue;
+                    }
+                    Definition::Function(def) => &def.reverts,
+                    Definition::Modifier(def) => &def.reverts,
+                    Definition::Constructor(def) => &def.reverts,
+                    _ => continue,
+                };
+                used.extend(reverts.iter().filter_map(|p| self.resolve_error(p, &scope)));
+            }
+        }
+        self.errors
+            .iter()
+            .copied()
+            .filter(|e| used.iter().any(|u| std::ptr::eq(*u, *e)))
+            .collect()
+    }
+
+    /// Find the error referenced by the path of a `revert` statement
+    ///
+    /// A qualified path (e.g. `Errors.Unauthorized`) refers to a member of the named contract. Otherwise, the error is
+    /// looked up in the contract and its bases, then among the free errors.
+    fn resolve_error(&self, path: &str, scope: &[Symbol]) -> Option<&'a ErrorDefinition> {
+        let find_in = |contract: &str, name: &str| {
+            self.members.get(contract)?.iter().find_map(|m| match m {
+                Definition::Error(def) if def.name.resolve_with(&INTERNER) == name => Some(def),
+                _ => None,
+            })
+        };
+        if let Some((container, name)) = path.rsplit_once('.') {
+            let container = container.rsplit('.').next().unwrap_or(container);
+            return find_in(container, name);
+        }
+        scope
+            .iter()
+            .find_map(|contract| find_in(contract.resolve_with(&INTERNER), path))
+            .or_else(|| {
+                self.errors
+                    .iter()
+                    .copied()
+                    .find(|e| e.parent.is_none() && e.name.resolve_with(&INTERNER) == path)
+            })
+    }
+
+    /// Retrieve the `NatSpec` items of a function or state variable, completed with the tags of the base member
+    /// referenced by `@inheritdoc`
+    ///
+    /// Like the compiler, a tag which appears at least once in the item is not copied, and custom tags are never
+    /// copied.
+    pub(crate) fn resolved_items(&self, definition: &Definition, depth: usize) -> Vec<NatSpecItem> {
+        let Some(natspec) = definition.natspec() else {
+            return Vec::new();
+        };
+        let mut items = natspec.items.clone();
+        let Some(base) = items.iter().find_map(|i| match &i.kind {
+            NatSpecKind::Inheritdoc { parent } => Some(*parent),
+            _ => None,
+        }) else {
+            return items;
+        };
+        if depth >= MAX_DEPTH {
+            return items;
+        }
+        let Some(base_member) = self.base_member(base, definition) else {
+            return items;
+        };
+        let base_items = self.resolved_items(base_member, depth + 1);
+        let own_kinds: Vec<_> = items
+            .iter()
+            .map(|i| std::mem::discriminant(&i.kind))
+            .collect();
+        items.extend(base_items.into_iter().filter(|i| {
+            !matches!(
+                i.kind,
+                NatSpecKind::Inheritdoc { .. }
+                    | NatSpecKind::Custom { .. }
+                    | NatSpecKind::Unknown { .. }
+            ) && !own_kinds.contains(&std::mem::discriminant(&i.kind))
+        }));
+        items
+    }
+
+    /// Find the member of a base which matches a function or state variable
+    fn base_member(&self, base: Symbol, definition: &Definition) -> Option<&'a Definition> {
+        let (name, param_types) = match definition {
+            Definition::Function(def) => (def.name, def.param_types.as_slice()),
+            Definition::Variable(def) => (def.name, &[][..]),
+            _ => return None,
+        };
+        self.members
+            .get(base.resolve_with(&INTERNER))?
+            .iter()
+            .copied()
+            .find(|m| match m {
+                Definition::Function(def) => def.name == name && def.param_types == param_types,
+                Definition::Variable(def) => def.name == name && param_types.is_empty(),
+                _ => false,
+            })
+    }
+
+    /// Compute the list of a contract and its bases, from the most derived to the most base
+    ///
+    /// This approximates the C3 linearization by visiting the bases breadth-first, from right to left.
+    fn linearize(&self, contract: Symbol) -> Vec<Symbol> {
+        let mut order = vec![contract];
+        let mut visited = HashSet::from([contract]);
+        let mut queue = VecDeque::from([contract]);
+        while let Some(current) = queue.pop_front() {
+            let Some(bases) = self.bases.get(&current) else {
+                continue;
+            };
+            for base in bases.iter().rev() {
+                if visited.insert(*base) {
+                    order.push(*base);
+                    queue.push_back(*base);
+                }
+            }
+        }
+        order
+    }
+
+    /// Compute the signature of a function, event or error from its name and normalized param types
+    fn signature(&self, name: Symbol, param_types: &[String]) -> String {
+        let params: Vec<_> = param_types
+            .iter()
+            .map(|ty| self.canonical_type(ty, 0))
+            .collect();
+        format!("{}({})", name.resolve_with(&INTERNER), params.join(","))
+    }
+
+    /// Convert a normalized type to its canonical ABI type
+    ///
+    /// Contracts become `address`, enums become `uint8` and structs become tuples.
+    /// Other user-defined types are kept as is.
+    fn canonical_type(&self, ty: &str, depth: usize) -> String {
+        if ty.starts_with("function") {
+            return "function".to_string();
+        }
+        let (base, suffix) = ty.split_at(ty.find('[').unwrap_or(ty.len()));
+        let base = match base {
+            "address payable" => "address".to_string(),
+            base if self.contracts.contains(base) => "address".to_string(),
+            base if self.enums.contains(base) => "uint8".to_string(),
+            base if depth < MAX_DEPTH
+                && let Some(members) = self.structs.get(base) =>
+            {
+                let members: Vec<_> = members
+                    .iter()
+                    .map(|m| self.canonical_type(m, depth + 1))
+                    .collect();
+                format!("({})", members.join(","))
+            }
+            base => base.to_string(),
+        };
+        format!("{base}{suffix}")
+    }
+}
+
+/// Retrieve the name of a contract, interface or library definition
+pub(crate) fn contract_name(definition: &Definition) -> Option<Symbol> {
+    match definition {
+        Definition::Contract(def) => Some(def.name),
+        Definition::Interface(def) => Some(def.name),
+        Definition::Library(def) => Some(def.name),
+        _ => None,
+    }
+}
+
+/// Retrieve the parent of a contract member
+pub(crate) fn parent(definition: &Definition) -> Option<Parent> {
+    match definition {
+        Definition::Constructor(def) => def.parent.clone(),
+        Definition::Enumeration(def) => def.parent.clone(),
+        Definition::Error(def) => def.parent.clone(),
+        Definition::Event(def) => def.parent.clone(),
+        Definition::Function(def) => def.parent.clone(),
+        Definition::Modifier(def) => def.parent.clone(),
+        Definition::Struct(def) => def.parent.clone(),
+        Definition::Variable(def) => def.parent.clone(),
+        Definition::Contract(_)
+        | Definition::Interface(_)
+        | Definition::Library(_)
+        | Definition::Comment(_)
+        | Definition::NatspecParsingError(_) => None,
+    }
+}
+
+/// Concatenate the comments of all the items of a kind, like the compiler does
+///
+/// The return items match regardless of their name.
+fn extract(items: &[NatSpecItem], kind: &NatSpecKind) -> Option<String> {
+    let comments: Vec<_> = items
+        .iter()
+        .filter(|i| match kind {
+            NatSpecKind::Return { .. } => i.kind.is_return(),
+            kind => &i.kind == kind,
+        })
+        .map(|i| i.comment.as_str())
+        .collect();
+    (!comments.is_empty()).then(|| comments.concat())
+}
+
+/// Extract the `@custom:<tag>` comments, keyed by `custom:<tag>`
+fn extract_custom(items: &[NatSpecItem]) -> BTreeMap<String, String> {
+    let mut custom = BTreeMap::<String, String>::new();
+    for item in items {
+        if let NatSpecKind::Custom { tag } = &item.kind {
+            custom
+                .entry(format!("custom:{}", tag.resolve_with(&INTERNER)))
+                .or_default()
+                .push_str(&item.comment);
+        }
+    }
+    custom
+}
+
+/// Build the developer documentation of a member
+///
+/// The `@return` comments are matched with the returns by position, and keyed by the return name or `_<index>` for
+/// unnamed returns.
+fn dev_entry(items: &[NatSpecItem], returns: &[Identifier]) -> DevDocEntry {
+    let mut entry = DevDocEntry {
+        details: extract(items, &NatSpecKind::Dev),
+        custom: extract_custom(items),
+        ..Default::default()
+    };
+    for item in items {
+        if let NatSpecKind::Param { name } = &item.kind {
+            entry.params.insert(
+                name.resolve_with(&INTERNER).to_string(),
+                item.comment.clone(),
+            );
+        }
+    }
+    for (i, item) in items.iter().filter(|i| i.kind.is_return()).enumerate() {
+        let Some(ret) = returns.get(i) else {
+            break;
+        };
+        let key = match ret.name {
+            Some(name) => name.resolve_with(&INTERNER).to_string(),
+            None => format!("_{i}"),
+        };
+        entry.returns.insert(key, item.comment.clone());
+    }
+    entry
+}
+
+/// Compute the normalized param types of the getter of a public state variable
+///
+/// Each mapping key and each array dimension adds a param (of type `uint256` for arrays).
+fn getter_params(type_name: &str) -> Vec<String> {
+    let mut params = Vec::new();
+    let mut ty = type_name;
+    loop {
+        if let Some(inner) = ty
+            .strip_prefix("mapping(")
+            .and_then(|t| t.strip_suffix(')'))
+        {
+            let Some((key, value)) = split_mapping(inner) else {
+                break;
+            };
+            params.push(strip_name(key).to_string());
+            ty = strip_name(value);
+        } else if ty.ends_with(']')
+            && let Some(pos) = ty.rfind('[')
+        {
+            params.push("uint256".to_string());
+            ty = &ty[..pos];
+        } else {
+            break;
+        }
+    }
+    params
+}
+
+/// Split the inside of a mapping type at the top-level `=>`
+fn split_mapping(inner: &str) -> Option<(&str, &str)> {
+    let mut depth = 0usize;
+    for (pos, c) in inner.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            '=' if depth == 0 && inner[pos..].starts_with("=>") => {
+                return Some((&inner[..pos], &inner[pos + 2..]));
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Remove the optional name of a mapping key or value (e.g. `address owner` becomes `address`)
+fn strip_name(ty: &str) -> &str {
+    if ty.starts_with("mapping(") || ty == "address payable" {
+        return ty;
+    }
+    ty.rsplit_once(' ').map_or(ty, |(ty, _)| ty)
+}
+
+#[cfg(test)]
+mod tests {
+    use similar_asserts::assert_eq;
+
+    use crate::{
+        definitions::{
+            Attributes, contract::ContractDefinition, event::EventDefinition,
+            function::FunctionDefinition, interface::InterfaceDefinition,
+            structure::StructDefinition, variable::VariableDeclaration,
+        },
+        natspec::NatSpec,
+        textindex::TextRange,
+    };
+
+    use super::*;
+
+    fn natspec(items: &[(NatSpecKind, &str)]) -> NatSpec {
+        NatSpec {
+            items: items
+                .iter()
+                .map(|(kind, comment)| {
+                    NatSpecItem::builder()
+                        .kind(kind.clone())
+                        .comment(*comment)
+                        .span(TextRange::default())
+                        .build()
+                })
+                .collect(),
+        }
+    }
+
+    fn sym(name: &str) -> Symbol {
+        INTERNER.get_or_intern(name)
+    }
+
+    fn external() -> Attributes {
+        Attributes::builder()
+            .visibility(Visibility::External)
+            .r#override(false)
+            .build()
+    }
+
+    fn interface_definitions() -> Vec<Definition> {
+        vec![
+            InterfaceDefinition::builder()
+                .name(sym("IVault"))
+                .span(TextRange::default())
+                .natspec(natspec(&[(NatSpecKind::Notice, "A vault")]))
+                .build()
+                .into(),
+            StructDefinition::builder()
+                .parent(Parent::Interface("IVault"))
+                .name(sym("Position"))
+                .span(TextRange::default())
+                .members(Vec::new())
+                .member_types(vec!["address".to_string(), "uint256".to_string()])
+                .build()
+                .into(),
+            FunctionDefinition::builder()
+                .parent(Parent::Interface("IVault"))
+                .name(sym("deposit"))
+                .span(TextRange::default())
+                .params(vec![
+                    Identifier::builder()
+                        .name(sym("position"))
+                        .span(TextRange::default())
+                        .build(),
+                ])
+                .param_types(vec!["Position".to_string()])
+                .returns(vec![
+                    Identifier::builder().span(TextRange::default()).build(),
+                ])
+                .natspec(natspec(&[
+                    (NatSpecKind::Notice, "Deposit into the vault"),
+                    (NatSpecKind::Dev, "Reverts if paused"),
+                    (
+                        NatSpecKind::Param {
+                            name: sym("position"),
+                        },
+                        "The position",
+                    ),
+                    (NatSpecKind::Return { name: None }, "The shares"),
+                    (
+                        NatSpecKind::Custom {
+                            tag: sym("security"),
+                        },
+                        "non-reentrant",
+                    ),
+                ]))
+                .attributes(external())
+                .build()
+                .into(),
+            EventDefinition::builder()
+                .parent(Parent::Interface("IVault"))
+                .name(sym("Deposited"))
+                .span(TextRange::default())
+                .params(Vec::new())
+                .param_types(vec!["address".to_string(), "uint256".to_string()])
+                .natspec(natspec(&[(NatSpecKind::Notice, "Emitted on deposit")]))
+                .build()
+                .into(),
+        ]
+    }
+
+    fn contract_definitions() -> Vec<Definition> {
+        vec![
+            ContractDefinition::builder()
+                .name(sym("Vault"))
+                .span(TextRange::default())
+                .bases(vec![sym("IVault")])
+                .natspec(natspec(&[
+                    (NatSpecKind::Title, "Vault"),
+                    (NatSpecKind::Author, "Alice"),
+                    (
+                        NatSpecKind::Custom {
+                            tag: sym("version"),
+                        },
+                        "1",
+                    ),
+                ]))
+                .build()
+                .into(),
+            FunctionDefinition::builder()
+                .parent(Parent::Contract("Vault"))
+                .name(sym("deposit"))
+                .span(TextRange::default())
+                .params(Vec::new())
+                .param_types(vec!["Position".to_string()])
+                .returns(vec![
+                    Identifier::builder()
+                        .name(sym("shares"))
+                        .span(TextRange::default())
+                        .build(),
+                ])
+                .natspec(natspec(&[
+                    (
+                        NatSpecKind::Inheritdoc {
+                            parent: sym("IVault"),
+                        },
+                        "",
+                    ),
+                    (NatSpecKind::Dev, "Overridden"),
+                ]))
+                .attributes(external())
+                .build()
+                .into(),
+            VariableDeclaration::builder()
+                .parent(Parent::Contract("Vault"))
+                .name(sym("balances"))
+                .span(TextRange::default())
+                .type_name("mapping(address owner=>uint256[])")
+                .natspec(natspec(&[
+                    (NatSpecKind::Notice, "The balances"),
+                    (NatSpecKind::Return { name: None }, "The balance"),
+                ]))
+                .attributes(
+                    Attributes::builder()
+                        .visibility(Visibility::Public)
+                        .r#override(false)
+                        .build(),
+                )
+                .build()
+                .into(),
+        ]
+    }
+
+    #[test]
+    fn test_getter_params() {
+        assert_eq!(getter_params("uint256"), Vec::<String>::new());
+        assert_eq!(
+            getter_params("mapping(address owner=>mapping(uint256=>bool))"),
+            vec!["address", "uint256"]
+        );
+        assert_eq!(
+            getter_params("mapping(address=>uint256[])[]"),
+            vec!["uint256", "address", "uint256"]
+        );
+    }
+
+    #[test]
+    fn test_export() {
+        let files = vec![
+            (
+                PathBuf::from("/project/src/IVault.sol"),
+                interface_definitions(),
+            ),
+            (
+                PathBuf::from("/project/src/Vault.sol"),
+                contract_definitions(),
+            ),
+        ];
+        let export = DocsExport::new(&files, "/project");
+        let vault = &export.contracts["src/Vault.sol"]["Vault"];
+        let value = serde_json::to_value(vault).unwrap();
+        assert_eq!(
+            value,
+            serde_json::json!({
+                "userdoc": {
+                    "kind": "user",
+                    "version": 1,
+                    "methods": {
+                        "balances(address,uint256)": { "notice": "The balances" },
+                        "deposit((address,uint256))": { "notice": "Deposit into the vault" }
+                    },
+                    "events": {
+                        "Deposited(address,uint256)": { "notice": "Emitted on deposit" }
+                    }
+                },
+                "devdoc": {
+                    "kind": "dev",
+                    "version": 1,
+                    "author": "Alice",
+                    "title": "Vault",
+                    "custom:version": "1",
+                    "methods": {
+                        "deposit((address,uint256))": {
+                            "details": "Overridden",
+                            "params": { "position": "The position" },
+                            "returns": { "shares": "The shares" }
+                        }
+                    },
+                    "stateVariables": {
+                        "balances": {
+                            "return": "The balance",
+                            "returns": { "_0": "The balance" }
+                        }
+                    }
+                }
+            })
+        );
+        let ivault = &export.contracts["src/IVault.sol"]["IVault"];
+        assert_eq!(ivault.userdoc.notice.as_deref(), Some("A vault"));
+        assert_eq!(
+            ivault.devdoc.methods["deposit((address,uint256))"].returns["_0"],
+            "The shares"
+        );
+        assert_eq!(
+            ivault.devdoc.methods["deposit((address,uint256))"].custom["custom:security"],
+            "non-reentrant"
+        );
+    }
+
+    /// Compare the export of `test-data/ExportSample.sol` with the compiler's output for the same file
+    ///
+    /// The expected JSON has the shape of the `contracts` output of `solc --standard-json` when selecting `userdoc` and
+    /// `devdoc`.
+    #[cfg(any(feature = "solar", feature = "slang"))]
+    fn check_export_sample(mut parser: impl crate::parser::Parse) {
+        let document = parser
+            .parse_document(
+                include_str!("../test-data/ExportSample.sol").as_bytes(),
+                Some("/project/ExportSample.sol"),
+                false,
+            )
+            .unwrap();
+        let files = vec![(
+            PathBuf::from("/project/ExportSample.sol"),
+            document.definitions,
+        )];
+        let export = DocsExport::new(&files, "/project");
+        let expected: serde_json::Value =
+            serde_json::from_str(include_str!("../test-data/ExportSample.json")).unwrap();
+        assert_eq!(serde_json::to_value(&export).unwrap(), expected);
+    }
+
+    #[test]
+    #[cfg(feature = "solar")]
+    fn test_export_sample() {
+        check_export_sample(crate::parser::solar::SolarParser::default());
+    }
+
+    #[test]
+    #[cfg(feature = "slang")]
+    fn test_export_sample_slang() {
+        check_export_sample(crate::parser::slang::SlangParser::builder().build());
+    }
+}
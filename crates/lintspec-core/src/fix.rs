@@ -0,0 +1,1349 @@
+//! Automatic fixes for diagnostics
+//!
+//! Some diagnostics can be resolved automatically by editing the source code, for instance by inserting the missing
+//! `@param` and `@return` tags in the `NatSpec` comment. The fixes are attached to the corresponding [`Diagnostic`] and
+//! can be applied to the source with [`apply_fixes`].
+use std::ops::Range;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    definitions::{
+        Definition, Identifier, constructor::ConstructorDefinition, enumeration::EnumDefinition,
+        error::ErrorDefinition, event::EventDefinition, modifier::ModifierDefinition,
+        structure::StructDefinition,
+    },
+    interner::INTERNER,
+    lint::{Diagnostic, ItemDiagnostics, Rule},
+    natspec::suggest_tag,
+    textindex::{TextIndex, TextRange, compute_indices},
+};
+
+/// The placeholder description used for the inserted tags
+pub const PLACEHOLDER: &str = "TODO";
+
+/// A fix which resolves a diagnostic when applied to the source
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct Fix {
+    /// A short description of the fix
+    pub description: String,
+
+    /// The edits to apply to the source
+    pub edits: Vec<TextEdit>,
+}
+
+/// A single edit to the source
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct TextEdit {
+    /// The span of the text to replace (empty for an insertion)
+    pub span: TextRange,
+
+    /// The replacement text
+    pub text: String,
+}
+
+impl TextEdit {
+    /// Create an edit which replaces a byte range with some text
+    ///
+    /// Only the utf-8 offsets of the span are populated, the other fields are filled by [`populate_fixes`].
+    fn new(range: Range<usize>, text: String) -> Self {
+        let start = TextIndex {
+            utf8: range.start,
+            ..Default::default()
+        };
+        let end = TextIndex {
+            utf8: range.end,
+            ..Default::default()
+        };
+        Self {
+            span: start..end,
+            text,
+        }
+    }
+
+    /// Create an edit which inserts text at a byte offset
+    fn insert(offset: usize, text: String) -> Self {
+        Self::new(offset..offset, text)
+    }
+}
+
+/// Apply the edits of a list of fixes to a source string
+///
+/// Edits are applied in order of their start offset. Insertions at the same offset are applied in the order in which
+/// they appear, before any replacement starting at that offset. Edits which overlap with a previous edit are ignored,
+/// and identical edits (e.g. from a fix shared by several diagnostics) are applied only once.
+#[must_use]
+pub fn apply_fixes<'a>(source: &str, fixes: impl IntoIterator<Item = &'a Fix>) -> String {
+    let mut edits: Vec<_> = fixes.into_iter().flat_map(|f| &f.edits).collect();
+    // stable sort to keep the order of insertions
+    edits.sort_by_key(|e| (e.span.start.utf8, e.span.end.utf8));
+    edits.dedup();
+    let mut res = String::with_capacity(source.len());
+    let mut cursor = 0;
+    for edit in edits {
+        if edit.span.start.utf8 < cursor {
+            continue;
+        }
+        res.push_str(&source[cursor..edit.span.start.utf8]);
+        res.push_str(&edit.text);
+        cursor = edit.span.end.utf8;
+    }
+    res.push_str(&source[cursor..]);
+    res
+}
+
+/// The style of a doc-comment
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CommentStyle {
+    /// Consecutive `///` comments
+    Line,
+
+    /// A `/** */` comment
+    Block,
+}
+
+/// A line of a doc-comment
+#[derive(Debug, Clone)]
+struct DocLine {
+    /// Offset of the start of the line (including indentation)
+    start: usize,
+
+    /// Offset of the end of the line (after the line break)
+    end: usize,
+
+    /// The range of the line's content, without comment delimiters and surrounding whitespace
+    content: Range<usize>,
+
+    /// Whether the content is followed by other characters on the same line (e.g. a closing `*/`)
+    inline: bool,
+
+    /// The tag which replaces the unknown tag of the line when the fixes are applied, if any
+    renamed: Option<String>,
+}
+
+/// The doc-comment directly preceding a declaration
+#[derive(Debug, Clone)]
+struct DocComment {
+    /// The style of the comment
+    style: CommentStyle,
+
+    /// The lines of the comment
+    lines: Vec<DocLine>,
+
+    /// The prefix to use for new lines, including indentation and delimiter (e.g. `    /// `)
+    prefix: String,
+
+    /// The ranges of the opening (with the following whitespace) and closing (with the preceding whitespace)
+    /// delimiters of a block comment which fits on a single line
+    inline_delimiters: Option<(Range<usize>, Range<usize>)>,
+}
+
+impl DocComment {
+    /// Find the last doc-comment in the source range of an item (before its declaration)
+    fn find(source: &str, item_start: usize, item_end: usize) -> Option<Self> {
+        let mut comments: Vec<DocComment> = Vec::new();
+        let mut in_block = false;
+        let mut previous_is_line_doc = false;
+        let mut pos = line_start(source, item_start);
+        while pos < item_end {
+            let end = source[pos..]
+                .find('\n')
+                .map_or(source.len(), |i| pos + i + 1);
+            let line = &source[pos..end];
+            let indent = line.len() - line.trim_start().len();
+            let trimmed = line.trim();
+            if in_block {
+                let comment = comments.last_mut()?;
+                let (content, inline) = if let Some(close) = trimmed.find("*/") {
+                    in_block = false;
+                    let content = trimmed[..close]
+                        .strip_prefix('*')
+                        .unwrap_or(&trimmed[..close]);
+                    (content, true)
+                } else {
+                    (trimmed.strip_prefix('*').unwrap_or(trimmed), false)
+                };
+                let content = content_range(source, content);
+                comment.lines.push(DocLine {
+                    start: pos,
+                    end,
+                    content,
+                    inline,
+                    renamed: None,
+                });
+            } else if let Some(rest) = trimmed.strip_prefix("///") {
+                let doc_line = DocLine {
+                    start: pos,
+                    end,
+                    content: content_range(source, rest),
+                    inline: false,
+                    renamed: None,
+                };
+                match comments.last_mut() {
+                    Some(comment) if previous_is_line_doc => comment.lines.push(doc_line),
+                    _ => comments.push(DocComment {
+                        style: CommentStyle::Line,
+                        lines: vec![doc_line],
+                        prefix: format!("{}/// ", &line[..indent]),
+                        inline_delimiters: None,
+                    }),
+                }
+                previous_is_line_doc = true;
+                pos = end;
+                continue;
+            } else if let Some(rest) = trimmed.strip_prefix("/**")
+                && !rest.starts_with('/')
+            {
+                let (content, inline) = if let Some(close) = rest.find("*/") {
+                    (&rest[..close], true)
+                } else {
+                    in_block = true;
+                    (rest, false)
+                };
+                comments.push(DocComment {
+                    style: CommentStyle::Block,
+                    lines: vec![DocLine {
+                        start: pos,
+                        end,
+                        content: content_range(source, content),
+                        inline,
+                        renamed: None,
+                    }],
+                    prefix: format!("{} * ", &line[..indent]),
+                    inline_delimiters: None,
+                });
+            } else if !trimmed.is_empty() && !trimmed.starts_with("//") {
+                // start of the declaration
+                break;
+            }
+            previous_is_line_doc = false;
+            pos = end;
+        }
+        let mut comment = comments.pop()?;
+        // use the same prefix as existing lines if possible
+        if let Some(line) = comment
+            .lines
+            .iter()
+            .skip(usize::from(comment.style == CommentStyle::Block))
+            .find(|l| !l.content.is_empty() && l.content.start > l.start)
+        {
+            comment.prefix = source[line.start..line.content.start].to_string();
+        }
+        if let [line] = comment.lines.as_slice()
+            && comment.style == CommentStyle::Block
+            && line.inline
+            && let Some(open) = source[line.start..line.content.start].rfind("/**")
+            && let Some(close) = source[line.content.end..line.end].find("*/")
+        {
+            comment.inline_delimiters = Some((
+                line.start + open..line.content.start,
+                line.content.end..line.content.end + close + 2,
+            ));
+        }
+        Some(comment)
+    }
+
+    /// Generate the edits which expand a single-line block comment to the multi-line layout, if needed
+    ///
+    /// The opening and closing delimiters are moved to their own lines. The edits are identical for all the fixes
+    /// of a comment, so that they are only applied once.
+    fn expand(&self) -> Vec<TextEdit> {
+        let Some((open, close)) = &self.inline_delimiters else {
+            return Vec::new();
+        };
+        vec![
+            TextEdit::new(open.clone(), format!("/**\n{}", self.prefix)),
+            TextEdit::new(close.clone(), format!("\n{}/", self.prefix.trim_end())),
+        ]
+    }
+
+    /// Find the index of the last line belonging to the tag which starts at line `idx` (including continuation lines)
+    fn group_end(&self, source: &str, idx: usize) -> usize {
+        let mut last = idx;
+        for (i, line) in self.lines.iter().enumerate().skip(idx + 1) {
+            if line_tag(source, line).is_some() {
+                break;
+            }
+            if !line.content.is_empty() {
+                last = i;
+            }
+        }
+        last
+    }
+
+    /// Generate the edits which insert a new line after the line at index `idx`
+    fn insert_after(&self, idx: usize, text: &str) -> Vec<TextEdit> {
+        let line = &self.lines[idx];
+        let mut edits = self.expand();
+        if line.inline || (self.style == CommentStyle::Block && idx == 0) {
+            // the line contains the comment delimiters, we insert right after the content
+            edits.push(TextEdit::insert(
+                line.content.end,
+                format!("\n{}{text}", self.prefix),
+            ));
+        } else {
+            edits.push(TextEdit::insert(
+                line.end,
+                format!("{}{text}\n", self.prefix),
+            ));
+        }
+        edits
+    }
+
+    /// Generate the edits which insert a new line before the line at index `idx`
+    fn insert_before(&self, idx: usize, text: &str) -> Vec<TextEdit> {
+        let line = &self.lines[idx];
+        let mut edits = self.expand();
+        if self.style == CommentStyle::Block && idx == 0 {
+            // the line contains the opening delimiter, we insert right before the content
+            edits.push(TextEdit::insert(
+                line.content.start,
+                format!("{text}\n{}", self.prefix),
+            ));
+        } else {
+            edits.push(TextEdit::insert(
+                line.start,
+                format!("{}{text}\n", self.prefix),
+            ));
+        }
+        edits
+    }
+
+    /// Generate an edit which removes the tag starting at line `idx` (including continuation lines)
+    ///
+    /// The comment delimiters are preserved when they are on the same line as the tag.
+    fn remove(&self, source: &str, idx: usize) -> TextEdit {
+        let last = self.group_end(source, idx);
+        let (first_line, last_line) = (&self.lines[idx], &self.lines[last]);
+        let range = if self.style == CommentStyle::Block && idx == 0 {
+            // the tag follows the opening delimiter, the next line's content is moved after it
+            first_line.content.start
+                ..self
+                    .lines
+                    .get(last + 1)
+                    .map_or(last_line.content.end, |l| l.content.start)
+        } else if last_line.inline {
+            // the tag is followed by the closing delimiter, which is moved to the end of the previous line
+            self.lines[idx - 1].content.end..last_line.content.end
+        } else {
+            first_line.start..last_line.end
+        };
+        TextEdit::new(range, String::new())
+    }
+
+    /// Generate the edits which insert a new line at the end of the comment
+    fn insert_at_end(&self, text: &str) -> Vec<TextEdit> {
+        let idx = self
+            .lines
+            .iter()
+            .rposition(|l| !l.content.is_empty())
+            .unwrap_or_default();
+        self.insert_after(idx, text)
+    }
+}
+
+/// Retrieve the tag (e.g. `param`) and the following word (e.g. the param name) of a doc-comment line, if any
+///
+/// If the tag is renamed by a fix, the new tag is returned.
+fn line_tag<'a>(source: &'a str, line: &'a DocLine) -> Option<(&'a str, Option<&'a str>)> {
+    let content = source[line.content.clone()].strip_prefix('@')?;
+    let mut words = content.split_whitespace();
+    let tag = words.next()?;
+    Some((line.renamed.as_deref().unwrap_or(tag), words.next()))
+}
+
+/// Find the indentation of the line containing `offset`, if there is only whitespace before the offset
+fn indentation(source: &str, offset: usize) -> &str {
+    let before = &source[line_start(source, offset)..offset];
+    if before.trim().is_empty() { before } else { "" }
+}
+
+/// Find the offset of the start of the line containing `offset`
+fn line_start(source: &str, offset: usize) -> usize {
+    source[..offset].rfind('\n').map_or(0, |i| i + 1)
+}
+
+/// Compute the range of the trimmed content of a line, given a slice of the source containing the content
+fn content_range(source: &str, content: &str) -> Range<usize> {
+    let content = content.trim();
+    // compute the offset of the content slice inside of the source
+    let start = content.as_ptr().addr() - source.as_ptr().addr();
+    start..start + content.len()
+}
+
+/// The params and returns of a definition
+fn params_and_returns(definition: &Definition) -> (&[Identifier], &[Identifier]) {
+    match definition {
+        Definition::Constructor(ConstructorDefinition { params, .. })
+        | Definition::Error(ErrorDefinition { params, .. })
+        | Definition::Event(EventDefinition { params, .. })
+        | Definition::Modifier(ModifierDefinition { params, .. })
+        | Definition::Enumeration(EnumDefinition {
+            members: params, ..
+        })
+        | Definition::Struct(StructDefinition {
+            members: params, ..
+        }) => (params, &[]),
+        Definition::Function(def) => (&def.params, &def.returns),
+        _ => (&[], &[]),
+    }
+}
+
+/// Generate fixes for the `@param`, `@return` and unknown tag diagnostics of an item
+///
+/// Missing tags are inserted with a placeholder description and extra tags are removed. If there are as many `@param`
+/// which don't match any parameter as undocumented parameters, they are paired in order and each tag which is at the
+/// same position as its parameter is renamed instead.
+/// Unknown tags are replaced with the suggested tag, if any. The other fixes take the renamed tags into account, and the
+/// diagnostics which are resolved by a renamed tag (e.g. a missing `@notice` when `@notive` is renamed) get its fix.
+pub(crate) fn add_fixes(source: &str, definition: &Definition, item: &mut ItemDiagnostics) {
+    let renames = unknown_tags_fixes(source, &mut item.diags);
+    renamed_tags_fixes(&renames, &mut item.diags);
+    if !item.diags.iter().any(|d| {
+        matches!(
+            d.rule,
+            Rule::MissingParam | Rule::MissingReturn | Rule::ExtraParam | Rule::ExtraReturn
+        )
+    }) {
+        return;
+    }
+    let (params, returns) = params_and_returns(definition);
+    let comment =
+        DocComment::find(source, item.span.start.utf8, item.span.end.utf8).map(|mut comment| {
+            for line in &mut comment.lines {
+                line.renamed = renames
+                    .iter()
+                    .find(|r| r.offset == line.content.start)
+                    .map(|r| r.tag.clone());
+            }
+            comment
+        });
+    if let Some(comment) = &comment {
+        renamed_params_fixes(source, comment, &renames, params, returns, &mut item.diags);
+        extra_params_fixes(source, comment, params, &mut item.diags);
+        extra_returns_fixes(source, comment, returns, &mut item.diags);
+    }
+    missing_tags_fixes(source, comment.as_ref(), params, returns, item);
+}
+
+/// An unknown tag which is replaced with a valid tag by a fix
+struct Rename {
+    /// The offset of the `@` of the unknown tag
+    offset: usize,
+
+    /// The suggested tag, without `@`
+    tag: String,
+
+    /// The fix which replaces the tag
+    fix: Fix,
+}
+
+/// Generate fixes for the unknown tag diagnostics for which a valid tag can be suggested
+///
+/// The span of the diagnostic starts at the `@` of the unknown tag.
+fn unknown_tags_fixes(source: &str, diags: &mut [Diagnostic]) -> Vec<Rename> {
+    let mut renames = Vec::new();
+    for diag in diags.iter_mut().filter(|d| d.rule == Rule::UnknownTag) {
+        let start = diag.span.start.utf8;
+        let Some(tag) = source.get(start..).and_then(|s| s.strip_prefix('@')) else {
+            continue;
+        };
+        let end = tag
+            .find(|c: char| !(c.is_alphanumeric() || matches!(c, '_' | '-' | ':')))
+            .unwrap_or(tag.len());
+        let tag = &tag[..end];
+        let Some(suggestion) = suggest_tag(tag) else {
+            continue;
+        };
+        let fix = Fix {
+            description: format!("replace `@{tag}` with `@{suggestion}`"),
+            edits: vec![TextEdit::new(
+                start..start + 1 + tag.len(),
+                format!("@{suggestion}"),
+            )],
+        };
+        diag.fix = Some(fix.clone());
+        renames.push(Rename {
+            offset: start,
+            tag: suggestion,
+            fix,
+        });
+    }
+    renames
+}
+
+/// Attach the fix of a renamed tag to the diagnostics for a missing `@notice`, `@dev`, `@title` or `@author` which
+/// it resolves
+fn renamed_tags_fixes(renames: &[Rename], diags: &mut [Diagnostic]) {
+    for diag in diags.iter_mut().filter(|d| d.fix.is_none()) {
+        let tags: &[&str] = match diag.rule {
+            Rule::MissingNotice => &["notice"],
+            Rule::MissingDev => &["dev"],
+            Rule::MissingNoticeOrDev => &["notice", "dev"],
+            Rule::MissingTitle => &["title"],
+            Rule::MissingAuthor => &["author"],
+            _ => continue,
+        };
+        if let Some(rename) = renames.iter().find(|r| tags.contains(&r.tag.as_str())) {
+            diag.fix = Some(rename.fix.clone());
+        }
+    }
+}
+
+/// Attach the fix of a tag renamed to `@param` or `@return` to the missing `@param` and `@return` diagnostics which it
+/// resolves
+///
+/// Like the linter, the unnamed returns are documented by the `@return` tags in order.
+fn renamed_params_fixes(
+    source: &str,
+    comment: &DocComment,
+    renames: &[Rename],
+    params: &[Identifier],
+    returns: &[Identifier],
+    diags: &mut [Diagnostic],
+) {
+    let fix_of = |line: &DocLine| {
+        renames
+            .iter()
+            .find(|r| r.offset == line.content.start)
+            .map(|r| r.fix.clone())
+    };
+    let renamed_line =
+        |tag: &str, name: &str| {
+            comment.lines.iter().filter(|l| l.renamed.is_some()).find(
+                |l| matches!(line_tag(source, l), Some((t, Some(n))) if t == tag && n == name),
+            )
+        };
+    let (renamed_returns, documented_returns): (Vec<_>, Vec<_>) = comment
+        .lines
+        .iter()
+        .filter(|l| matches!(line_tag(source, l), Some(("return", _))))
+        .partition(|l| l.renamed.is_some());
+    for diag in diags.iter_mut().filter(|d| d.fix.is_none()) {
+        let line = match diag.rule {
+            Rule::MissingParam => params
+                .iter()
+                .find(|p| p.span == diag.span)
+                .and_then(|p| p.name)
+                .and_then(|name| renamed_line("param", INTERNER.resolve(name))),
+            Rule::MissingReturn => {
+                let Some(idx) = returns.iter().position(|r| r.span == diag.span) else {
+                    continue;
+                };
+                match returns[idx].name {
+                    Some(name) => renamed_line("return", INTERNER.resolve(name)),
+                    None => idx
+                        .checked_sub(documented_returns.len())
+                        .and_then(|i| renamed_returns.get(i).copied()),
+                }
+            }
+            _ => continue,
+        };
+        if let Some(fix) = line.and_then(fix_of) {
+            diag.fix = Some(fix);
+        }
+    }
+}
+
+/// Generate fixes for the extra `@param` diagnostics
+fn extra_params_fixes(
+    source: &str,
+    comment: &DocComment,
+    params: &[Identifier],
+    diags: &mut [Diagnostic],
+) {
+    let param_lines: Vec<_> = comment
+        .lines
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, line)| match line_tag(source, line) {
+            Some(("param", Some(name))) => Some((idx, name)),
+            _ => None,
+        })
+        .collect();
+    // the renamed tags are not reported by the linter
+    let extra_lines: Vec<_> = param_lines
+        .iter()
+        .enumerate()
+        .filter(|(_, (idx, name))| {
+            comment.lines[*idx].renamed.is_none()
+                && !params
+                    .iter()
+                    .any(|p| p.name.is_some_and(|n| INTERNER.resolve(n) == *name))
+        })
+        .collect();
+    // the comment might not be the one which was parsed, in which case we can't match the diagnostics to the lines
+    if extra_lines.is_empty()
+        || extra_lines.len() != diags.iter().filter(|d| d.rule == Rule::ExtraParam).count()
+    {
+        return;
+    }
+    // the undocumented params, with the index of their diagnostic
+    let mut missing: Vec<_> = diags
+        .iter()
+        .enumerate()
+        .filter(|(_, d)| d.rule == Rule::MissingParam)
+        .filter_map(|(diag_idx, d)| {
+            let idx = params.iter().position(|p| p.span == d.span)?;
+            Some((diag_idx, idx))
+        })
+        .collect();
+    missing.sort_by_key(|(_, idx)| *idx);
+    let mut renames = vec![None; extra_lines.len()];
+    if extra_lines.len() == missing.len() {
+        for (rename, ((position, (_, old_name)), (diag_idx, idx))) in
+            renames.iter_mut().zip(extra_lines.iter().zip(missing))
+        {
+            let Some(new_name) = params[idx].name.filter(|_| *position == idx) else {
+                continue;
+            };
+            let new_name = INTERNER.resolve(new_name);
+            let start = old_name.as_ptr().addr() - source.as_ptr().addr();
+            let fix = Fix {
+                description: format!("rename `@param {old_name}` to `@param {new_name}`"),
+                edits: vec![TextEdit::new(
+                    start..start + old_name.len(),
+                    new_name.to_string(),
+                )],
+            };
+            diags[diag_idx].fix = Some(fix.clone());
+            *rename = Some(fix);
+        }
+    }
+    for ((diag, (_, (line, name))), rename) in diags
+        .iter_mut()
+        .filter(|d| d.rule == Rule::ExtraParam)
+        .zip(extra_lines)
+        .zip(renames)
+    {
+        diag.fix = Some(rename.unwrap_or_else(|| Fix {
+            description: format!("remove `@param {name}`"),
+            edits: vec![comment.remove(source, *line)],
+        }));
+    }
+}
+
+/// Generate a fix for the extra `@return` diagnostic
+///
+/// If there is a single extra unnamed `@return` and a single undocumented named return (and no unnamed return), the
+/// name is added to the existing `@return` instead of removing it.
+fn extra_returns_fixes(
+    source: &str,
+    comment: &DocComment,
+    returns: &[Identifier],
+    diags: &mut [Diagnostic],
+) {
+    let Some(diag_idx) = diags.iter().position(|d| d.rule == Rule::ExtraReturn) else {
+        return;
+    };
+    let unnamed_lines: Vec<_> = comment
+        .lines
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| line.renamed.is_none())
+        .filter_map(|(idx, line)| match line_tag(source, line) {
+            Some(("return", name))
+                if !returns
+                    .iter()
+                    .any(|r| r.name.is_some_and(|n| Some(INTERNER.resolve(n)) == name)) =>
+            {
+                Some(idx)
+            }
+            _ => None,
+        })
+        .collect();
+    let unnamed_returns = returns.iter().filter(|r| r.name.is_none()).count();
+    let Some(excess) = unnamed_lines
+        .len()
+        .checked_sub(unnamed_returns)
+        .filter(|e| *e > 0)
+    else {
+        return;
+    };
+    let missing: Vec<_> = diags
+        .iter()
+        .enumerate()
+        .filter(|(_, d)| d.rule == Rule::MissingReturn)
+        .collect();
+    if let ([line], [(missing_idx, missing)]) = (unnamed_lines.as_slice(), missing.as_slice())
+        && unnamed_returns == 0
+        && let Some(name) = returns
+            .iter()
+            .find(|r| r.span == missing.span)
+            .and_then(|r| r.name)
+    {
+        let name = INTERNER.resolve(name);
+        let offset = comment.lines[*line].content.start + "@return".len();
+        let fix = Fix {
+            description: format!("add name `{name}` to `@return`"),
+            edits: vec![TextEdit::insert(offset, format!(" {name}"))],
+        };
+        diags[*missing_idx].fix = Some(fix.clone());
+        diags[diag_idx].fix = Some(fix);
+        return;
+    }
+    diags[diag_idx].fix = Some(Fix {
+        description: "remove the extra `@return`".to_string(),
+        edits: unnamed_lines[unnamed_lines.len() - excess..]
+            .iter()
+            .map(|line| comment.remove(source, *line))
+            .collect(),
+    });
+}
+
+/// Generate fixes for the missing `@param` and `@return` diagnostics which don't have a fix yet
+fn missing_tags_fixes(
+    source: &str,
+    comment: Option<&DocComment>,
+    params: &[Identifier],
+    returns: &[Identifier],
+    item: &mut ItemDiagnostics,
+) {
+    // indentation of the declaration, used when creating a new comment
+    let indent = indentation(source, item.span.start.utf8);
+    for diag in item.diags.iter_mut().filter(|d| d.fix.is_none()) {
+        let (tag, text) = match diag.rule {
+            Rule::MissingParam => {
+                let Some(name) = params
+                    .iter()
+                    .find(|p| p.span == diag.span)
+                    .and_then(|p| p.name)
+                else {
+                    continue;
+                };
+                let name = INTERNER.resolve(name);
+                (
+                    format!("@param {name}"),
+                    format!("@param {name} {PLACEHOLDER}"),
+                )
+            }
+            Rule::MissingReturn => match returns
+                .iter()
+                .find(|r| r.span == diag.span)
+                .and_then(|r| r.name)
+            {
+                Some(name) => {
+                    let name = INTERNER.resolve(name);
+                    (
+                        format!("@return {name}"),
+                        format!("@return {name} {PLACEHOLDER}"),
+                    )
+                }
+                None => ("@return".to_string(), format!("@return {PLACEHOLDER}")),
+            },
+            _ => continue,
+        };
+        let edits = match comment {
+            Some(comment) => insertion_edits(source, comment, diag, params, &text),
+            None => vec![TextEdit::insert(
+                item.span.start.utf8,
+                format!("/// {text}\n{indent}"),
+            )],
+        };
+        diag.fix = Some(Fix {
+            description: format!("add `{tag}`"),
+            edits,
+        });
+    }
+}
+
+/// Generate the edits which insert a tag as the first line of the item's doc-comment
+///
+/// A new `///` comment is created if the item has none.
+pub(crate) fn insert_first_tag(source: &str, span: &TextRange, text: &str) -> Vec<TextEdit> {
+    match DocComment::find(source, span.start.utf8, span.end.utf8) {
+        Some(comment) => {
+            // skip the opening delimiter of a block comment if it's alone on its line
+            let idx = comment
+                .lines
+                .iter()
+                .position(|l| !l.content.is_empty())
+                .unwrap_or_default();
+            comment.insert_before(idx, text)
+        }
+        None => vec![TextEdit::insert(
+            span.start.utf8,
+            format!("/// {text}\n{}", indentation(source, span.start.utf8)),
+        )],
+    }
+}
+
+/// Find where to insert a missing tag in an existing comment and generate the corresponding edits
+///
+/// Missing params are inserted after the closest preceding documented param (or before the closest following one),
+/// to keep the declaration order. Missing returns are inserted after the last `@return`.
+fn insertion_edits(
+    source: &str,
+    comment: &DocComment,
+    diag: &Diagnostic,
+    params: &[Identifier],
+    text: &str,
+) -> Vec<TextEdit> {
+    let tags: Vec<_> = comment.lines.iter().map(|l| line_tag(source, l)).collect();
+    let find_param = |name: &str| {
+        tags.iter()
+            .rposition(|t| matches!(t, Some(("param", Some(n))) if *n == name))
+    };
+    let last_tag = |tag: &str| {
+        tags.iter()
+            .rposition(|t| matches!(t, Some((t, _)) if *t == tag))
+    };
+    let first_tag = |tag: &str| {
+        tags.iter()
+            .position(|t| matches!(t, Some((t, _)) if *t == tag))
+    };
+    if diag.rule == Rule::MissingParam {
+        let idx = params
+            .iter()
+            .position(|p| p.span == diag.span)
+            .unwrap_or_default();
+        // closest preceding param which is documented
+        if let Some(line) = params[..idx]
+            .iter()
+            .rev()
+            .filter_map(|p| p.name)
+            .find_map(|n| find_param(INTERNER.resolve(n)))
+        {
+            return comment.insert_after(comment.group_end(source, line), text);
+        }
+        // closest following param which is documented
+        if let Some(line) = params[idx + 1..]
+            .iter()
+            .filter_map(|p| p.name)
+            .find_map(|n| {
+                let name = INTERNER.resolve(n);
+                tags.iter()
+                    .position(|t| matches!(t, Some(("param", Some(n))) if *n == name))
+            })
+        {
+            return comment.insert_before(line, text);
+        }
+        if let Some(line) = last_tag("param") {
+            return comment.insert_after(comment.group_end(source, line), text);
+        }
+        if let Some(line) = first_tag("return") {
+            return comment.insert_before(line, text);
+        }
+    } else if let Some(line) = last_tag("return") {
+        return comment.insert_after(comment.group_end(source, line), text);
+    }
+    comment.insert_at_end(text)
+}
+
+/// Fill in the line and column information of the fixes' edits
+pub(crate) fn populate_fixes(source: &str, items: &mut [ItemDiagnostics]) {
+    let mut offsets: Vec<_> = items
+        .iter()
+        .flat_map(|i| &i.diags)
+        .filter_map(|d| d.fix.as_ref())
+        .flat_map(|f| &f.edits)
+        .flat_map(|e| [e.span.start.utf8, e.span.end.utf8])
+        .collect();
+    if offsets.is_empty() || source.is_empty() {
+        return;
+    }
+    offsets.sort_unstable();
+    let indices = compute_indices(source, &offsets);
+    let find = |offset: usize| {
+        indices
+            .binary_search_by_key(&offset, |i| i.utf8)
+            .map_or_else(|_| TextIndex::default(), |i| indices[i])
+    };
+    for edit in items
+        .iter_mut()
+        .flat_map(|i| &mut i.diags)
+        .filter_map(|d| d.fix.as_mut())
+        .flat_map(|f| &mut f.edits)
+    {
+        edit.span = find(edit.span.start.utf8)..find(edit.span.end.utf8);
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "solar")]
+mod tests {
+    use similar_asserts::assert_eq;
+
+    use crate::{
+        lint::{Validate as _, ValidationOptions},
+        parser::{Parse as _, solar::SolarParser},
+    };
+
+    use super::*;
+
+    fn fix(contents: &str) -> String {
+        let mut parser = SolarParser::default();
+        let doc = parser
+            .parse_document(contents.as_bytes(), None::<std::path::PathBuf>, false)
+            .unwrap();
+        let options = ValidationOptions::builder().inheritdoc(false).build();
+        let mut items: Vec<_> = doc
+            .definitions
+            .iter()
+            .map(|d| {
+                let mut item = d.validate(&options);
+                add_fixes(contents, d, &mut item);
+                item
+            })
+            .collect();
+        populate_fixes(contents, &mut items);
+        apply_fixes(
+            contents,
+            items
+                .iter()
+                .flat_map(|i| &i.diags)
+                .filter_map(|d| d.fix.as_ref()),
+        )
+    }
+
+    #[test]
+    fn test_line_comment() {
+        let contents = "contract Test {
+    /// @notice A function
+    /// @param b The second
+    /// @return The first output
+    function foo(uint256 a, uint256 b, uint256 c) internal returns (uint256, uint256 d) {}
+}";
+        assert_eq!(
+            fix(contents),
+            "contract Test {
+    /// @notice A function
+    /// @param a TODO
+    /// @param b The second
+    /// @param c TODO
+    /// @return The first output
+    /// @return d TODO
+    function foo(uint256 a, uint256 b, uint256 c) internal returns (uint256, uint256 d) {}
+}"
+        );
+    }
+
+    #[test]
+    fn test_block_comment() {
+        let contents = "contract Test {
+    /**
+     * @notice A function
+     * @param a The first
+     *        with more details
+     */
+    function foo(uint256 a, uint256 b) internal returns (uint256) {}
+}";
+        assert_eq!(
+            fix(contents),
+            "contract Test {
+    /**
+     * @notice A function
+     * @param a The first
+     *        with more details
+     * @param b TODO
+     * @return TODO
+     */
+    function foo(uint256 a, uint256 b) internal returns (uint256) {}
+}"
+        );
+    }
+
+    #[test]
+    fn test_single_line_block_comment() {
+        let contents = "contract Test {
+    /** @notice A function */
+    function foo(uint256 a) internal {}
+}";
+        assert_eq!(
+            fix(contents),
+            "contract Test {
+    /**
+     * @notice A function
+     * @param a TODO
+     */
+    function foo(uint256 a) internal {}
+}"
+        );
+    }
+
+    #[test]
+    fn test_inline_block_comment() {
+        let contents = "contract Test {
+    /** @notice A function */
+    function foo(uint256 a, uint256 b) internal returns (uint256 r) {}
+
+    /**@notice Without spaces*/
+    function bar(uint256 a) internal {}
+}";
+        assert_eq!(
+            fix(contents),
+            "contract Test {
+    /**
+     * @notice A function
+     * @param a TODO
+     * @param b TODO
+     * @return r TODO
+     */
+    function foo(uint256 a, uint256 b) internal returns (uint256 r) {}
+
+    /**
+     * @notice Without spaces
+     * @param a TODO
+     */
+    function bar(uint256 a) internal {}
+}"
+        );
+    }
+
+    #[test]
+    fn test_no_comment() {
+        let contents = "contract Test {
+    /// @notice An event
+    event Foo(uint256 a);
+
+    function foo(uint256 a) internal returns (uint256 b) {}
+}";
+        assert_eq!(
+            fix(contents),
+            "contract Test {
+    /// @notice An event
+    /// @param a TODO
+    event Foo(uint256 a);
+
+    /// @param a TODO
+    /// @return b TODO
+    function foo(uint256 a) internal returns (uint256 b) {}
+}"
+        );
+    }
+
+    #[test]
+    fn test_remove_extra_line_comment() {
+        let contents = "contract Test {
+    /// @notice A function
+    /// @param old The old param
+    ///        with more details
+    /// @param a The first
+    function foo(uint256 a) internal {}
+}";
+        assert_eq!(
+            fix(contents),
+            "contract Test {
+    /// @notice A function
+    /// @param a The first
+    function foo(uint256 a) internal {}
+}"
+        );
+    }
+
+    #[test]
+    fn test_remove_extra_block_comment() {
+        let contents = "contract Test {
+    /** @param old The old param
+     * @notice A function
+     * @param a The first
+     * @param other Another old param */
+    function foo(uint256 a) internal {}
+}";
+        assert_eq!(
+            fix(contents),
+            "contract Test {
+    /** @notice A function
+     * @param a The first */
+    function foo(uint256 a) internal {}
+}"
+        );
+    }
+
+    #[test]
+    fn test_rename_param() {
+        let contents = "contract Test {
+    /// @notice A function
+    /// @param a The first
+    /// @param old The second
+    /// @param c The third
+    function foo(uint256 a, uint256 b, uint256 c) internal {}
+}";
+        assert_eq!(
+            fix(contents),
+            "contract Test {
+    /// @notice A function
+    /// @param a The first
+    /// @param b The second
+    /// @param c The third
+    function foo(uint256 a, uint256 b, uint256 c) internal {}
+}"
+        );
+    }
+
+    #[test]
+    fn test_rename_params() {
+        let contents = "contract Test {
+    /// @notice A function
+    /// @param a1 The first
+    /// @param b1 The second
+    /// @param c The third
+    function foo(uint256 a2, uint256 b2, uint256 c) internal {}
+}";
+        assert_eq!(
+            fix(contents),
+            "contract Test {
+    /// @notice A function
+    /// @param a2 The first
+    /// @param b2 The second
+    /// @param c The third
+    function foo(uint256 a2, uint256 b2, uint256 c) internal {}
+}"
+        );
+    }
+
+    #[test]
+    fn test_no_rename_different_position() {
+        let contents = "contract Test {
+    /// @notice A function
+    /// @param old The first
+    /// @param b The second
+    function foo(uint256 b, uint256 c) internal {}
+}";
+        assert_eq!(
+            fix(contents),
+            "contract Test {
+    /// @notice A function
+    /// @param b The second
+    /// @param c TODO
+    function foo(uint256 b, uint256 c) internal {}
+}"
+        );
+    }
+
+    #[test]
+    fn test_extra_returns() {
+        let contents = "contract Test {
+    /**
+     * @notice A function
+     * @return The first
+     * @return The second
+     */
+    function foo() internal returns (uint256) {}
+
+    /// @notice A function
+    /// @return The output
+    function bar() internal returns (uint256 out) {}
+}";
+        assert_eq!(
+            fix(contents),
+            "contract Test {
+    /**
+     * @notice A function
+     * @return The first
+     */
+    function foo() internal returns (uint256) {}
+
+    /// @notice A function
+    /// @return out The output
+    function bar() internal returns (uint256 out) {}
+}"
+        );
+    }
+
+    #[test]
+    fn test_unknown_tags() {
+        let contents = "contract Test {
+    /// @notive A function
+    /// @Dev Some details
+    /// @foo Bar
+    function foo() internal {}
+
+    /// @notice A function
+    /// @params a The input
+    /// @returns The output
+    function bar(uint256 a) internal returns (uint256) {}
+}";
+        assert_eq!(
+            fix(contents),
+            "contract Test {
+    /// @notice A function
+    /// @dev Some details
+    /// @foo Bar
+    function foo() internal {}
+
+    /// @notice A function
+    /// @param a The input
+    /// @return The output
+    function bar(uint256 a) internal returns (uint256) {}
+}"
+        );
+    }
+
+    #[test]
+    fn test_unknown_tags_with_other_fixes() {
+        let contents = "contract Test {
+    /// @notive A function
+    /// @params x The x
+    /// @returns The first output
+    function foo(uint256 x, uint256 y) internal returns (uint256, uint256 r) {}
+}";
+        assert_eq!(
+            fix(contents),
+            "contract Test {
+    /// @notice A function
+    /// @param x The x
+    /// @param y TODO
+    /// @return The first output
+    /// @return r TODO
+    function foo(uint256 x, uint256 y) internal returns (uint256, uint256 r) {}
+}"
+        );
+
+        // the diagnostics resolved by a renamed tag get the same fix
+        let mut parser = SolarParser::default();
+        let doc = parser
+            .parse_document(contents.as_bytes(), None::<std::path::PathBuf>, false)
+            .unwrap();
+        let def = doc
+            .definitions
+            .iter()
+            .find(|d| matches!(d, Definition::Function(_)))
+            .unwrap();
+        let mut item = def.validate(&ValidationOptions::default());
+        add_fixes(contents, def, &mut item);
+        let fixes: Vec<_> = item
+            .diags
+            .iter()
+            .map(|d| (d.rule, d.fix.as_ref().map(|f| f.description.as_str())))
+            .collect();
+        assert_eq!(
+            fixes,
+            vec![
+                (
+                    Rule::MissingNotice,
+                    Some("replace `@notive` with `@notice`")
+                ),
+                (Rule::MissingParam, Some("replace `@params` with `@param`")),
+                (Rule::MissingParam, Some("add `@param y`")),
+                (
+                    Rule::MissingReturn,
+                    Some("replace `@returns` with `@return`")
+                ),
+                (Rule::MissingReturn, Some("add `@return r`")),
+                (Rule::UnknownTag, Some("replace `@notive` with `@notice`")),
+                (Rule::UnknownTag, Some("replace `@params` with `@param`")),
+                (Rule::UnknownTag, Some("replace `@returns` with `@return`")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_insert_first_tag() {
+        let insert = |contents: &str| {
+            let mut parser = SolarParser::default();
+            let doc = parser
+                .parse_document(contents.as_bytes(), None::<std::path::PathBuf>, false)
+                .unwrap();
+            let span = doc.definitions[1].span().unwrap();
+            let edits = insert_first_tag(contents, &span, "@inheritdoc IA");
+            apply_fixes(
+                contents,
+                &[Fix {
+                    description: String::new(),
+                    edits,
+                }],
+            )
+        };
+        assert_eq!(
+            insert(
+                "contract Test is IA {
+    function foo() external {}
+}"
+            ),
+            "contract Test is IA {
+    /// @inheritdoc IA
+    function foo() external {}
+}"
+        );
+        assert_eq!(
+            insert(
+                "contract Test is IA {
+    /**
+     * @notice A function
+     */
+    function foo() external {}
+}"
+            ),
+            "contract Test is IA {
+    /**
+     * @inheritdoc IA
+     * @notice A function
+     */
+    function foo() external {}
+}"
+        );
+        assert_eq!(
+            insert(
+                "contract Test is IA {
+    /** @notice A function */
+    function foo() external {}
+}"
+            ),
+            "contract Test is IA {
+    /**
+     * @inheritdoc IA
+     * @notice A function
+     */
+    function foo() external {}
+}"
+        );
+    }
+
+    #[test]
+    fn test_populate_fixes() {
+        let contents = "contract Test {
+    /// @notice A function
+    function foo(uint256 a) internal {}
+}";
+        let mut parser = SolarParser::default();
+        let doc = parser
+            .parse_document(contents.as_bytes(), None::<std::path::PathBuf>, false)
+            .unwrap();
+        let def = doc
+            .definitions
+            .iter()
+            .find(|d| matches!(d, Definition::Function(_)))
+            .unwrap();
+        let mut items = vec![def.validate(&ValidationOptions::default())];
+        add_fixes(contents, def, &mut items[0]);
+        populate_fixes(contents, &mut items);
+        let fix = items[0].diags[0].fix.as_ref().unwrap();
+        assert_eq!(fix.description, "add `@param a`");
+        assert_eq!(fix.edits[0].span.start.line, 2);
+        assert_eq!(fix.edits[0].span.start.col_utf8, 0);
+        assert_eq!(fix.edits[0].text, "    /// @param a TODO\n");
+    }
+
+    #[test]
+    fn test_apply_overlapping() {
+        let source = "abcdef";
+        let index = |utf8| TextIndex {
+            utf8,
+            ..Default::default()
+        };
+        let fixes = [
+            Fix {
+                description: String::new(),
+                edits: vec![TextEdit {
+                    span: index(1)..index(3),
+                    text: "X".to_string(),
+                }],
+            },
+            Fix {
+                description: String::new(),
+                edits: vec![TextEdit {
+                    span: index(2)..index(4),
+                    text: "Y".to_string(),
+                }],
+            },
+            Fix {
+                description: String::new(),
+                edits: vec![TextEdit::insert(5, "Z".to_string())],
+            },
+        ];
+        assert_eq!(apply_fixes(source, &fixes), "aXdeZf");
+    }
+}
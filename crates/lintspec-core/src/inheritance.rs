@@ -0,0 +1,585 @@
+//! Inheritance relationships between contracts and interfaces
+//!
+//! The `@inheritdoc` tag must refer to a direct or indirect base of the contract or interface containing the
+//! documented item, and this base must declare a documented member with the same signature, otherwise there is no
+//! documentation to inherit. The [`Inheritance`] type records the bases and members of each contract and interface to
+//! check this.
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use derive_more::Display;
+
+use crate::{
+    definitions::{Definition, Parent},
+    fix::{Fix, insert_first_tag},
+    interner::{INTERNER, Symbol},
+    lint::{Diagnostic, ItemDiagnostics, Rule},
+    natspec::NatSpecKind,
+};
+
+/// The kind of a contract member which can be the target of `@inheritdoc`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display)]
+pub enum MemberKind {
+    #[display("function")]
+    Function,
+    #[display("variable")]
+    Variable,
+    #[display("event")]
+    Event,
+}
+
+/// A function, state variable or event declared in a contract or interface
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Member {
+    /// The kind of member
+    pub kind: MemberKind,
+
+    /// The name of the member
+    pub name: Symbol,
+
+    /// The normalized types of the parameters (empty for variables)
+    pub param_types: Vec<String>,
+
+    /// Whether the member has a `NatSpec` comment, which can be inherited
+    pub documented: bool,
+}
+
+impl Member {
+    /// Create a member from a definition, along with the name of its parent contract or interface
+    ///
+    /// Returns `None` if the definition is not a function, state variable or event declared in a contract or
+    /// interface.
+    #[must_use]
+    pub fn from_definition(definition: &Definition) -> Option<(Symbol, Self)> {
+        let (kind, parent, name, param_types) = match definition {
+            Definition::Function(def) => (
+                MemberKind::Function,
+                def.parent.as_ref(),
+                def.name,
+                def.param_types.clone(),
+            ),
+            Definition::Variable(def) => {
+                (MemberKind::Variable, def.parent.as_ref(), def.name, vec![])
+            }
+            Definition::Event(def) => (
+                MemberKind::Event,
+                def.parent.as_ref(),
+                def.name,
+                def.param_types.clone(),
+            ),
+            _ => return None,
+        };
+        let (Parent::Contract(parent) | Parent::Interface(parent)) = parent? else {
+            return None;
+        };
+        let member = Member {
+            kind,
+            name,
+            param_types,
+            documented: definition.natspec().is_some_and(|n| !n.items.is_empty()),
+        };
+        Some((INTERNER.get_or_intern(parent), member))
+    }
+
+    /// Check whether this member (declared in a base) can provide the documentation for `item`
+    ///
+    /// Functions and events must have the same name and parameter types. State variables can inherit the documentation
+    /// of a function (the getter) or of another variable with the same name.
+    #[must_use]
+    pub fn matches(&self, item: &Member) -> bool {
+        if self.name != item.name {
+            return false;
+        }
+        match item.kind {
+            MemberKind::Function | MemberKind::Event => {
+                self.kind == item.kind && self.param_types == item.param_types
+            }
+            MemberKind::Variable => {
+                matches!(self.kind, MemberKind::Function | MemberKind::Variable)
+            }
+        }
+    }
+
+    /// The signature of the member, as displayed in diagnostics
+    fn signature(&self) -> String {
+        let name = self.name.resolve_with(&INTERNER);
+        match self.kind {
+            MemberKind::Variable => name.to_string(),
+            kind => format!("{kind} {name}({})", self.param_types.join(",")),
+        }
+    }
+}
+
+/// The direct bases and the members of the known contracts and interfaces
+#[derive(Debug, Clone, Default)]
+pub struct Inheritance {
+    bases: HashMap<Symbol, Vec<Symbol>>,
+    members: HashMap<Symbol, Vec<Member>>,
+}
+
+impl Inheritance {
+    /// Record the bases and members of the contracts and interfaces in a list of definitions
+    ///
+    /// The contracts and interfaces which were already known are replaced.
+    pub fn extend<'a>(&mut self, definitions: impl IntoIterator<Item = &'a Definition>) {
+        let mut members: HashMap<Symbol, Vec<Member>> = HashMap::new();
+        for definition in definitions {
+            match definition {
+                Definition::Contract(def) => {
+                    self.bases.insert(def.name, def.bases.clone());
+                    members.entry(def.name).or_default();
+                }
+                Definition::Interface(def) => {
+                    self.bases.insert(def.name, def.bases.clone());
+                    members.entry(def.name).or_default();
+                }
+                def => {
+                    if let Some((parent, member)) = Member::from_definition(def) {
+                        members.entry(parent).or_default().push(member);
+                    }
+                }
+            }
+        }
+        self.members.extend(members);
+    }
+
+    /// Record the bases and members of the contracts and interfaces of another [`Inheritance`]
+    ///
+    /// The contracts and interfaces which were already known are replaced.
+    pub fn merge(&mut self, other: Inheritance) {
+        self.bases.extend(other.bases);
+        self.members.extend(other.members);
+    }
+
+    /// Check whether a known contract or interface inherits from a contract or interface which is unknown
+    ///
+    /// This is the case when a base is defined in another file which was not recorded.
+    #[must_use]
+    pub fn has_unknown_bases(&self) -> bool {
+        self.bases
+            .values()
+            .flatten()
+            .any(|base| !self.bases.contains_key(base))
+    }
+
+    /// Retrieve the members declared in a contract or interface (excluding the inherited ones)
+    ///
+    /// Returns `None` if the contract is unknown.
+    #[must_use]
+    pub fn members(&self, contract: Symbol) -> Option<&[Member]> {
+        self.members.get(&contract).map(Vec::as_slice)
+    }
+
+    /// Find the most specific base of `contract` which declares a documented member matching `member`
+    ///
+    /// The bases are visited in breadth-first order, starting with the last base in each inheritance list since it's
+    /// the most derived one. If `overrides` is not empty, only the bases it contains are considered (the inheritance
+    /// tree is still traversed to find them).
+    #[must_use]
+    pub fn inheritdoc_target(
+        &self,
+        contract: Symbol,
+        member: &Member,
+        overrides: &[Symbol],
+    ) -> Option<Symbol> {
+        let mut visited = HashSet::new();
+        let mut queue = VecDeque::from([contract]);
+        while let Some(current) = queue.pop_front() {
+            let Some(bases) = self.bases.get(&current) else {
+                continue;
+            };
+            for base in bases.iter().rev() {
+                if !visited.insert(*base) {
+                    continue;
+                }
+                if (overrides.is_empty() || overrides.contains(base))
+                    && self.members(*base).is_some_and(|members| {
+                        members.iter().any(|m| m.documented && m.matches(member))
+                    })
+                {
+                    return Some(*base);
+                }
+                queue.push_back(*base);
+            }
+        }
+        None
+    }
+
+    /// Check whether `base` is a direct or indirect base of `contract`
+    ///
+    /// Returns `None` if it can't be determined, because the bases of a contract in the inheritance tree are unknown
+    /// (for instance if it's defined in another file).
+    #[must_use]
+    pub fn is_base(&self, contract: Symbol, base: Symbol) -> Option<bool> {
+        let mut complete = true;
+        let mut visited = HashSet::new();
+        let mut stack = vec![contract];
+        while let Some(current) = stack.pop() {
+            if !visited.insert(current) {
+                continue;
+            }
+            let Some(bases) = self.bases.get(&current) else {
+                complete = false;
+                continue;
+            };
+            if bases.contains(&base) {
+                return Some(true);
+            }
+            stack.extend(bases);
+        }
+        complete.then_some(false)
+    }
+}
+
+/// Check that the `@inheritdoc` tags of a definition refer to a documented member of a base of its parent
+///
+/// A diagnostic is added for each `@inheritdoc` which is known to refer to a contract which is not a base, to a
+/// contract which doesn't declare a matching member, or to a member without documentation.
+pub(crate) fn check_inheritdoc(
+    definition: &Definition,
+    inheritance: &Inheritance,
+    item_diags: &mut ItemDiagnostics,
+) {
+    let Some(natspec) = definition.natspec() else {
+        return;
+    };
+    let Some(Parent::Contract(parent) | Parent::Interface(parent)) = &item_diags.parent else {
+        return;
+    };
+    let parent_name = INTERNER.get_or_intern(parent);
+    let member = Member::from_definition(definition).map(|(_, m)| m);
+    for item in &natspec.items {
+        let NatSpecKind::Inheritdoc { parent: target } = item.kind else {
+            continue;
+        };
+        let target_name = target.resolve_with(&INTERNER);
+        let message = if inheritance.is_base(parent_name, target) == Some(false) {
+            format!("@inheritdoc {target_name} is not a base of {parent}")
+        } else if let Some(member) = &member
+            && let Some(members) = inheritance.members(target)
+        {
+            match members.iter().find(|m| m.matches(member)) {
+                None => format!("{target_name} does not declare {}", member.signature()),
+                Some(m) if !m.documented => format!(
+                    "{target_name}.{} is not documented",
+                    m.name.resolve_with(&INTERNER)
+                ),
+                Some(_) => continue,
+            }
+        } else {
+            continue;
+        };
+        // the item's span is relative to the comment's start offset
+        let start = item.span.start.offset_by(item_diags.span.start);
+        let end = item.span.end.offset_by(item_diags.span.start);
+        item_diags.diags.push(Diagnostic::new(
+            Rule::InvalidInheritdoc,
+            start..end,
+            message,
+        ));
+    }
+}
+
+/// Suggest the `@inheritdoc` tag which is missing for a definition, with a fix which inserts it
+///
+/// The suggested contract is the most specific base of the parent which declares a documented member with the same
+/// signature (see [`Inheritance::inheritdoc_target`]).
+pub(crate) fn suggest_inheritdoc(
+    source: &str,
+    definition: &Definition,
+    inheritance: &Inheritance,
+    item_diags: &mut ItemDiagnostics,
+) {
+    let Some(diag) = item_diags
+        .diags
+        .iter_mut()
+        .find(|d| d.rule == Rule::MissingInheritdoc)
+    else {
+        return;
+    };
+    let Some((parent, member)) = Member::from_definition(definition) else {
+        return;
+    };
+    let overrides = match definition {
+        Definition::Function(def) => def.overrides.as_slice(),
+        Definition::Variable(def) => def.overrides.as_slice(),
+        _ => &[],
+    };
+    let Some(target) = inheritance.inheritdoc_target(parent, &member, overrides) else {
+        return;
+    };
+    let tag = format!("@inheritdoc {}", target.resolve_with(&INTERNER));
+    diag.message = format!("@inheritdoc is missing, add `{tag}`");
+    diag.fix = Some(Fix {
+        description: format!("add `{tag}`"),
+        edits: insert_first_tag(source, &item_diags.span, &tag),
+    });
+}
+
+#[cfg(test)]
+#[cfg(feature = "solar")]
+mod tests {
+    use similar_asserts::assert_eq;
+
+    use crate::{
+        fix::apply_fixes,
+        lint::{ValidationOptions, lint_source, lint_source_with_project},
+        parser::{Parse as _, solar::SolarParser},
+        project::Project,
+    };
+
+    use super::*;
+
+    fn inheritance(contents: &str) -> Inheritance {
+        let mut parser = SolarParser::default();
+        let doc = parser
+            .parse_document(contents.as_bytes(), None::<std::path::PathBuf>, false)
+            .unwrap();
+        let mut inheritance = Inheritance::default();
+        inheritance.extend(&doc.definitions);
+        inheritance
+    }
+
+    #[test]
+    fn test_is_base() {
+        let res = inheritance(
+            "interface IA {}
+            interface IB is IA {}
+            abstract contract C is IB {}
+            contract D is C, Lib.E {}",
+        );
+        let sym = |name: &str| INTERNER.get_or_intern(name);
+        assert_eq!(res.is_base(sym("IB"), sym("IA")), Some(true));
+        assert_eq!(res.is_base(sym("C"), sym("IA")), Some(true));
+        assert_eq!(res.is_base(sym("IA"), sym("IB")), Some(false));
+        assert_eq!(res.is_base(sym("C"), sym("D")), Some(false));
+        assert_eq!(res.is_base(sym("D"), sym("E")), Some(true));
+        assert_eq!(res.is_base(sym("D"), sym("IA")), Some(true));
+        // the bases of `E` are unknown
+        assert_eq!(res.is_base(sym("D"), sym("F")), None);
+    }
+
+    #[test]
+    fn test_check_inheritdoc() {
+        let source = "interface IA {
+    /// @notice A function
+    function foo() external;
+}
+
+interface IB {}
+
+contract C is IA {
+    /// @inheritdoc IA
+    function foo() external {}
+
+    /// @notice Some variable
+    /// @inheritdoc IB
+    uint256 public bar;
+}
+";
+        let diags = lint_source(
+            SolarParser::default(),
+            "C.sol",
+            source,
+            &ValidationOptions::default(),
+            false,
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(diags.items.len(), 1);
+        assert_eq!(diags.items[0].name, "bar");
+        assert_eq!(diags.items[0].diags.len(), 1);
+        let diag = &diags.items[0].diags[0];
+        assert_eq!(diag.rule, Rule::InvalidInheritdoc);
+        assert_eq!(diag.message, "@inheritdoc IB is not a base of C");
+        assert_eq!(diag.span.start.line, 12);
+        assert_eq!(diag.span.start.col_utf8, 8);
+        assert_eq!(diag.span.end.col_utf8, 22);
+    }
+
+    #[test]
+    fn test_check_inheritdoc_member() {
+        let source = "interface IA {
+    /// @notice Foo
+    function foo(uint256 a) external;
+
+    function bar() external;
+
+    /// @notice Baz
+    function baz() external view returns (uint256);
+}
+
+contract C is IA {
+    /// @inheritdoc IA
+    function foo(uint a) external {}
+
+    /// @inheritdoc IA
+    function foo(address a) external {}
+
+    /// @inheritdoc IA
+    function bar() external {}
+
+    /// @inheritdoc IA
+    uint256 public baz;
+
+    /// @inheritdoc IA
+    uint256 public qux;
+}
+";
+        let diags = lint_source(
+            SolarParser::default(),
+            "C.sol",
+            source,
+            &ValidationOptions::default(),
+            false,
+        )
+        .unwrap()
+        .unwrap();
+        let messages: Vec<_> = diags
+            .items
+            .iter()
+            .flat_map(|i| &i.diags)
+            .filter(|d| d.rule == Rule::InvalidInheritdoc)
+            .map(|d| d.message.as_str())
+            .collect();
+        assert_eq!(
+            messages,
+            vec![
+                "IA does not declare function foo(address)",
+                "IA.bar is not documented",
+                "IA does not declare qux",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_suggest_inheritdoc() {
+        let source = "interface IA {
+    /// @notice Foo
+    function foo(uint256 a) external;
+}
+
+interface IB is IA {
+    /// @notice Foo from IB
+    function foo(uint256 a) external;
+
+    function baz() external;
+}
+
+contract C is IB {
+    function foo(uint256 a) external {}
+
+    /**
+     * @notice Bar
+     */
+    function bar() external {}
+
+    function baz() external {}
+}
+
+contract D is IA, IB {
+    /// @notice Foo
+    function foo(uint256 a) external override(IA) {}
+}
+";
+        let diags = lint_source(
+            SolarParser::default(),
+            "C.sol",
+            source,
+            &ValidationOptions::default(),
+            false,
+        )
+        .unwrap()
+        .unwrap();
+        let inheritdoc: Vec<_> = diags
+            .items
+            .iter()
+            .flat_map(|i| &i.diags)
+            .filter(|d| d.rule == Rule::MissingInheritdoc)
+            .collect();
+        let messages: Vec<_> = inheritdoc.iter().map(|d| d.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "@inheritdoc is missing, add `@inheritdoc IB`",
+                "@inheritdoc is missing",
+                "@inheritdoc is missing",
+                "@inheritdoc is missing, add `@inheritdoc IA`",
+            ]
+        );
+        let fixed = apply_fixes(source, inheritdoc.iter().filter_map(|d| d.fix.as_ref()));
+        assert!(fixed.contains(
+            "contract C is IB {
+    /// @inheritdoc IB
+    function foo(uint256 a) external {}"
+        ));
+        assert!(fixed.contains(
+            "    /// @inheritdoc IA
+    /// @notice Foo
+    function foo(uint256 a) external override(IA) {}"
+        ));
+    }
+
+    #[test]
+    fn test_check_inheritdoc_unknown_base() {
+        // `Base` is defined in another file, so `IB` could be one of its bases
+        let source = "import {Base} from './Base.sol';
+
+contract C is Base {
+    /// @inheritdoc IB
+    function foo() external {}
+}
+";
+        let diags = lint_source(
+            SolarParser::default(),
+            "C.sol",
+            source,
+            &ValidationOptions::default(),
+            false,
+        )
+        .unwrap();
+        assert!(diags.is_none(), "{diags:#?}");
+    }
+
+    #[test]
+    fn test_check_inheritdoc_imported() {
+        let dir = temp_dir::TempDir::new().unwrap();
+        std::fs::write(
+            dir.path().join("Base.sol"),
+            "import './IA.sol';\ninterface IB {}\ncontract Base is IA {}",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("IA.sol"),
+            "interface IA {\n/// @notice Foo\nfunction foo() external;\n}",
+        )
+        .unwrap();
+        let project = Project::load(dir.path());
+        let source = "import {Base, IB} from './Base.sol';
+
+contract C is Base {
+    /// @inheritdoc IA
+    function foo() external {}
+
+    /// @inheritdoc IB
+    function bar() external {}
+}
+";
+        let diags = lint_source_with_project(
+            SolarParser::default(),
+            dir.path().join("C.sol"),
+            source,
+            &ValidationOptions::default(),
+            &project,
+            false,
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(diags.items.len(), 1);
+        assert_eq!(diags.items[0].name, "bar");
+        assert_eq!(
+            diags.items[0].diags[0].message,
+            "@inheritdoc IB is not a base of C"
+        );
+    }
+}
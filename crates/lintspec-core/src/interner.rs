@@ -7,7 +7,7 @@ pub static INTERNER: LazyLock<Interner> = LazyLock::new(Interner::new);
 /// The reference to an interned string from the [`INTERNER`].
 ///
 /// This type can be compared to rapidly check for string equality.
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub struct Symbol(inturn::Symbol);
 
 impl Symbol {
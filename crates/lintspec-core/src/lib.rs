@@ -1,14 +1,26 @@
 #![cfg_attr(docsrs, feature(doc_cfg))]
 #![doc = include_str!(concat!("../", std::env!("CARGO_PKG_README")))]
+pub mod baseline;
+pub mod cache;
+pub mod changes;
 pub mod config;
+pub mod coverage;
 pub mod definitions;
 pub mod error;
+pub mod export;
 pub mod files;
+pub mod fix;
+pub mod inheritance;
 pub mod interner;
 pub mod lint;
+pub mod markdown;
 pub mod natspec;
+pub mod overrides;
 pub mod parser;
 pub(crate) mod prelude;
+pub mod project;
+pub mod sarif;
+pub mod suppression;
 pub mod textindex;
 
 #[cfg_attr(docsrs, doc(cfg(feature = "slang")))]
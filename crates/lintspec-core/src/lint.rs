@@ -3,20 +3,29 @@
 //! The [`lint`] function parsers the source file and contained items, validates them according to the configured
 //! rules and emits a list of diagnostics, grouped by source item.
 use std::{
-    fs::File,
-    io,
+    collections::BTreeMap,
+    env, fs, io,
     path::{Path, PathBuf},
 };
 
-use serde::Serialize;
+use derive_more::{Display, FromStr};
+use serde::{Deserialize, Serialize};
 
 use crate::{
-    config::{Config, ContractRules, FunctionConfig, Req, VariableConfig, WithParamsRules},
-    definitions::{Identifier, ItemType, Parent},
+    config::{
+        Config, ContractRules, FilterConfig, FunctionConfig, Req, Severity, SeverityConfig,
+        VariableConfig, WithParamsRules,
+    },
+    definitions::{Definition, Identifier, ItemType, Parent},
     error::{ErrorKind, Result},
+    fix::{Fix, add_fixes, populate_fixes},
+    inheritance::{Inheritance, check_inheritdoc, suggest_inheritdoc},
     interner::INTERNER,
-    natspec::{NatSpec, NatSpecKind},
+    natspec::{NatSpec, NatSpecKind, suggest_tag},
+    overrides::PathOverrides,
     parser::{DocumentId, Parse, ParsedDocument},
+    project::Project,
+    suppression::Suppressions,
     textindex::TextRange,
 };
 
@@ -69,27 +78,242 @@ impl ItemDiagnostics {
         source_name: &str,
     ) -> std::result::Result<(), io::Error> {
         writeln!(f, "{source_name}:{}", self.span.start)?;
-        if let Some(parent) = &self.parent {
-            writeln!(f, "{} {}.{}", self.item_type, parent, self.name)?;
-        } else {
-            writeln!(f, "{} {}", self.item_type, self.name)?;
+        // some items (like stray comments) have no name
+        match (&self.parent, self.name) {
+            (Some(parent), "") => writeln!(f, "{} {}", self.item_type, parent)?,
+            (Some(parent), name) => writeln!(f, "{} {}.{}", self.item_type, parent, name)?,
+            (None, "") => writeln!(f, "{}", self.item_type)?,
+            (None, name) => writeln!(f, "{} {}", self.item_type, name)?,
         }
         for diag in &self.diags {
-            writeln!(f, "  {}", diag.message)?;
+            if diag.severity.is_error() {
+                writeln!(f, "  {}", diag.message)?;
+            } else {
+                writeln!(f, "  {}: {}", diag.severity, diag.message)?;
+            }
         }
         writeln!(f)
     }
+
+    /// The highest severity among the diagnostics of this item
+    #[must_use]
+    pub fn severity(&self) -> Severity {
+        let mut severity = Severity::Info;
+        for diag in &self.diags {
+            match diag.severity {
+                Severity::Error => return Severity::Error,
+                Severity::Warning => severity = Severity::Warning,
+                Severity::Info => {}
+            }
+        }
+        severity
+    }
 }
 
 /// A single diagnostic related to `NatSpec`.
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Diagnostic {
     /// The span (text range) related to the diagnostic
     ///
     /// If related to the item's `NatSpec` as a whole, this is the same as the item's span.
     /// For a missing param or return `NatSpec`, this is the span of the param or return item.
     pub span: TextRange,
+
+    /// The rule which was violated
+    pub rule: Rule,
+
+    /// The severity of the diagnostic
+    pub severity: Severity,
+
+    /// A human-readable message describing the problem
     pub message: String,
+
+    /// The name of the param or return value which the diagnostic refers to, if any
+    ///
+    /// For unnamed return values, this is the position of the return value (e.g. `#1`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub param: Option<String>,
+
+    /// A fix which can be applied to the source to resolve the problem, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fix: Option<Fix>,
+}
+
+impl Diagnostic {
+    /// Create a new diagnostic without fix
+    ///
+    /// The severity is the default one, it's replaced with the configured severity of the rule by [`lint`].
+    #[must_use]
+    pub fn new(rule: Rule, span: TextRange, message: impl Into<String>) -> Self {
+        Self {
+            span,
+            rule,
+            severity: Severity::default(),
+            message: message.into(),
+            param: None,
+            fix: None,
+        }
+    }
+
+    /// Set the name of the param or return value which the diagnostic refers to
+    #[must_use]
+    pub fn with_param(mut self, param: impl Into<String>) -> Self {
+        self.param = Some(param.into());
+        self
+    }
+}
+
+/// The identifier of a lint rule
+///
+/// Each [`Diagnostic`] refers to exactly one rule. The identifiers are stable and can be used by downstream tooling to
+/// know which problem was found without relying on the diagnostic message.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    Display,
+    FromStr,
+)]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+#[serde(rename_all = "kebab-case")]
+#[display(rename_all = "kebab-case")]
+#[from_str(rename_all = "kebab-case")]
+pub enum Rule {
+    /// A `@param` is missing for a param or member
+    MissingParam,
+    /// A `@param` refers to a param or member which doesn't exist
+    ExtraParam,
+    /// A param or member is documented more than once
+    DuplicateParam,
+    /// A `@param` is present but the rule forbids it
+    ForbiddenParam,
+    /// A `@return` is missing for a return value
+    MissingReturn,
+    /// There are more unnamed `@return` than unnamed return values
+    ExtraReturn,
+    /// A named return value is documented more than once
+    DuplicateReturn,
+    /// A `@return` is present but the rule forbids it
+    ForbiddenReturn,
+    /// The `@notice` is missing
+    MissingNotice,
+    /// A `@notice` is present but the rule forbids it
+    ForbiddenNotice,
+    /// The `@dev` is missing
+    MissingDev,
+    /// A `@dev` is present but the rule forbids it
+    ForbiddenDev,
+    /// The `@title` is missing
+    MissingTitle,
+    /// A `@title` is present but the rule forbids it
+    ForbiddenTitle,
+    /// The `@author` is missing
+    MissingAuthor,
+    /// An `@author` is present but the rule forbids it
+    ForbiddenAuthor,
+    /// Neither `@notice` nor `@dev` is present (with the `notice_or_dev` option)
+    MissingNoticeOrDev,
+    /// The `@inheritdoc` is missing
+    MissingInheritdoc,
+    /// The `@inheritdoc` refers to a contract which is not a base of the parent contract, or which doesn't declare a
+    /// documented member with the same signature
+    InvalidInheritdoc,
+    /// A required `@custom:<tag>` is missing
+    MissingCustom,
+    /// A `@custom:<tag>` is present but the rule forbids it
+    ForbiddenCustom,
+    /// A `@custom:<tag>` is not in the list of known custom tags
+    UnknownCustom,
+    /// A word starting with `@` at the beginning of a line is not a valid tag
+    UnknownTag,
+    /// A doc-comment is not attached to any source item, or is separated from it by an empty line
+    OrphanComment,
+    /// A regular comment contains `NatSpec` tags, which are ignored by the compiler
+    TagInComment,
+    /// A suppression comment refers to a rule which doesn't exist
+    UnknownRule,
+    /// The `NatSpec` comment could not be parsed
+    ParsingError,
+}
+
+impl Rule {
+    /// The list of all rules
+    pub const ALL: &[Rule] = &[
+        Rule::MissingParam,
+        Rule::ExtraParam,
+        Rule::DuplicateParam,
+        Rule::ForbiddenParam,
+        Rule::MissingReturn,
+        Rule::ExtraReturn,
+        Rule::DuplicateReturn,
+        Rule::ForbiddenReturn,
+        Rule::MissingNotice,
+        Rule::ForbiddenNotice,
+        Rule::MissingDev,
+        Rule::ForbiddenDev,
+        Rule::MissingTitle,
+        Rule::ForbiddenTitle,
+        Rule::MissingAuthor,
+        Rule::ForbiddenAuthor,
+        Rule::MissingNoticeOrDev,
+        Rule::MissingInheritdoc,
+        Rule::InvalidInheritdoc,
+        Rule::MissingCustom,
+        Rule::ForbiddenCustom,
+        Rule::UnknownCustom,
+        Rule::UnknownTag,
+        Rule::OrphanComment,
+        Rule::TagInComment,
+        Rule::UnknownRule,
+        Rule::ParsingError,
+    ];
+
+    /// A short description of the rule
+    #[must_use]
+    pub fn description(&self) -> &'static str {
+        match self {
+            Rule::MissingParam => "a param or member is not documented with `@param`",
+            Rule::ExtraParam => "a `@param` refers to a param or member which does not exist",
+            Rule::DuplicateParam => "a param or member is documented more than once",
+            Rule::ForbiddenParam => "`@param` is present although it is forbidden",
+            Rule::MissingReturn => "a return value is not documented with `@return`",
+            Rule::ExtraReturn => "there are more unnamed `@return` than unnamed return values",
+            Rule::DuplicateReturn => "a named return value is documented more than once",
+            Rule::ForbiddenReturn => "`@return` is present although it is forbidden",
+            Rule::MissingNotice => "`@notice` is required but missing",
+            Rule::ForbiddenNotice => "`@notice` is present although it is forbidden",
+            Rule::MissingDev => "`@dev` is required but missing",
+            Rule::ForbiddenDev => "`@dev` is present although it is forbidden",
+            Rule::MissingTitle => "`@title` is required but missing",
+            Rule::ForbiddenTitle => "`@title` is present although it is forbidden",
+            Rule::MissingAuthor => "`@author` is required but missing",
+            Rule::ForbiddenAuthor => "`@author` is present although it is forbidden",
+            Rule::MissingNoticeOrDev => "`@notice` or `@dev` is required but both are missing",
+            Rule::MissingInheritdoc => "`@inheritdoc` is required but missing",
+            Rule::InvalidInheritdoc => {
+                "`@inheritdoc` doesn't refer to a documented member of a base of the parent contract"
+            }
+            Rule::MissingCustom => "a `@custom:<tag>` is required but missing",
+            Rule::ForbiddenCustom => "a `@custom:<tag>` is present although it is forbidden",
+            Rule::UnknownCustom => "a `@custom:<tag>` is not in the list of known custom tags",
+            Rule::UnknownTag => {
+                "a word starting with `@` at the beginning of a line is not a valid tag"
+            }
+            Rule::OrphanComment => {
+                "a doc-comment is not attached to any source item, or is separated from it by an empty line"
+            }
+            Rule::TagInComment => "a regular comment contains `NatSpec` tags",
+            Rule::UnknownRule => "a suppression comment refers to a rule which doesn't exist",
+            Rule::ParsingError => "the `NatSpec` comment could not be parsed",
+        }
+    }
 }
 
 /// Lint a file by identifying `NatSpec` problems.
@@ -98,51 +322,241 @@ pub struct Diagnostic {
 /// provided, and a compatible Solidity version will be inferred from the first version pragma statement (if any) to
 /// inform the parsing. [`ValidationOptions`] can be provided to control whether some of the lints get reported.
 /// The `keep_contents` parameter controls if the returned [`FileDiagnostics`] contains the original source code.
+///
+/// Diagnostics which are disabled by a suppression comment in the source (see [`crate::suppression`]) are not
+/// reported. Missing and extra `@param` and `@return` diagnostics contain a [`Fix`] which can be applied to the source.
+///
+/// The imported files are not parsed, see [`lint_with_project`] to validate the `@inheritdoc` tags which refer to
+/// contracts from other files.
 pub fn lint(
+    parser: impl Parse,
+    path: impl AsRef<Path>,
+    options: &ValidationOptions,
+    keep_contents: bool,
+) -> Result<Option<FileDiagnostics>> {
+    let source = fs::read_to_string(&path).map_err(|err| ErrorKind::IOError {
+        path: path.as_ref().to_path_buf(),
+        err,
+    })?;
+    lint_source(parser, path, &source, options, keep_contents)
+}
+
+/// Lint a file which is part of a [`Project`] by identifying `NatSpec` problems.
+///
+/// This is the same as [`lint`], except that the files imported by the source can be parsed to retrieve the bases and
+/// members of the contracts from other files, which are used to validate the `@inheritdoc` tags. The imports are only
+/// parsed if a contract inherits from a contract which is not defined in the file, and if an item has an
+/// `@inheritdoc` tag or is missing one.
+pub fn lint_with_project(
+    parser: impl Parse,
+    path: impl AsRef<Path>,
+    options: &ValidationOptions,
+    project: &Project,
+    keep_contents: bool,
+) -> Result<Option<FileDiagnostics>> {
+    let source = fs::read_to_string(&path).map_err(|err| ErrorKind::IOError {
+        path: path.as_ref().to_path_buf(),
+        err,
+    })?;
+    lint_source_with_project(parser, path, &source, options, project, keep_contents)
+}
+
+/// Lint Solidity source code read from a reader (e.g. stdin) by identifying `NatSpec` problems.
+///
+/// The whole contents of the reader are read before linting with [`lint_source`]. The `path` is used to identify the
+/// source in the diagnostics, it doesn't need to exist on the filesystem.
+pub fn lint_reader(
+    parser: impl Parse,
+    mut reader: impl io::Read,
+    path: impl AsRef<Path>,
+    options: &ValidationOptions,
+    keep_contents: bool,
+) -> Result<Option<FileDiagnostics>> {
+    let mut source = String::new();
+    reader
+        .read_to_string(&mut source)
+        .map_err(|err| ErrorKind::IOError {
+            path: path.as_ref().to_path_buf(),
+            err,
+        })?;
+    lint_source(parser, path, &source, options, keep_contents)
+}
+
+/// Lint Solidity source code by identifying `NatSpec` problems.
+///
+/// This is the same as [`lint`], except that the source code is provided directly instead of being read from the
+/// filesystem (for example the unsaved contents of an editor buffer). The `path` is used to identify the file in the
+/// diagnostics.
+pub fn lint_source(
     mut parser: impl Parse,
     path: impl AsRef<Path>,
+    source: &str,
     options: &ValidationOptions,
     keep_contents: bool,
 ) -> Result<Option<FileDiagnostics>> {
-    fn inner(
-        path: &Path,
-        document: ParsedDocument,
-        options: &ValidationOptions,
-    ) -> Option<FileDiagnostics> {
-        let mut items: Vec<_> = document
-            .definitions
-            .into_iter()
-            .filter_map(|item| {
-                let mut item_diags = item.validate(options);
-                if item_diags.diags.is_empty() {
-                    None
-                } else {
-                    item_diags.diags.sort_unstable_by_key(|d| d.span.start);
-                    Some(item_diags)
-                }
-            })
-            .collect();
-        if items.is_empty() {
+    let document = parser.parse_document(source.as_bytes(), Some(&path), keep_contents)?;
+    Ok(lint_document(
+        path.as_ref(),
+        source,
+        document,
+        Inheritance::default,
+        options,
+    ))
+}
+
+/// Lint Solidity source code which is part of a [`Project`] by identifying `NatSpec` problems.
+///
+/// This is the same as [`lint_with_project`], except that the source code is provided directly. The `path` is used to
+/// identify the file in the diagnostics and to resolve the relative imports.
+pub fn lint_source_with_project(
+    mut parser: impl Parse,
+    path: impl AsRef<Path>,
+    source: &str,
+    options: &ValidationOptions,
+    project: &Project,
+    keep_contents: bool,
+) -> Result<Option<FileDiagnostics>> {
+    let document = parser.parse_document(source.as_bytes(), Some(&path), keep_contents)?;
+    let imports = document.imports.clone();
+    Ok(lint_document(
+        path.as_ref(),
+        source,
+        document,
+        || project.inheritance(&parser, &path, &imports),
+        options,
+    ))
+}
+
+/// Validate the definitions of a parsed document and compute the fixes
+///
+/// The severity of the diagnostics is set according to the [`ValidationOptions`], this is the only place where it's
+/// done. The `imported` function returns the definitions from the imported files, it's only called if they are needed (see
+/// [`validate_definitions`]). Returns `None` if there is no diagnostic.
+pub(crate) fn lint_document(
+    path: &Path,
+    source: &str,
+    document: ParsedDocument,
+    imported: impl FnOnce() -> Inheritance,
+    options: &ValidationOptions,
+) -> Option<FileDiagnostics> {
+    let resolved = options.overrides.resolve(path);
+    let options = resolved.as_deref().unwrap_or(options);
+    let suppressions = Suppressions::new(source, &document.comments);
+    let mut items: Vec<_> = validate_definitions(
+        source,
+        document.definitions,
+        imported,
+        &suppressions,
+        options,
+    )
+    .into_iter()
+    .map(|(item, mut item_diags)| {
+        add_fixes(source, &item, &mut item_diags);
+        item_diags
+    })
+    .chain(suppressions.unknown_rules(source))
+    .filter_map(|mut item_diags| {
+        if item_diags.diags.is_empty() {
             return None;
         }
-        items.sort_unstable_by_key(|i| i.span.start);
-        Some(FileDiagnostics {
-            path: path.to_path_buf(),
-            document_id: document.id,
-            items,
+        for diag in &mut item_diags.diags {
+            diag.severity = options.severity.get(item_diags.item_type, diag.rule);
+        }
+        item_diags.diags.sort_unstable_by_key(|d| d.span.start);
+        Some(item_diags)
+    })
+    .collect();
+    if items.is_empty() {
+        return None;
+    }
+    items.sort_unstable_by_key(|i| i.span.start);
+    populate_fixes(source, &mut items);
+    Some(FileDiagnostics {
+        path: path.to_path_buf(),
+        document_id: document.id,
+        items,
+    })
+}
+
+/// Validate the definitions of a document, including the ones which have no diagnostic
+///
+/// The items which are excluded by the name filters are removed, as well as the diagnostics which are disabled by a
+/// suppression comment (see [`Suppressions`]). No fix is computed.
+///
+/// The `imported` function returns the definitions from the imported files. Parsing the imports can be slow, so it's
+/// only called if a contract inherits from a contract which is not defined in the document, and if an item has an
+/// `@inheritdoc` tag or is missing one.
+pub(crate) fn validate_definitions(
+    source: &str,
+    definitions: Vec<Definition>,
+    imported: impl FnOnce() -> Inheritance,
+    suppressions: &Suppressions,
+    options: &ValidationOptions,
+) -> Vec<(Definition, ItemDiagnostics)> {
+    // the span of all items, used to find the item targeted by a `lintspec-disable-next-item` comment
+    let item_spans: Vec<_> = if suppressions.is_empty() {
+        Vec::new()
+    } else {
+        let mut spans: Vec<_> = definitions
+            .iter()
+            .filter_map(|d| d.span().map(|s| s.start.utf8..s.end.utf8))
+            .collect();
+        spans.sort_unstable_by_key(|s| s.start);
+        spans
+    };
+    let mut inheritance = Inheritance::default();
+    inheritance.extend(&definitions);
+    let mut items: Vec<_> = definitions
+        .into_iter()
+        .filter_map(|item| {
+            let item_diags = item.validate(options);
+            if !matches!(
+                item_diags.item_type,
+                ItemType::ParsingError | ItemType::Comment
+            ) && !options.filters.is_checked(
+                item_diags.item_type,
+                item_diags.parent.as_ref(),
+                item_diags.name,
+            ) {
+                return None;
+            }
+            Some((item, item_diags))
+        })
+        .collect();
+    if inheritance.has_unknown_bases()
+        && items.iter().any(|(item, item_diags)| {
+            item.natspec().is_some_and(|n| {
+                n.items
+                    .iter()
+                    .any(|i| matches!(i.kind, NatSpecKind::Inheritdoc { .. }))
+            }) || item_diags
+                .diags
+                .iter()
+                .any(|d| d.rule == Rule::MissingInheritdoc)
         })
+    {
+        // the definitions of the document replace the imported ones with the same name
+        let mut full = imported();
+        full.merge(inheritance);
+        inheritance = full;
     }
-    let file = File::open(&path).map_err(|err| ErrorKind::IOError {
-        path: path.as_ref().to_path_buf(),
-        err,
-    })?;
-    let document = parser.parse_document(file, Some(&path), keep_contents)?;
-    Ok(inner(path.as_ref(), document, options))
+    for (item, item_diags) in &mut items {
+        check_inheritdoc(item, &inheritance, item_diags);
+        suggest_inheritdoc(source, item, &inheritance, item_diags);
+        if !suppressions.is_empty() {
+            let item_start = item_diags.span.start.utf8;
+            item_diags.diags.retain(|d| {
+                !suppressions.is_suppressed(d.rule, item_start, d.span.start.utf8, &item_spans)
+            });
+        }
+    }
+    items
 }
 
 /// Validation options to control which lints generate a diagnostic
-#[derive(Debug, Clone, PartialEq, Eq, bon::Builder)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, bon::Builder)]
 #[non_exhaustive]
+#[expect(clippy::struct_excessive_bools)]
 pub struct ValidationOptions {
     /// Whether public and external functions should have an `@inheritdoc`
     #[builder(default = true)]
@@ -156,6 +570,14 @@ pub struct ValidationOptions {
     #[builder(default)]
     pub notice_or_dev: bool,
 
+    /// The custom tags which are known in addition to the ones which have a rule, or empty to allow all custom tags
+    #[builder(default)]
+    pub known_custom_tags: Vec<String>,
+
+    /// Whether doc-comments which are not attached to any item and regular comments with `NatSpec` tags are reported
+    #[builder(default)]
+    pub stray_comments: bool,
+
     /// Validation options for contracts
     #[builder(default)]
     pub contracts: ContractRules,
@@ -199,6 +621,18 @@ pub struct ValidationOptions {
     /// Validation options for state variables
     #[builder(default)]
     pub variables: VariableConfig,
+
+    /// Severity of the diagnostics for each rule
+    #[builder(default)]
+    pub severity: SeverityConfig,
+
+    /// Filters on the names of the items which are checked
+    #[builder(default)]
+    pub filters: FilterConfig,
+
+    /// The options which apply to some files only
+    #[builder(default)]
+    pub overrides: PathOverrides,
 }
 
 impl Default for ValidationOptions {
@@ -211,6 +645,8 @@ impl Default for ValidationOptions {
             inheritdoc: true,
             inheritdoc_override: false,
             notice_or_dev: false,
+            known_custom_tags: Vec::default(),
+            stray_comments: false,
             contracts: ContractRules::default(),
             interfaces: ContractRules::default(),
             libraries: ContractRules::default(),
@@ -222,6 +658,9 @@ impl Default for ValidationOptions {
             modifiers: WithParamsRules::required(),
             structs: WithParamsRules::default(),
             variables: VariableConfig::default(),
+            severity: SeverityConfig::default(),
+            filters: FilterConfig::default(),
+            overrides: PathOverrides::default(),
         }
     }
 }
@@ -229,10 +668,13 @@ impl Default for ValidationOptions {
 /// Create a [`ValidationOptions`] from a [`Config`]
 impl From<Config> for ValidationOptions {
     fn from(value: Config) -> Self {
+        let overrides = path_overrides(&value);
         Self {
             inheritdoc: value.lintspec.inheritdoc,
             inheritdoc_override: value.lintspec.inheritdoc_override,
             notice_or_dev: value.lintspec.notice_or_dev,
+            known_custom_tags: value.lintspec.known_custom_tags,
+            stray_comments: value.lintspec.stray_comments,
             contracts: value.contracts,
             interfaces: value.interfaces,
             libraries: value.libraries,
@@ -244,6 +686,9 @@ impl From<Config> for ValidationOptions {
             modifiers: value.modifiers,
             structs: value.structs,
             variables: value.variables,
+            severity: value.severity,
+            filters: value.filters,
+            overrides,
         }
     }
 }
@@ -255,6 +700,8 @@ impl From<&Config> for ValidationOptions {
             inheritdoc: value.lintspec.inheritdoc,
             inheritdoc_override: value.lintspec.inheritdoc_override,
             notice_or_dev: value.lintspec.notice_or_dev,
+            known_custom_tags: value.lintspec.known_custom_tags.clone(),
+            stray_comments: value.lintspec.stray_comments,
             contracts: value.contracts.clone(),
             interfaces: value.interfaces.clone(),
             libraries: value.libraries.clone(),
@@ -266,10 +713,24 @@ impl From<&Config> for ValidationOptions {
             modifiers: value.modifiers.clone(),
             structs: value.structs.clone(),
             variables: value.variables.clone(),
+            severity: value.severity.clone(),
+            filters: value.filters.clone(),
+            overrides: path_overrides(value),
         }
     }
 }
 
+/// Compile the overrides of a config, with patterns relative to the current directory
+fn path_overrides(config: &Config) -> PathOverrides {
+    if config.overrides.is_empty() {
+        return PathOverrides::default();
+    }
+    let root = env::current_dir()
+        .and_then(dunce::canonicalize)
+        .unwrap_or_default();
+    PathOverrides::new(config, root)
+}
+
 /// A trait implemented by [`Definition`][crate::definitions::Definition] to validate the related `NatSpec`
 pub trait Validate {
     /// Validate the definition and extract the relevant diagnostics
@@ -329,10 +790,11 @@ impl CheckParams<'_> {
         if let Some(natspec) = self.natspec
             && natspec.has_param()
         {
-            out.push(Diagnostic {
-                span: self.default_span.clone(),
-                message: "@param is forbidden".to_string(),
-            });
+            out.push(Diagnostic::new(
+                Rule::ForbiddenParam,
+                self.default_span.clone(),
+                "@param is forbidden",
+            ));
         }
     }
 
@@ -341,10 +803,12 @@ impl CheckParams<'_> {
         self.params.iter().filter_map(|p| {
             p.name.map(|name| {
                 let name = INTERNER.resolve(name);
-                Diagnostic {
-                    span: p.span.clone(),
-                    message: format!("@param {name} is missing"),
-                }
+                Diagnostic::new(
+                    Rule::MissingParam,
+                    p.span.clone(),
+                    format!("@param {name} is missing"),
+                )
+                .with_param(name)
             })
         })
     }
@@ -367,13 +831,17 @@ impl CheckParams<'_> {
                         None
                     } else {
                         // the item's span is relative to the comment's start offset
-                        let span_start = self.default_span.start + item.span.start;
-                        let span_end = self.default_span.start + item.span.end;
+                        let span_start = item.span.start.offset_by(self.default_span.start);
+                        let span_end = item.span.end.offset_by(self.default_span.start);
                         let name = INTERNER.resolve(name);
-                        Some(Diagnostic {
-                            span: span_start..span_end,
-                            message: format!("extra @param {name}"),
-                        })
+                        Some(
+                            Diagnostic::new(
+                                Rule::ExtraParam,
+                                span_start..span_end,
+                                format!("extra @param {name}"),
+                            )
+                            .with_param(name),
+                        )
                     }
                 })
             })
@@ -386,15 +854,23 @@ impl CheckParams<'_> {
         self.counts(natspec).filter_map(|(param, count)| {
             let name = param.name.map_or("unnamed_param", |n| INTERNER.resolve(n));
             match count {
-                0 => Some(Diagnostic {
-                    span: param.span.clone(),
-                    message: format!("@param {name} is missing"),
-                }),
+                0 => Some(
+                    Diagnostic::new(
+                        Rule::MissingParam,
+                        param.span.clone(),
+                        format!("@param {name} is missing"),
+                    )
+                    .with_param(name),
+                ),
                 1 => None,
-                2.. => Some(Diagnostic {
-                    span: param.span.clone(),
-                    message: format!("@param {name} is present more than once"),
-                }),
+                2.. => Some(
+                    Diagnostic::new(
+                        Rule::DuplicateParam,
+                        param.span.clone(),
+                        format!("@param {name} is present more than once"),
+                    )
+                    .with_param(name),
+                ),
             }
         })
     }
@@ -472,27 +948,27 @@ impl CheckReturns<'_> {
         if let Some(natspec) = self.natspec
             && natspec.has_return()
         {
-            out.push(Diagnostic {
-                span: self.default_span.clone(),
-                message: "@return is forbidden".to_string(),
-            });
+            out.push(Diagnostic::new(
+                Rule::ForbiddenReturn,
+                self.default_span.clone(),
+                "@return is forbidden",
+            ));
         }
     }
 
     /// Generate missing return diags if `@return` is required and there's no natspec
     fn missing_diags(&self) -> impl Iterator<Item = Diagnostic> {
         self.returns.iter().enumerate().map(|(idx, r)| {
-            let message = if let Some(name) = r.name {
+            if let Some(name) = r.name {
                 let name = INTERNER.resolve(name);
-                format!("@return {name} is missing")
-            } else if self.is_var {
-                "@return is missing".to_string()
+                Diagnostic::new(
+                    Rule::MissingReturn,
+                    r.span.clone(),
+                    format!("@return {name} is missing"),
+                )
+                .with_param(name)
             } else {
-                format!("@return missing for unnamed return #{}", idx + 1)
-            };
-            Diagnostic {
-                span: r.span.clone(),
-                message,
+                self.unnamed_missing_diag(idx, r)
             }
         })
     }
@@ -500,15 +976,23 @@ impl CheckReturns<'_> {
     /// Check a named return's `NatSpec` count
     fn named_count_diag(natspec: &NatSpec, ret: &Identifier, name: &str) -> Option<Diagnostic> {
         match natspec.count_return(ret) {
-            0 => Some(Diagnostic {
-                span: ret.span.clone(),
-                message: format!("@return {name} is missing"),
-            }),
+            0 => Some(
+                Diagnostic::new(
+                    Rule::MissingReturn,
+                    ret.span.clone(),
+                    format!("@return {name} is missing"),
+                )
+                .with_param(name),
+            ),
             1 => None,
-            2.. => Some(Diagnostic {
-                span: ret.span.clone(),
-                message: format!("@return {name} is present more than once"),
-            }),
+            2.. => Some(
+                Diagnostic::new(
+                    Rule::DuplicateReturn,
+                    ret.span.clone(),
+                    format!("@return {name} is present more than once"),
+                )
+                .with_param(name),
+            ),
         }
     }
 
@@ -519,18 +1003,22 @@ impl CheckReturns<'_> {
         idx: usize,
         ret: &Identifier,
     ) -> Option<Diagnostic> {
-        if idx + 1 > returns_count {
-            let message = if self.is_var {
-                "@return is missing".to_string()
-            } else {
-                format!("@return missing for unnamed return #{}", idx + 1)
-            };
-            Some(Diagnostic {
-                span: ret.span.clone(),
-                message,
-            })
+        (idx + 1 > returns_count).then(|| self.unnamed_missing_diag(idx, ret))
+    }
+
+    /// Generate the missing return diag for an unnamed return, which refers to its position (except for state
+    /// variables)
+    fn unnamed_missing_diag(&self, idx: usize, ret: &Identifier) -> Diagnostic {
+        if self.is_var {
+            Diagnostic::new(Rule::MissingReturn, ret.span.clone(), "@return is missing")
         } else {
-            None
+            let position = format!("#{}", idx + 1);
+            Diagnostic::new(
+                Rule::MissingReturn,
+                ret.span.clone(),
+                format!("@return missing for unnamed return {position}"),
+            )
+            .with_param(position)
         }
     }
 
@@ -555,14 +1043,14 @@ impl CheckReturns<'_> {
     fn extra_unnamed_diags(&self, natspec: &NatSpec) -> impl Iterator<Item = Diagnostic> {
         let unnamed_returns = self.returns.iter().filter(|r| r.name.is_none()).count();
         if natspec.count_unnamed_returns() > unnamed_returns {
-            Some(Diagnostic {
-                span: self
-                    .returns
+            Some(Diagnostic::new(
+                Rule::ExtraReturn,
+                self.returns
                     .last()
                     .cloned()
                     .map_or(self.default_span.clone(), |r| r.span),
-                message: "too many unnamed returns".to_string(),
-            })
+                "too many unnamed returns".to_string(),
+            ))
         } else {
             None
         }
@@ -609,10 +1097,11 @@ impl CheckNotice<'_> {
         {
             None
         } else {
-            Some(Diagnostic {
-                span: self.span.clone(),
-                message: "@notice is missing".to_string(),
-            })
+            Some(Diagnostic::new(
+                Rule::MissingNotice,
+                self.span.clone(),
+                "@notice is missing",
+            ))
         }
     }
 
@@ -621,10 +1110,11 @@ impl CheckNotice<'_> {
         if let Some(natspec) = self.natspec
             && natspec.has_notice()
         {
-            Some(Diagnostic {
-                span: self.span.clone(),
-                message: "@notice is forbidden".to_string(),
-            })
+            Some(Diagnostic::new(
+                Rule::ForbiddenNotice,
+                self.span.clone(),
+                "@notice is forbidden",
+            ))
         } else {
             None
         }
@@ -670,10 +1160,11 @@ impl CheckDev<'_> {
         {
             None
         } else {
-            Some(Diagnostic {
-                span: self.span.clone(),
-                message: "@dev is missing".to_string(),
-            })
+            Some(Diagnostic::new(
+                Rule::MissingDev,
+                self.span.clone(),
+                "@dev is missing",
+            ))
         }
     }
 
@@ -682,10 +1173,11 @@ impl CheckDev<'_> {
         if let Some(natspec) = self.natspec
             && natspec.has_dev()
         {
-            Some(Diagnostic {
-                span: self.span.clone(),
-                message: "@dev is forbidden".to_string(),
-            })
+            Some(Diagnostic::new(
+                Rule::ForbiddenDev,
+                self.span.clone(),
+                "@dev is forbidden",
+            ))
         } else {
             None
         }
@@ -731,10 +1223,11 @@ impl CheckTitle<'_> {
         {
             None
         } else {
-            Some(Diagnostic {
-                span: self.span.clone(),
-                message: "@title is missing".to_string(),
-            })
+            Some(Diagnostic::new(
+                Rule::MissingTitle,
+                self.span.clone(),
+                "@title is missing",
+            ))
         }
     }
 
@@ -743,10 +1236,11 @@ impl CheckTitle<'_> {
         if let Some(natspec) = self.natspec
             && natspec.has_title()
         {
-            Some(Diagnostic {
-                span: self.span.clone(),
-                message: "@title is forbidden".to_string(),
-            })
+            Some(Diagnostic::new(
+                Rule::ForbiddenTitle,
+                self.span.clone(),
+                "@title is forbidden",
+            ))
         } else {
             None
         }
@@ -792,10 +1286,11 @@ impl CheckAuthor<'_> {
         {
             None
         } else {
-            Some(Diagnostic {
-                span: self.span.clone(),
-                message: "@author is missing".to_string(),
-            })
+            Some(Diagnostic::new(
+                Rule::MissingAuthor,
+                self.span.clone(),
+                "@author is missing",
+            ))
         }
     }
 
@@ -804,10 +1299,11 @@ impl CheckAuthor<'_> {
         if let Some(natspec) = self.natspec
             && natspec.has_author()
         {
-            Some(Diagnostic {
-                span: self.span.clone(),
-                message: "@author is forbidden".to_string(),
-            })
+            Some(Diagnostic::new(
+                Rule::ForbiddenAuthor,
+                self.span.clone(),
+                "@author is forbidden",
+            ))
         } else {
             None
         }
@@ -866,10 +1362,11 @@ impl CheckNoticeAndDev<'_> {
         {
             // OK
         } else {
-            out.push(Diagnostic {
-                span: self.span.clone(),
-                message: "@notice or @dev is missing".to_string(),
-            });
+            out.push(Diagnostic::new(
+                Rule::MissingNoticeOrDev,
+                self.span.clone(),
+                "@notice or @dev is missing",
+            ));
         }
     }
 
@@ -890,6 +1387,125 @@ impl CheckNoticeAndDev<'_> {
     }
 }
 
+/// Custom tags (`@custom:<tag>`) `NatSpec` checker.
+#[derive(Debug, Clone, bon::Builder)]
+pub struct CheckCustom<'a> {
+    /// The parsed [`NatSpec`], if any
+    natspec: &'a Option<NatSpec>,
+    /// The rule to apply for each custom tag, keyed by the tag without the `custom:` prefix
+    rules: &'a BTreeMap<String, Req>,
+    /// The custom tags which are known in addition to the ones in `rules`, or empty to allow all custom tags
+    known: &'a [String],
+    /// The span of the source item
+    span: &'a TextRange,
+}
+
+impl CheckCustom<'_> {
+    /// Check if the custom tags match the requirements (`Req::Required` or `Req::Forbidden`) and the list of known
+    /// tags, and generate diagnostics if they don't.
+    ///
+    /// The diagnostics for forbidden and unknown tags point to the offending tag, while the ones for missing tags
+    /// point to the source item.
+    #[must_use]
+    pub fn check(&self) -> Vec<Diagnostic> {
+        let mut res = Vec::new();
+        self.check_into(&mut res);
+        res
+    }
+
+    /// Check the custom tags, appending diagnostics to the provided vector.
+    ///
+    /// This is more efficient than [`check`](Self::check) when collecting diagnostics into an existing vector.
+    pub fn check_into(&self, out: &mut Vec<Diagnostic>) {
+        let tags: Vec<_> = self
+            .natspec
+            .iter()
+            .flat_map(|n| &n.items)
+            .filter_map(|item| match &item.kind {
+                NatSpecKind::Custom { tag } => Some((INTERNER.resolve(*tag), item)),
+                _ => None,
+            })
+            .collect();
+        for (tag, _) in self.rules.iter().filter(|(_, req)| req.is_required()) {
+            if !tags.iter().any(|(t, _)| t == tag) {
+                out.push(Diagnostic::new(
+                    Rule::MissingCustom,
+                    self.span.clone(),
+                    format!("@custom:{tag} is missing"),
+                ));
+            }
+        }
+        for (tag, item) in tags {
+            let rule = match self.rules.get(tag) {
+                Some(Req::Forbidden) => Rule::ForbiddenCustom,
+                None if !self.known.is_empty() && !self.known.iter().any(|a| a == tag) => {
+                    Rule::UnknownCustom
+                }
+                _ => continue,
+            };
+            // the item's span is relative to the comment's start offset
+            let span_start = item.span.start.offset_by(self.span.start);
+            let span_end = item.span.end.offset_by(self.span.start);
+            let message = if rule == Rule::ForbiddenCustom {
+                format!("@custom:{tag} is forbidden")
+            } else {
+                format!("@custom:{tag} is not a known custom tag")
+            };
+            out.push(Diagnostic::new(rule, span_start..span_end, message));
+        }
+    }
+}
+
+/// Unknown tags `NatSpec` checker.
+#[derive(Debug, Clone, bon::Builder)]
+pub struct CheckUnknownTags<'a> {
+    /// The parsed [`NatSpec`], if any
+    natspec: Option<&'a NatSpec>,
+    /// The span of the source item
+    span: &'a TextRange,
+}
+
+impl CheckUnknownTags<'_> {
+    /// Generate a diagnostic for each word starting with `@` at the beginning of a line which is not a valid tag.
+    ///
+    /// The message suggests the closest valid tag, if any. The fix is computed later, from the source.
+    #[must_use]
+    pub fn check(&self) -> Vec<Diagnostic> {
+        let mut res = Vec::new();
+        self.check_into(&mut res);
+        res
+    }
+
+    /// Check for unknown tags, appending diagnostics to the provided vector.
+    ///
+    /// This is more efficient than [`check`](Self::check) when collecting diagnostics into an existing vector.
+    pub fn check_into(&self, out: &mut Vec<Diagnostic>) {
+        let Some(natspec) = self.natspec else {
+            return;
+        };
+        for item in &natspec.items {
+            let NatSpecKind::Unknown { tag } = item.kind else {
+                continue;
+            };
+            let tag = INTERNER.resolve(tag);
+            // the item's span is relative to the comment's start offset
+            let span_start = item.span.start.offset_by(self.span.start);
+            let span_end = item.span.end.offset_by(self.span.start);
+            let message = match suggest_tag(tag) {
+                Some(suggestion) => {
+                    format!("@{tag} is not a valid tag, did you mean @{suggestion}?")
+                }
+                None => format!("@{tag} is not a valid tag"),
+            };
+            out.push(Diagnostic::new(
+                Rule::UnknownTag,
+                span_start..span_end,
+                message,
+            ));
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use similar_asserts::assert_eq;
@@ -898,6 +1514,96 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn test_rule_names() {
+        for rule in Rule::ALL {
+            let name = rule.to_string();
+            assert_eq!(name.parse::<Rule>().unwrap(), *rule);
+            assert_eq!(serde_json::to_value(rule).unwrap(), name);
+        }
+        assert_eq!(
+            Rule::MissingNoticeOrDev.to_string(),
+            "missing-notice-or-dev"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "solar")]
+    fn test_lint_reader() {
+        let source = "contract Test {
+    /// @notice A function
+    function foo(uint256 a) internal {}
+}";
+        let options = ValidationOptions::builder().inheritdoc(false).build();
+        let diags = lint_reader(
+            crate::parser::solar::SolarParser::default(),
+            source.as_bytes(),
+            "src/Test.sol",
+            &options,
+            false,
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(diags.path, PathBuf::from("src/Test.sol"));
+        assert_eq!(diags.items.len(), 1);
+        assert_eq!(diags.items[0].diags[0].rule, Rule::MissingParam);
+
+        let source = "contract Test {
+    /// @notice A function
+    /// @param a The param
+    function foo(uint256 a) internal {}
+}";
+        let diags = lint_reader(
+            crate::parser::solar::SolarParser::default(),
+            source.as_bytes(),
+            "<stdin>",
+            &options,
+            false,
+        )
+        .unwrap();
+        assert!(diags.is_none());
+    }
+
+    #[test]
+    #[cfg(feature = "solar")]
+    fn test_suppressions() {
+        let source = r#"contract Test {
+    string constant A = "// lintspec-disable-file";
+
+    /// @notice A function
+    // lintspec-disable-next-line missing-parm
+    function foo(uint256 a) internal {}
+
+    /// @notice A function
+    /* lintspec-disable-next-item missing-param */
+    function bar(uint256 a) internal {}
+}"#;
+        let options = ValidationOptions::builder().inheritdoc(false).build();
+        let diags = lint_source(
+            crate::parser::solar::SolarParser::default(),
+            "src/Test.sol",
+            source,
+            &options,
+            false,
+        )
+        .unwrap()
+        .unwrap();
+        // the string literal doesn't suppress anything, and the misspelled rule is reported
+        let rules: Vec<_> = diags
+            .items
+            .iter()
+            .flat_map(|i| i.diags.iter().map(|d| d.rule))
+            .collect();
+        assert_eq!(
+            rules,
+            vec![Rule::MissingNotice, Rule::MissingParam, Rule::UnknownRule]
+        );
+        assert_eq!(
+            diags.items[2].diags[0].message,
+            "unknown rule `missing-parm`, did you mean `missing-param`?"
+        );
+    }
+
     #[test]
     fn test_validation_options_default() {
         assert_eq!(
@@ -927,12 +1633,15 @@ mod tests {
         assert_eq!(config.modifiers, options.modifiers);
         assert_eq!(config.structs, options.structs);
         assert_eq!(config.variables, options.variables);
+        assert_eq!(config.severity, options.severity);
 
         let config = Config::builder()
             .lintspec(
                 BaseConfig::builder()
                     .inheritdoc(false)
                     .notice_or_dev(true)
+                    .known_custom_tags(vec!["oz-upgrades-unsafe-allow".to_string()])
+                    .stray_comments(true)
                     .build(),
             )
             .contracts(
@@ -941,6 +1650,7 @@ mod tests {
                     .author(Req::Required)
                     .dev(Req::Required)
                     .notice(Req::Forbidden)
+                    .custom([("security-contact".to_string(), Req::Required)].into())
                     .build(),
             )
             .interfaces(
@@ -979,6 +1689,8 @@ mod tests {
         let options = ValidationOptions::from(&config);
         assert_eq!(config.lintspec.inheritdoc, options.inheritdoc);
         assert_eq!(config.lintspec.notice_or_dev, options.notice_or_dev);
+        assert_eq!(config.lintspec.known_custom_tags, options.known_custom_tags);
+        assert_eq!(config.lintspec.stray_comments, options.stray_comments);
         assert_eq!(config.contracts, options.contracts);
         assert_eq!(config.interfaces, options.interfaces);
         assert_eq!(config.libraries, options.libraries);
@@ -990,5 +1702,6 @@ mod tests {
         assert_eq!(config.modifiers, options.modifiers);
         assert_eq!(config.structs, options.structs);
         assert_eq!(config.variables, options.variables);
+        assert_eq!(config.severity, options.severity);
     }
 }
@@ -0,0 +1,729 @@
+//! Markdown documentation generated from the `NatSpec`
+//!
+//! Each contract, interface and library is rendered as a Markdown page, with a section for each of its members and
+//! tables describing their params and returns. The items declared outside of a contract are rendered on a page named
+//! after their source file, and an index page links to all the other pages.
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::{self, Write as _},
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    definitions::{Definition, Identifier, Parent, Visibility},
+    export::{Index, contract_name, parent},
+    interner::{INTERNER, Symbol},
+    natspec::{NatSpecItem, NatSpecKind},
+    prelude::OrPanic as _,
+};
+
+/// The name of the index page
+pub const INDEX_PAGE: &str = "index.md";
+
+/// A rendered Markdown page
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Page {
+    /// The path of the page, relative to the output directory
+    pub path: PathBuf,
+
+    /// The Markdown contents of the page
+    pub contents: String,
+}
+
+/// A section of a page, with the filter for the definitions it contains
+type Section = (&'static str, fn(&Definition) -> bool);
+
+/// The sections of a page, in order
+const SECTIONS: [Section; 7] = [
+    ("State Variables", Definition::is_variable),
+    ("Events", Definition::is_event),
+    ("Errors", Definition::is_error),
+    ("Structs", Definition::is_struct),
+    ("Enums", Definition::is_enumeration),
+    ("Modifiers", Definition::is_modifier),
+    ("Functions", |d| d.is_constructor() || d.is_function()),
+];
+
+/// Render the documentation of the contracts defined in a list of files
+///
+/// The returned pages include the index page. The paths in the pages are made relative to the root directory if
+/// possible. The `@inheritdoc` tags are resolved for the bases defined in these files.
+#[must_use]
+pub fn render(files: &[(PathBuf, Vec<Definition>)], root_path: impl AsRef<Path>) -> Vec<Page> {
+    let root_path = root_path.as_ref();
+    let mut renderer = Renderer {
+        index: Index::new(files),
+        links: HashMap::new(),
+        used: HashSet::from([INDEX_PAGE.to_string()]),
+    };
+    // assign the page names first, so that the pages can link to the bases
+    let mut file_pages = Vec::new();
+    for (path, definitions) in files {
+        let mut contracts = Vec::new();
+        for definition in definitions {
+            if let Some(name) = contract_name(definition) {
+                let page = renderer.page_name(name.resolve_with(&INTERNER));
+                renderer.links.entry(name).or_insert_with(|| page.clone());
+                contracts.push((definition, page));
+            }
+        }
+        let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+        let free_items = definitions
+            .iter()
+            .any(|d| {
+                parent(d).is_none() && contract_name(d).is_none() && !d.is_natspec_parsing_error()
+            })
+            .then(|| renderer.page_name(&file_name));
+        let display_path = path
+            .strip_prefix(root_path)
+            .unwrap_or(path)
+            .to_string_lossy()
+            .replace('\\', "/");
+        file_pages.push((display_path, definitions, contracts, free_items));
+    }
+
+    let mut pages = Vec::new();
+    let mut index = String::from("# Documentation\n");
+    for (path, definitions, contracts, free_items) in &file_pages {
+        if contracts.is_empty() && free_items.is_none() {
+            continue;
+        }
+        writeln!(index, "\n## `{path}`\n").or_panic("writing to a String should not fail");
+        for (definition, page) in contracts {
+            let mut contents = String::new();
+            renderer
+                .write_contract(&mut contents, path, definition, definitions)
+                .or_panic("writing to a String should not fail");
+            write_index_entry(&mut index, definition, page)
+                .or_panic("writing to a String should not fail");
+            pages.push(Page {
+                path: PathBuf::from(page),
+                contents,
+            });
+        }
+        if let Some(page) = free_items {
+            let mut contents = String::new();
+            renderer
+                .write_free_items(&mut contents, path, definitions)
+                .or_panic("writing to a String should not fail");
+            writeln!(index, "- [Other items]({page})")
+                .or_panic("writing to a String should not fail");
+            pages.push(Page {
+                path: PathBuf::from(page),
+                contents,
+            });
+        }
+    }
+    pages.push(Page {
+        path: PathBuf::from(INDEX_PAGE),
+        contents: index,
+    });
+    pages
+}
+
+/// State for rendering the pages
+struct Renderer<'a> {
+    /// The contracts and types declared in the files, to resolve `@inheritdoc`
+    index: Index<'a>,
+
+    /// The page of each contract, interface and library
+    links: HashMap<Symbol, String>,
+
+    /// The page names which were already assigned
+    used: HashSet<String>,
+}
+
+impl Renderer<'_> {
+    /// Assign a unique page name, adding a numeric suffix if a page with the same name exists
+    fn page_name(&mut self, stem: &str) -> String {
+        let mut name = format!("{stem}.md");
+        let mut n = 1;
+        while !self.used.insert(name.clone()) {
+            n += 1;
+            name = format!("{stem}-{n}.md");
+        }
+        name
+    }
+
+    /// Render a link to the page of a contract, or its name if it's not part of the documentation
+    fn link(&self, name: Symbol) -> String {
+        let label = name.resolve_with(&INTERNER);
+        match self.links.get(&name) {
+            Some(page) => format!("[{label}]({page})"),
+            None => format!("`{label}`"),
+        }
+    }
+
+    /// Write the page of a contract, interface or library
+    fn write_contract(
+        &self,
+        f: &mut String,
+        path: &str,
+        definition: &Definition,
+        definitions: &[Definition],
+    ) -> fmt::Result {
+        let Some((name, kind, bases)) = contract_info(definition) else {
+            return Ok(());
+        };
+        let name = name.resolve_with(&INTERNER);
+        writeln!(f, "# {name}\n")?;
+        let mut kind = kind.to_string();
+        kind[..1].make_ascii_uppercase();
+        writeln!(f, "*{kind} defined in `{path}`*\n")?;
+        if !bases.is_empty() {
+            let bases: Vec<_> = bases.iter().map(|b| self.link(*b)).collect();
+            writeln!(f, "**Inherits:** {}\n", bases.join(", "))?;
+        }
+        if let Some(natspec) = definition.natspec() {
+            write_description(f, &natspec.items)?;
+        }
+        let members: Vec<_> = definitions
+            .iter()
+            .filter(|d| {
+                matches!(
+                    parent(d),
+                    Some(Parent::Contract(p) | Parent::Interface(p) | Parent::Library(p)) if p == name
+                )
+            })
+            .collect();
+        self.write_sections(f, &members)
+    }
+
+    /// Write the page of the items declared outside of a contract in a file
+    fn write_free_items(
+        &self,
+        f: &mut String,
+        path: &str,
+        definitions: &[Definition],
+    ) -> fmt::Result {
+        let file_name = path.rsplit('/').next().unwrap_or(path);
+        writeln!(f, "# {file_name}\n")?;
+        writeln!(f, "*Items defined in `{path}` outside of a contract*\n")?;
+        let items: Vec<_> = definitions
+            .iter()
+            .filter(|d| parent(d).is_none() && contract_name(d).is_none())
+            .collect();
+        self.write_sections(f, &items)
+    }
+
+    /// Write a section for each kind of member, in order of declaration
+    fn write_sections(&self, f: &mut String, members: &[&Definition]) -> fmt::Result {
+        for (heading, filter) in SECTIONS {
+            let mut members = members.iter().filter(|d| filter(d)).peekable();
+            if members.peek().is_none() {
+                continue;
+            }
+            writeln!(f, "## {heading}\n")?;
+            for member in members {
+                self.write_member(f, member)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Write the documentation of a member, with tables for its params and returns
+    fn write_member(&self, f: &mut String, definition: &Definition) -> fmt::Result {
+        let items = self.index.resolved_items(definition, 0);
+        match definition {
+            Definition::Variable(def) => {
+                let name = def.name.resolve_with(&INTERNER);
+                writeln!(f, "### {name}\n")?;
+                writeln!(
+                    f,
+                    "```solidity\n{} {} {name}\n```\n",
+                    def.type_name,
+                    visibility(def.attributes.visibility)
+                )?;
+                write_description(f, &items)?;
+                for item in items.iter().filter(|i| i.kind.is_return()) {
+                    writeln!(f, "**Returns:** {}\n", item.comment)?;
+                }
+            }
+            Definition::Constructor(def) => {
+                writeln!(f, "### constructor\n")?;
+                write_description(f, &items)?;
+                write_params(f, "Parameters", &def.params, &[], &items)?;
+            }
+            Definition::Function(def) => {
+                writeln!(f, "### {}\n", def.name.resolve_with(&INTERNER))?;
+                write_description(f, &items)?;
+                write_params(f, "Parameters", &def.params, &def.param_types, &items)?;
+                write_returns(f, &def.returns, &items)?;
+            }
+            Definition::Modifier(def) => {
+                writeln!(f, "### {}\n", def.name.resolve_with(&INTERNER))?;
+                write_description(f, &items)?;
+                write_params(f, "Parameters", &def.params, &[], &items)?;
+            }
+            Definition::Event(def) => {
+                writeln!(f, "### {}\n", def.name.resolve_with(&INTERNER))?;
+                write_description(f, &items)?;
+                write_params(f, "Parameters", &def.params, &def.param_types, &items)?;
+            }
+            Definition::Error(def) => {
+                writeln!(f, "### {}\n", def.name.resolve_with(&INTERNER))?;
+                write_description(f, &items)?;
+                write_params(f, "Parameters", &def.params, &def.param_types, &items)?;
+            }
+            Definition::Struct(def) => {
+                writeln!(f, "### {}\n", def.name.resolve_with(&INTERNER))?;
+                write_description(f, &items)?;
+                write_params(f, "Members", &def.members, &def.member_types, &items)?;
+            }
+            Definition::Enumeration(def) => {
+                writeln!(f, "### {}\n", def.name.resolve_with(&INTERNER))?;
+                write_description(f, &items)?;
+                write_params(f, "Variants", &def.members, &[], &items)?;
+            }
+            Definition::Contract(_)
+            | Definition::Interface(_)
+            | Definition::Library(_)
+            | Definition::Comment(_)
+            | Definition::NatspecParsingError(_) => {}
+        }
+        Ok(())
+    }
+}
+
+/// Write the entry of a contract in the index page, with the first line of its notice
+fn write_index_entry(f: &mut String, definition: &Definition, page: &str) -> fmt::Result {
+    let Some((name, kind, _)) = contract_info(definition) else {
+        return Ok(());
+    };
+    write!(f, "- [{}]({page}) ({kind})", name.resolve_with(&INTERNER))?;
+    if let Some(notice) = definition.natspec().and_then(|n| {
+        n.items
+            .iter()
+            .find(|i| i.kind == NatSpecKind::Notice)
+            .and_then(|i| i.comment.lines().next())
+    }) {
+        write!(f, ": {notice}")?;
+    }
+    writeln!(f)
+}
+
+/// Retrieve the name, kind and bases of a contract, interface or library
+fn contract_info(definition: &Definition) -> Option<(Symbol, &'static str, &[Symbol])> {
+    match definition {
+        Definition::Contract(def) => Some((def.name, "contract", &def.bases)),
+        Definition::Interface(def) => Some((def.name, "interface", &def.bases)),
+        Definition::Library(def) => Some((def.name, "library", &[])),
+        _ => None,
+    }
+}
+
+/// The keyword of a visibility
+fn visibility(visibility: Visibility) -> &'static str {
+    match visibility {
+        Visibility::External => "external",
+        Visibility::Internal => "internal",
+        Visibility::Private => "private",
+        Visibility::Public => "public",
+    }
+}
+
+/// Write the notice, title, author, dev and custom comments, in order of appearance
+fn write_description(f: &mut String, items: &[NatSpecItem]) -> fmt::Result {
+    for item in items {
+        match &item.kind {
+            NatSpecKind::Notice => writeln!(f, "{}\n", item.comment)?,
+            NatSpecKind::Title => writeln!(f, "**Title:** {}\n", item.comment)?,
+            NatSpecKind::Author => writeln!(f, "**Author:** {}\n", item.comment)?,
+            NatSpecKind::Dev => writeln!(f, "**Details:** {}\n", item.comment)?,
+            NatSpecKind::Custom { tag } => {
+                writeln!(
+                    f,
+                    "**custom:{}:** {}\n",
+                    tag.resolve_with(&INTERNER),
+                    item.comment
+                )?;
+            }
+            NatSpecKind::Param { .. }
+            | NatSpecKind::Return { .. }
+            | NatSpecKind::Inheritdoc { .. }
+            | NatSpecKind::Unknown { .. } => {}
+        }
+    }
+    Ok(())
+}
+
+/// Write a table with the name, type (if known) and `@param` description of params, struct members or enum variants
+fn write_params(
+    f: &mut String,
+    heading: &str,
+    params: &[Identifier],
+    types: &[String],
+    items: &[NatSpecItem],
+) -> fmt::Result {
+    if params.is_empty() {
+        return Ok(());
+    }
+    let with_types = types.len() == params.len();
+    writeln!(f, "**{heading}**\n")?;
+    if with_types {
+        writeln!(
+            f,
+            "| Name | Type | Description |\n| ---- | ---- | ----------- |"
+        )?;
+    } else {
+        writeln!(f, "| Name | Description |\n| ---- | ----------- |")?;
+    }
+    for (i, param) in params.iter().enumerate() {
+        let description = param
+            .name
+            .and_then(|name| {
+                items.iter().find_map(|item| match &item.kind {
+                    NatSpecKind::Param { name: n } if *n == name => Some(cell(&item.comment)),
+                    _ => None,
+                })
+            })
+            .unwrap_or_default();
+        let name = param
+            .name
+            .map(|n| format!("`{}`", n.resolve_with(&INTERNER)))
+            .unwrap_or_default();
+        if with_types {
+            writeln!(f, "| {name} | `{}` | {description} |", types[i])?;
+        } else {
+            writeln!(f, "| {name} | {description} |")?;
+        }
+    }
+    writeln!(f)
+}
+
+/// Write a table with the name and `@return` description of returns, which are matched by position
+///
+/// Unnamed returns are named `_<index>`.
+fn write_returns(f: &mut String, returns: &[Identifier], items: &[NatSpecItem]) -> fmt::Result {
+    if returns.is_empty() {
+        return Ok(());
+    }
+    let comments: Vec<_> = items
+        .iter()
+        .filter(|i| i.kind.is_return())
+        .map(|i| cell(&i.comment))
+        .collect();
+    writeln!(f, "**Returns**\n")?;
+    writeln!(f, "| Name | Description |\n| ---- | ----------- |")?;
+    for (i, ret) in returns.iter().enumerate() {
+        let name = ret.name.map_or_else(
+            || format!("_{i}"),
+            |n| n.resolve_with(&INTERNER).to_string(),
+        );
+        writeln!(
+            f,
+            "| `{name}` | {} |",
+            comments.get(i).map(String::as_str).unwrap_or_default()
+        )?;
+    }
+    writeln!(f)
+}
+
+/// Escape a comment to be displayed in a table cell
+fn cell(comment: &str) -> String {
+    comment.replace('|', "\\|").replace('\n', "<br>")
+}
+
+#[cfg(test)]
+#[cfg(feature = "solar")]
+mod tests {
+    use similar_asserts::assert_eq;
+
+    use crate::parser::{Parse as _, solar::SolarParser};
+
+    use super::*;
+
+    fn parse(path: &str, source: &str) -> (PathBuf, Vec<Definition>) {
+        let document = SolarParser::default()
+            .parse_document(source.as_bytes(), Some(path), false)
+            .unwrap();
+        (PathBuf::from(path), document.definitions)
+    }
+
+    #[test]
+    fn test_render() {
+        let files = vec![
+            parse(
+                "/project/src/IVault.sol",
+                "/// @notice A vault
+                /// @dev Keeps funds
+                interface IVault {
+                    /// @notice Deposit funds
+                    /// @param amount The amount | in wei
+                    /// @return The shares
+                    function deposit(uint256 amount) external returns (uint256);
+                }
+
+                /// @notice Not allowed
+                error Unauthorized();",
+            ),
+            parse(
+                "/project/src/Vault.sol",
+                "/// @title Vault
+                contract Vault is IVault, Ownable {
+                    /// @notice A position
+                    /// @param owner The owner
+                    struct Position { address owner; uint256 amount; }
+
+                    /// @inheritdoc IVault
+                    function deposit(uint256 amount) external returns (uint256 shares) {}
+                }",
+            ),
+        ];
+        let pages = render(&files, "/project");
+        let paths: Vec<_> = pages.iter().map(|p| p.path.to_string_lossy()).collect();
+        assert_eq!(
+            paths,
+            vec!["IVault.md", "IVault.sol.md", "Vault.md", "index.md"]
+        );
+        assert_eq!(
+            pages[3].contents,
+            "# Documentation
+
+## `src/IVault.sol`
+
+- [IVault](IVault.md) (interface): A vault
+- [Other items](IVault.sol.md)
+
+## `src/Vault.sol`
+
+- [Vault](Vault.md) (contract)
+"
+        );
+        assert_eq!(
+            pages[2].contents,
+            "# Vault
+
+*Contract defined in `src/Vault.sol`*
+
+**Inherits:** [IVault](IVault.md), `Ownable`
+
+**Title:** Vault
+
+## Structs
+
+### Position
+
+A position
+
+**Members**
+
+| Name | Type | Description |
+| ---- | ---- | ----------- |
+| `owner` | `address` | The owner |
+| `amount` | `uint256` |  |
+
+## Functions
+
+### deposit
+
+Deposit funds
+
+**Parameters**
+
+| Name | Type | Description |
+| ---- | ---- | ----------- |
+| `amount` | `uint256` | The amount \\| in wei |
+
+**Returns**
+
+| Name | Description |
+| ---- | ----------- |
+| `shares` | The shares |
+
+"
+        );
+        assert!(
+            pages[1]
+                .contents
+                .contains("### Unauthorized\n\nNot allowed\n")
+        );
+    }
+
+    #[test]
+    fn test_params_returns() {
+        let files = vec![parse(
+            "/project/src/Math.sol",
+            "library Math {
+                /// @notice Divide two numbers
+                /// @param a The dividend
+                /// @return The quotient
+                /// @return The remainder
+                /// @return Extra return
+                function div(uint256 a, uint256 b, uint256) internal pure returns (uint256, uint256 rem, bool) {}
+
+                /// @notice The rounding
+                enum Rounding { Down, Up }
+
+                /// @notice Only when positive
+                /// @param x The value
+                modifier positive(int256 x) { _; }
+            }",
+        )];
+        let pages = render(&files, "/project");
+        assert_eq!(
+            pages[0].contents,
+            "# Math
+
+*Library defined in `src/Math.sol`*
+
+## Enums
+
+### Rounding
+
+The rounding
+
+**Variants**
+
+| Name | Description |
+| ---- | ----------- |
+| `Down` |  |
+| `Up` |  |
+
+## Modifiers
+
+### positive
+
+Only when positive
+
+**Parameters**
+
+| Name | Description |
+| ---- | ----------- |
+| `x` | The value |
+
+## Functions
+
+### div
+
+Divide two numbers
+
+**Parameters**
+
+| Name | Type | Description |
+| ---- | ---- | ----------- |
+| `a` | `uint256` | The dividend |
+| `b` | `uint256` |  |
+|  | `uint256` |  |
+
+**Returns**
+
+| Name | Description |
+| ---- | ----------- |
+| `_0` | The quotient |
+| `rem` | The remainder |
+| `_2` | Extra return |
+
+"
+        );
+    }
+
+    #[test]
+    fn test_escaping() {
+        assert_eq!(cell("a | b"), "a \\| b");
+        assert_eq!(cell("first\nsecond"), "first<br>second");
+        assert_eq!(cell("a|b\nc|d"), "a\\|b<br>c\\|d");
+
+        let files = vec![parse(
+            "/project/src/Flags.sol",
+            "contract Flags {
+                /// @notice Set the flags
+                /// @param flags The flags, combined with `a | b`
+                /// which can span multiple lines
+                /// @return ok Whether `x || y`
+                /// holds
+                function set(uint8 flags) external returns (bool ok) {}
+            }",
+        )];
+        let pages = render(&files, "/project");
+        assert!(
+            pages[0].contents.contains(
+                "| `flags` | `uint8` | The flags, combined with `a \\| b`<br>which can span multiple lines |\n"
+            ),
+            "{}",
+            pages[0].contents
+        );
+        assert!(
+            pages[0]
+                .contents
+                .contains("| `ok` | Whether `x \\|\\| y`<br>holds |\n"),
+            "{}",
+            pages[0].contents
+        );
+        // the description of the items is not escaped outside of tables
+        assert!(pages[0].contents.contains("Set the flags\n"));
+    }
+
+    #[test]
+    fn test_index() {
+        let files = vec![
+            parse(
+                "/project/src/A.sol",
+                "/// @notice First line
+                /// continued on the second line
+                contract Token {}
+
+                /// @notice A library
+                library Utils {}",
+            ),
+            // a file without any item is not part of the index
+            parse("/project/src/Empty.sol", "pragma solidity ^0.8.0;"),
+            parse(
+                "/other/B.sol",
+                "contract Token {}
+
+                /// @notice A free function
+                function helper() pure {}
+
+                struct Point { uint256 x; }",
+            ),
+        ];
+        let pages = render(&files, "/project");
+        let paths: Vec<_> = pages.iter().map(|p| p.path.to_string_lossy()).collect();
+        // the pages of contracts with the same name get a suffix
+        assert_eq!(
+            paths,
+            vec!["Token.md", "Utils.md", "Token-2.md", "B.sol.md", "index.md"]
+        );
+        assert_eq!(
+            pages[4].contents,
+            "# Documentation
+
+## `src/A.sol`
+
+- [Token](Token.md) (contract): First line
+- [Utils](Utils.md) (library): A library
+
+## `/other/B.sol`
+
+- [Token](Token-2.md) (contract)
+- [Other items](B.sol.md)
+"
+        );
+        assert_eq!(
+            pages[3].contents,
+            "# B.sol
+
+*Items defined in `/other/B.sol` outside of a contract*
+
+## Structs
+
+### Point
+
+**Members**
+
+| Name | Type | Description |
+| ---- | ---- | ----------- |
+| `x` | `uint256` |  |
+
+## Functions
+
+### helper
+
+A free function
+
+"
+        );
+    }
+}
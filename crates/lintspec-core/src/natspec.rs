@@ -4,11 +4,13 @@ use std::ops::Range;
 use derive_more::IsVariant;
 use winnow::{
     LocatingSlice,
-    ascii::{line_ending, space0, space1, till_line_ending},
-    combinator::{alt, cut_err, delimited, not, opt, repeat, separated},
+    ascii::{line_ending, multispace1, space0, space1, till_line_ending},
+    combinator::{
+        alt, cut_err, delimited, eof, not, opt, peek, preceded, repeat, separated, terminated,
+    },
     error::{StrContext, StrContextValue},
     seq,
-    token::{rest, take_till, take_until},
+    token::{rest, take_till, take_until, take_while},
 };
 pub use winnow::{ModalResult, Parser};
 
@@ -187,15 +189,43 @@ pub enum NatSpecKind {
     Custom {
         tag: Symbol,
     },
+    /// A word starting with `@` at the beginning of a line which is not a valid tag (e.g. `@returns` or `@Dev`), without
+    /// the `@` prefix
+    Unknown {
+        tag: Symbol,
+    },
 }
 
+/// The valid tags, without the `@` prefix (`custom` is followed by `:<tag>`)
+pub const TAGS: &[&str] = &[
+    "title",
+    "author",
+    "notice",
+    "dev",
+    "param",
+    "return",
+    "inheritdoc",
+    "custom",
+];
+
 impl From<NatSpecItem> for NatSpec {
     fn from(value: NatSpecItem) -> Self {
         Self { items: vec![value] }
     }
 }
 
+/// A single line of a doc-comment, with its tag (if any), its description and its byte span
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Line {
+    kind: Option<NatSpecKind>,
+    comment: String,
+    span: Range<usize>,
+}
+
 /// Parse a Solidity doc-comment to extract the `NatSpec` information
+///
+/// The input can be a multiline comment (`/** ... */`) or one or more consecutive single line comments (`/// ...`),
+/// separated by line breaks and optional indentation.
 pub fn parse_comment(input: &mut &str) -> ModalResult<NatSpec> {
     // consume input to avoid errors when used with `Parser::parse`
     let input = rest::<&str, _>.parse_next(input)?;
@@ -241,31 +271,99 @@ fn ident(input: &mut LocatingSlice<&str>) -> ModalResult<Symbol> {
         .parse_next(input)
 }
 
+/// Suggest a valid tag for an unknown tag (without the `@` prefix), based on the edit distance
+///
+/// The comparison is case-insensitive. A tag which looks like `custom:<tag>` keeps its suffix (e.g. `Custom:foo`
+/// becomes `custom:foo`).
+#[must_use]
+pub fn suggest_tag(tag: &str) -> Option<String> {
+    let (prefix, suffix) = tag.split_once(':').unwrap_or((tag, ""));
+    let prefix = prefix.to_lowercase();
+    let max_distance = if prefix.chars().count() <= 4 { 1 } else { 2 };
+    if !suffix.is_empty() {
+        return (edit_distance(&prefix, "custom") <= max_distance)
+            .then(|| format!("custom:{suffix}"));
+    }
+    TAGS.iter()
+        .filter(|t| **t != "custom")
+        .map(|t| (edit_distance(&prefix, t), t))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, t)| (*t).to_string())
+}
+
+/// Compute the Levenshtein distance between two strings
+pub(crate) fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(ca != *cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    row[b.len()]
+}
+
+/// Check that a tag is followed by whitespace, the end of the comment or the end of the input
+fn tag_end(input: &mut LocatingSlice<&str>) -> ModalResult<()> {
+    peek(alt((multispace1.void(), "*/".void(), eof.void()))).parse_next(input)
+}
+
+/// Parse an unknown tag (a word starting with `@` which is not a valid tag)
+///
+/// The tags which are valid but not followed by the expected identifier (e.g. `@param` alone) are not considered to be
+/// unknown.
+fn unknown_tag(input: &mut LocatingSlice<&str>) -> ModalResult<NatSpecKind> {
+    preceded(
+        '@',
+        take_while(1.., |c: char| {
+            c.is_alphanumeric() || matches!(c, '_' | '-' | ':')
+        })
+        .verify(|tag: &str| tag.starts_with(|c: char| c.is_alphabetic()) && !TAGS.contains(&tag)),
+    )
+    .map(|tag: &str| NatSpecKind::Unknown {
+        tag: INTERNER.get_or_intern(tag),
+    })
+    .parse_next(input)
+}
+
 /// Parse a [`NatSpecKind`] (tag followed by an optional identifier)
 ///
 /// For `@return`, the identifier, if present, is not included in the `NatSpecItem` for now. A post-processing
 /// step ([`NatSpecItem::populate_return`]) is needed to extract the name.
+///
+/// A word starting with `@` which is not a valid tag is parsed as [`NatSpecKind::Unknown`].
 fn natspec_kind(input: &mut LocatingSlice<&str>) -> ModalResult<NatSpecKind> {
     alt((
-        "@title".map(|_| NatSpecKind::Title),
-        "@author".map(|_| NatSpecKind::Author),
-        "@notice".map(|_| NatSpecKind::Notice),
-        "@dev".map(|_| NatSpecKind::Dev),
-        seq! {NatSpecKind::Param {
-            _: "@param",
-            _: space1,
-            name: ident
-        }},
-        "@return".map(|_| NatSpecKind::Return { name: None }), // we will process the name later since it's optional
-        seq! {NatSpecKind::Inheritdoc {
-            _: "@inheritdoc",
-            _: space1,
-            parent: ident
-        }},
-        seq! {NatSpecKind::Custom {
-            _: "@custom:",
-            tag: ident
-        }},
+        terminated(
+            alt((
+                "@title".map(|_| NatSpecKind::Title),
+                "@author".map(|_| NatSpecKind::Author),
+                "@notice".map(|_| NatSpecKind::Notice),
+                "@dev".map(|_| NatSpecKind::Dev),
+                seq! {NatSpecKind::Param {
+                    _: "@param",
+                    _: space1,
+                    name: ident
+                }},
+                "@return".map(|_| NatSpecKind::Return { name: None }), // we will process the name later since it's optional
+                seq! {NatSpecKind::Inheritdoc {
+                    _: "@inheritdoc",
+                    _: space1,
+                    parent: ident
+                }},
+                seq! {NatSpecKind::Custom {
+                    _: "@custom:",
+                    tag: ident
+                }},
+            )),
+            tag_end,
+        ),
+        terminated(unknown_tag, tag_end),
     ))
     .parse_next(input)
 }
@@ -277,30 +375,22 @@ fn end_of_comment(input: &mut LocatingSlice<&str>) -> ModalResult<()> {
     Ok(())
 }
 
-/// Parse a single `NatSpec` item (line) in a multiline comment
-fn one_multiline_natspec(
-    input: &mut LocatingSlice<&str>,
-) -> ModalResult<(NatSpecItem, Range<usize>)> {
+/// Parse a single `NatSpec` line in a multiline comment
+fn one_multiline_natspec(input: &mut LocatingSlice<&str>) -> ModalResult<Line> {
     let _ = space0.parse_next(input)?;
     let () = repeat::<_, _, (), _, _>(0.., '*').parse_next(input)?;
     let _ = space0.parse_next(input)?;
-    let (kind, kind_span) = opt(natspec_kind)
-        .map(|v| v.unwrap_or(NatSpecKind::Notice))
-        .with_span()
-        .parse_next(input)?;
+    let (kind, kind_span) = opt(natspec_kind).with_span().parse_next(input)?;
     let _ = space0.parse_next(input)?;
     let (comment, comment_span) = take_until(0.., ("\r", "\n", "*/"))
         .parse_to()
         .with_span()
         .parse_next(input)?;
-    Ok((
-        NatSpecItem {
-            kind,
-            comment,
-            span: TextRange::default(),
-        },
-        kind_span.start..comment_span.end,
-    ))
+    Ok(Line {
+        kind,
+        comment,
+        span: kind_span.start..comment_span.end,
+    })
 }
 
 /// Parse a multiline `NatSpec` comment
@@ -321,10 +411,7 @@ fn multiline_comment(input: &mut LocatingSlice<&str>) -> ModalResult<(NatSpec, V
         separated(0.., one_multiline_natspec, line_ending),
         (opt(line_ending), space0, end_of_comment),
     )
-    .map(|items: Vec<(NatSpecItem, Range<usize>)>| {
-        let (items, spans) = items.into_iter().unzip();
-        (NatSpec { items }, spans)
-    })
+    .map(join_lines)
     .parse_next(input)
 }
 
@@ -334,32 +421,26 @@ fn empty_multiline(input: &mut LocatingSlice<&str>) -> ModalResult<(NatSpec, Vec
     Ok((NatSpec::default(), Vec::new()))
 }
 
-/// Parse a single line comment `NatSpec` item
-fn single_line_natspec(
-    input: &mut LocatingSlice<&str>,
-) -> ModalResult<(NatSpecItem, Range<usize>)> {
+/// Parse the contents of a single line comment (after the `///` delimiter)
+fn single_line_natspec(input: &mut LocatingSlice<&str>) -> ModalResult<Line> {
     let _ = space0.parse_next(input)?;
-    let (kind, kind_span) = opt(natspec_kind)
-        .map(|v| v.unwrap_or(NatSpecKind::Notice))
-        .with_span()
-        .parse_next(input)?;
+    let (kind, kind_span) = opt(natspec_kind).with_span().parse_next(input)?;
     let _ = space0.parse_next(input)?;
     let (comment, comment_span) = till_line_ending.parse_to().with_span().parse_next(input)?;
-    Ok((
-        NatSpecItem {
-            kind,
-            comment,
-            span: TextRange::default(),
-        },
-        kind_span.start..comment_span.end,
-    ))
+    Ok(Line {
+        kind,
+        comment,
+        span: kind_span.start..comment_span.end,
+    })
 }
 
-/// Parse a single line `NatSpec` comment
+/// Parse one or more consecutive single line `NatSpec` comments
+///
+/// Like for multiline comments, a line without a tag continues the description of the previous line's tag.
 fn single_line_comment(
     input: &mut LocatingSlice<&str>,
 ) -> ModalResult<(NatSpec, Vec<Range<usize>>)> {
-    let (item, range) = delimited(
+    let first = preceded(
         (
             "///",
             // four slashes is not a valid doc-comment
@@ -369,13 +450,61 @@ fn single_line_comment(
                 .context(StrContext::Expected(StrContextValue::Description("///"))),
         ),
         single_line_natspec,
-        opt(line_ending),
     )
     .parse_next(input)?;
-    if item.is_empty() {
-        return Ok((NatSpec::default(), Vec::new()));
+    let mut lines: Vec<Line> = repeat(
+        0..,
+        preceded((line_ending, space0, "///", not('/')), single_line_natspec),
+    )
+    .parse_next(input)?;
+    let _ = opt(line_ending).parse_next(input)?;
+    lines.insert(0, first);
+    Ok(join_lines(lines))
+}
+
+/// Merge the lines of a comment into [`NatSpec`] items
+///
+/// Lines without a tag are appended (separated by a newline) to the description of the previous tag, which is how
+/// solc builds the devdoc and userdoc. If the first lines don't have a tag, they are considered to be a `@notice`.
+/// Empty lines are only kept if they are followed by more text for the same tag.
+fn join_lines(lines: Vec<Line>) -> (NatSpec, Vec<Range<usize>>) {
+    let mut items: Vec<NatSpecItem> = Vec::new();
+    let mut spans: Vec<Range<usize>> = Vec::new();
+    let mut empty_lines = 0;
+    for line in lines {
+        match (line.kind, items.last_mut(), spans.last_mut()) {
+            (Some(kind), _, _) => {
+                items.push(NatSpecItem {
+                    kind,
+                    comment: line.comment,
+                    span: TextRange::default(),
+                });
+                spans.push(line.span);
+                empty_lines = 0;
+            }
+            (None, _, _) if line.comment.is_empty() => {
+                empty_lines += 1;
+            }
+            (None, Some(item), Some(span)) => {
+                if !item.comment.is_empty() {
+                    item.comment.push_str(&"\n".repeat(empty_lines + 1));
+                }
+                item.comment.push_str(&line.comment);
+                span.end = line.span.end;
+                empty_lines = 0;
+            }
+            (None, _, _) => {
+                items.push(NatSpecItem {
+                    kind: NatSpecKind::Notice,
+                    comment: line.comment,
+                    span: TextRange::default(),
+                });
+                spans.push(line.span);
+                empty_lines = 0;
+            }
+        }
     }
-    Ok((item.into(), vec![range]))
+    (NatSpec { items }, spans)
 }
 
 #[cfg(test)]
@@ -420,6 +549,39 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_unknown_kind() {
+        for tag in ["returns", "params", "notive", "Dev", "dev:"] {
+            let res = natspec_kind
+                .parse_next(&mut LocatingSlice::new(format!("@{tag} foo").as_str()))
+                .unwrap();
+            assert_eq!(
+                res,
+                NatSpecKind::Unknown {
+                    tag: INTERNER.get_or_intern(tag),
+                }
+            );
+        }
+        // valid tags without the expected identifier and text which doesn't look like a tag are not unknown tags
+        for text in ["@param", "@custom foo", "@ foo", "@1x", "@user.name foo"] {
+            let res = natspec_kind.parse_next(&mut LocatingSlice::new(text));
+            assert!(res.is_err(), "{text}: {res:?}");
+        }
+    }
+
+    #[test]
+    fn test_suggest_tag() {
+        assert_eq!(suggest_tag("returns").as_deref(), Some("return"));
+        assert_eq!(suggest_tag("params").as_deref(), Some("param"));
+        assert_eq!(suggest_tag("notive").as_deref(), Some("notice"));
+        assert_eq!(suggest_tag("Dev").as_deref(), Some("dev"));
+        assert_eq!(suggest_tag("dev:").as_deref(), Some("dev"));
+        assert_eq!(suggest_tag("inheritDoc").as_deref(), Some("inheritdoc"));
+        assert_eq!(suggest_tag("Custom:foo").as_deref(), Some("custom:foo"));
+        assert_eq!(suggest_tag("foo"), None);
+        assert_eq!(suggest_tag("example"), None);
+    }
+
     #[test]
     fn test_one_multiline_item() {
         let cases = [
@@ -449,23 +611,26 @@ mod tests {
                 },
                 "bar",
             ),
-            ("  lorem ipsum\n", NatSpecKind::Notice, "lorem ipsum"),
-            ("lorem ipsum\r\n", NatSpecKind::Notice, "lorem ipsum"),
-            ("\t*  foobar\n", NatSpecKind::Notice, "foobar"),
-            ("    * foobar\n", NatSpecKind::Notice, "foobar"),
         ];
         for case in cases {
             let res = (one_multiline_natspec, line_ending).parse(LocatingSlice::new(case.0));
             assert!(res.is_ok(), "{res:?}");
-            let ((res, _), _) = res.unwrap();
-            assert_eq!(
-                res,
-                NatSpecItem {
-                    kind: case.1,
-                    comment: case.2.to_string(),
-                    span: TextRange::default()
-                }
-            );
+            let (res, _) = res.unwrap();
+            assert_eq!(res.kind, Some(case.1));
+            assert_eq!(res.comment, case.2);
+        }
+        let cases = [
+            ("  lorem ipsum\n", "lorem ipsum"),
+            ("lorem ipsum\r\n", "lorem ipsum"),
+            ("\t*  foobar\n", "foobar"),
+            ("    * foobar\n", "foobar"),
+        ];
+        for case in cases {
+            let res = (one_multiline_natspec, line_ending).parse(LocatingSlice::new(case.0));
+            assert!(res.is_ok(), "{res:?}");
+            let (res, _) = res.unwrap();
+            assert_eq!(res.kind, None);
+            assert_eq!(res.comment, case.1);
         }
     }
 
@@ -594,12 +759,7 @@ Another notice
                 items: vec![
                     NatSpecItem {
                         kind: NatSpecKind::Notice,
-                        comment: "Some notice text.".to_string(),
-                        span: TextRange::default()
-                    },
-                    NatSpecItem {
-                        kind: NatSpecKind::Notice,
-                        comment: "Another notice".to_string(),
+                        comment: "Some notice text.\nAnother notice".to_string(),
                         span: TextRange::default()
                     },
                     NatSpecItem {
@@ -621,6 +781,82 @@ Another notice
         );
     }
 
+    #[test]
+    fn test_multiline_continuation() {
+        let comment = "/**
+     * Some notice text
+     * on two lines.
+     * @dev A long dev comment
+     *      which is wrapped.
+     *
+     *      With a second paragraph.
+     *
+     * @param foo The foo
+     * param, wrapped
+     */";
+        let res = parse_comment.parse(comment);
+        assert!(res.is_ok(), "{res:?}");
+        let res = res.unwrap();
+        assert_eq!(
+            res.items
+                .iter()
+                .map(|i| (i.kind.clone(), i.comment.as_str()))
+                .collect::<Vec<_>>(),
+            vec![
+                (NatSpecKind::Notice, "Some notice text\non two lines."),
+                (
+                    NatSpecKind::Dev,
+                    "A long dev comment\nwhich is wrapped.\n\nWith a second paragraph."
+                ),
+                (
+                    NatSpecKind::Param {
+                        name: INTERNER.get_or_intern("foo")
+                    },
+                    "The foo\nparam, wrapped"
+                ),
+            ]
+        );
+        // the span covers all the lines of the description
+        assert_eq!(res.items[1].span.start.line, 3);
+        assert_eq!(res.items[1].span.start.col_utf8, 7);
+        assert_eq!(res.items[1].span.end.line, 6);
+        assert_eq!(res.items[1].span.end.col_utf8, 36);
+    }
+
+    #[test]
+    fn test_single_line_continuation() {
+        let comment = "/// @notice Some notice text
+    ///
+    /// @dev A long dev comment
+    ///      which is wrapped.
+    /// @custom:foo bar
+    ///";
+        let res = parse_comment.parse(comment);
+        assert!(res.is_ok(), "{res:?}");
+        let res = res.unwrap();
+        assert_eq!(
+            res.items
+                .iter()
+                .map(|i| (i.kind.clone(), i.comment.as_str()))
+                .collect::<Vec<_>>(),
+            vec![
+                (NatSpecKind::Notice, "Some notice text"),
+                (NatSpecKind::Dev, "A long dev comment\nwhich is wrapped."),
+                (
+                    NatSpecKind::Custom {
+                        tag: INTERNER.get_or_intern("foo")
+                    },
+                    "bar"
+                ),
+            ]
+        );
+        assert_eq!(res.items[1].span.start.line, 2);
+        assert_eq!(res.items[1].span.start.col_utf8, 8);
+        assert_eq!(res.items[1].span.end.line, 3);
+        assert_eq!(res.items[1].span.end.col_utf8, 30);
+        assert_eq!(res.items[2].span.start.line, 4);
+    }
+
     #[test]
     fn test_multiline_empty() {
         let comment = "/**
@@ -0,0 +1,210 @@
+//! Per-path overrides of the validation rules
+//!
+//! The `[[overrides]]` sections of the config apply different rules to the files which match some glob patterns, for
+//! instance to relax the rules for tests and mocks. The effective options of a file are resolved before its items are
+//! validated. Since many files match the same overrides, the options are computed once per set of matching overrides.
+use std::{
+    collections::HashMap,
+    fmt,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use serde::{Serialize, Serializer, ser::SerializeStruct as _};
+
+use crate::{
+    config::{Config, Override},
+    lint::ValidationOptions,
+    prelude::OrPanic as _,
+};
+
+/// The overrides of the validation options for some paths
+///
+/// Two instances are equal if they have the same root and overrides.
+#[derive(Clone, Default)]
+pub struct PathOverrides {
+    /// The directory to which the patterns are relative
+    root: PathBuf,
+
+    /// The config on which the overrides are applied
+    base: Arc<Config>,
+
+    /// The overrides, with their compiled patterns
+    overrides: Vec<(Override, GlobSet)>,
+
+    /// The effective options for each set of matching overrides (identified by their indices)
+    resolved: Arc<Mutex<HashMap<Vec<usize>, Arc<ValidationOptions>>>>,
+}
+
+impl PathOverrides {
+    /// Compile the overrides of a config, with patterns relative to the `root` directory
+    #[must_use]
+    pub fn new(config: &Config, root: impl Into<PathBuf>) -> Self {
+        let overrides = config
+            .overrides
+            .iter()
+            .map(|o| {
+                let mut builder = GlobSetBuilder::new();
+                // the patterns are validated when the config is deserialized
+                for glob in o.files.iter().filter_map(|f| Glob::new(f).ok()) {
+                    builder.add(glob);
+                }
+                (o.clone(), builder.build().unwrap_or_default())
+            })
+            .collect();
+        Self {
+            root: root.into(),
+            base: Arc::new(config.clone()),
+            overrides,
+            resolved: Arc::default(),
+        }
+    }
+
+    /// Retrieve the effective options for a file, or `None` if no override matches it
+    ///
+    /// Paths outside of the root directory are matched as-is.
+    pub fn resolve(&self, path: impl AsRef<Path>) -> Option<Arc<ValidationOptions>> {
+        let path = path.as_ref();
+        let path = path.strip_prefix(&self.root).unwrap_or(path);
+        let matching: Vec<_> = self
+            .overrides
+            .iter()
+            .enumerate()
+            .filter(|(_, (_, globs))| globs.is_match(path))
+            .map(|(i, _)| i)
+            .collect();
+        if matching.is_empty() {
+            return None;
+        }
+        let mut resolved = self
+            .resolved
+            .lock()
+            .or_panic("mutex should not be poisoned");
+        let options = resolved.entry(matching).or_insert_with_key(|matching| {
+            let config = self
+                .base
+                .apply_overrides(matching.iter().map(|&i| &self.overrides[i].0))
+                .or_panic("overrides are validated when the config is deserialized");
+            Arc::new(ValidationOptions::from(config))
+        });
+        Some(Arc::clone(options))
+    }
+}
+
+impl fmt::Debug for PathOverrides {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PathOverrides")
+            .field("root", &self.root)
+            .field(
+                "overrides",
+                &self.overrides.iter().map(|(o, _)| o).collect::<Vec<_>>(),
+            )
+            .finish_non_exhaustive()
+    }
+}
+
+impl PartialEq for PathOverrides {
+    fn eq(&self, other: &Self) -> bool {
+        self.root == other.root
+            && self.overrides.len() == other.overrides.len()
+            && self
+                .overrides
+                .iter()
+                .zip(&other.overrides)
+                .all(|((a, _), (b, _))| a == b)
+    }
+}
+
+impl Eq for PathOverrides {}
+
+impl Serialize for PathOverrides {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("PathOverrides", 2)?;
+        state.serialize_field("root", &self.root)?;
+        state.serialize_field(
+            "overrides",
+            &self.overrides.iter().map(|(o, _)| o).collect::<Vec<_>>(),
+        )?;
+        state.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use figment::providers::{Format as _, Toml};
+
+    use crate::config::Req;
+
+    use super::*;
+
+    fn config(toml: &str) -> Config {
+        figment::Figment::from(Config::default())
+            .admerge(Toml::string(toml))
+            .extract()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_resolve() {
+        let config = config(
+            r#"
+            [function.external]
+            dev = "required"
+
+            [[overrides]]
+            files = ["test/**", "*.t.sol"]
+            function.external.param = "ignored"
+
+            [[overrides]]
+            files = ["test/mocks/**"]
+            function.external.notice = "ignored"
+            "#,
+        );
+        let overrides = PathOverrides::new(&config, "/project");
+        assert!(overrides.resolve("/project/src/Foo.sol").is_none());
+        assert!(overrides.resolve("/other/test/Foo.sol").is_none());
+
+        let options = overrides.resolve("/project/src/Foo.t.sol").unwrap();
+        assert_eq!(options.functions.external.param, Req::Ignored);
+        assert_eq!(options.functions.external.notice, Req::Required);
+        // the rules which are not overridden come from the main config
+        assert_eq!(options.functions.external.dev, Req::Required);
+        assert_eq!(options.functions.public.param, Req::Required);
+
+        let options = overrides.resolve("/project/test/mocks/Mock.sol").unwrap();
+        assert_eq!(options.functions.external.param, Req::Ignored);
+        assert_eq!(options.functions.external.notice, Req::Ignored);
+        assert_eq!(options.functions.external.dev, Req::Required);
+
+        // the options are computed once per set of overrides
+        let other = overrides.resolve("/project/test/Bar.sol").unwrap();
+        assert!(Arc::ptr_eq(
+            &other,
+            &overrides.resolve("/project/src/Foo.t.sol").unwrap()
+        ));
+    }
+
+    #[test]
+    fn test_invalid_override() {
+        let is_valid = |rules: &str| {
+            figment::Figment::from(Config::default())
+                .admerge(Toml::string(&format!("[[overrides]]\n{rules}")))
+                .extract::<Config>()
+                .is_ok()
+        };
+        assert!(is_valid(
+            "files = [\"test/**\"]\nfunction.external.param = \"ignored\""
+        ));
+        assert!(!is_valid("files = [\"test/**\"]\noutput.json = true"));
+        assert!(!is_valid(
+            "files = []\nfunction.external.param = \"ignored\""
+        ));
+        assert!(!is_valid(
+            "files = [\"a[\"]\nfunction.external.param = \"ignored\""
+        ));
+        assert!(!is_valid(
+            "files = [\"test/**\"]\nfunction.external.param = \"nope\""
+        ));
+    }
+}
@@ -1,13 +1,19 @@
 //! Solidity parser interface
-use std::{collections::HashMap, io, path::Path};
+use std::{collections::HashMap, io, ops::Range, path::Path};
 
 use crate::{
     definitions::{
-        Definition, constructor::ConstructorDefinition, enumeration::EnumDefinition,
-        error::ErrorDefinition, event::EventDefinition, modifier::ModifierDefinition,
+        Definition, Parent,
+        comment::{CommentDefinition, StrayKind},
+        constructor::ConstructorDefinition,
+        enumeration::EnumDefinition,
+        error::ErrorDefinition,
+        event::EventDefinition,
+        modifier::ModifierDefinition,
         structure::StructDefinition,
     },
     error::{ErrorKind, Result},
+    natspec::TAGS,
     prelude::OrPanic as _,
     textindex::{TextIndex, TextRange, compute_indices},
 };
@@ -41,6 +47,14 @@ pub struct ParsedDocument {
 
     /// The list of definitions found in the document
     pub definitions: Vec<Definition>,
+
+    /// The paths of the `import` directives in the document, as written in the source
+    pub imports: Vec<String>,
+
+    /// The utf-8 byte offsets of the regular comments (`//` and `/* */`) in the document, sorted by start offset
+    ///
+    /// These can contain suppression directives (see [`Suppressions`][crate::suppression::Suppressions]).
+    pub comments: Vec<Range<usize>>,
 }
 
 /// The trait implemented by all parsers
@@ -112,6 +126,7 @@ fn gather_offsets(definitions: &[Definition]) -> Vec<usize> {
             | Definition::Interface(_)
             | Definition::Library(_)
             | Definition::Variable(_)
+            | Definition::Comment(_)
             | Definition::NatspecParsingError(_) => {}
         }
     }
@@ -180,6 +195,7 @@ fn populate(text_indices: &[TextIndex], definitions: &mut Vec<Definition>) {
             | Definition::Interface(_)
             | Definition::Library(_)
             | Definition::Variable(_)
+            | Definition::Comment(_)
             | Definition::NatspecParsingError(_) => {}
         }
     }
@@ -199,3 +215,121 @@ pub fn complete_text_ranges(source: &str, definitions: &mut Vec<Definition>) {
 
     populate(&text_indices, definitions);
 }
+
+/// A comment token found by a parser
+#[derive(Debug, Clone)]
+pub(crate) struct Comment {
+    /// The utf-8 byte offsets of the comment
+    pub span: Range<usize>,
+
+    /// Whether the comment is a doc-comment (`///` or `/** */`)
+    pub doc: bool,
+}
+
+/// Find the comments which look like documentation but are not part of the [`NatSpec`][crate::natspec::NatSpec] of
+/// any source item
+///
+/// The doc-comments inside of the `attached` ranges were consumed by a source item, each range ending at the start of
+/// the item. They are only reported if they are separated from the item by an empty line. All comments inside of the
+/// `skipped` ranges (e.g. function bodies) are ignored. The ranges in each list must not overlap. Consecutive
+/// single-line comments are reported as a single [`CommentDefinition`]. The parent of a comment is the item of the
+/// `containers` (contracts, interfaces and libraries, without their doc-comments) which contains it.
+///
+/// The returned definitions have complete text ranges.
+pub(crate) fn find_stray_comments(
+    source: &str,
+    containers: &[(Range<usize>, Parent)],
+    comments: impl IntoIterator<Item = Comment>,
+    mut attached: Vec<Range<usize>>,
+    mut skipped: Vec<Range<usize>>,
+) -> Vec<Definition> {
+    fn contains(ranges: &[Range<usize>], offset: usize) -> bool {
+        let idx = ranges.partition_point(|r| r.start <= offset);
+        idx > 0 && ranges[idx - 1].end > offset
+    }
+    attached.sort_unstable_by_key(|r| r.start);
+    skipped.sort_unstable_by_key(|r| r.start);
+    let comments: Vec<_> = comments.into_iter().collect();
+    // the attached doc-comments whose last comment is followed by an empty line before the item
+    let detached: Vec<_> = attached
+        .iter()
+        .filter(|range| {
+            comments
+                .iter()
+                .rfind(|c| c.doc && range.contains(&c.span.start))
+                .is_some_and(|c| {
+                    let lines: Vec<_> = source[c.span.end..range.end].split('\n').collect();
+                    lines.len() > 2
+                        && lines[1..lines.len() - 1]
+                            .iter()
+                            .any(|l| l.trim().is_empty())
+                })
+        })
+        .cloned()
+        .collect();
+    let mut stray: Vec<(Range<usize>, StrayKind)> = Vec::new();
+    for comment in comments {
+        let offset = comment.span.start;
+        if contains(&skipped, offset) {
+            continue;
+        }
+        let text = &source[comment.span.clone()];
+        let kind = if comment.doc {
+            if contains(&detached, offset) {
+                StrayKind::Detached
+            } else if contains(&attached, offset) {
+                continue;
+            } else {
+                StrayKind::Orphan
+            }
+        } else if contains_tags(text) {
+            StrayKind::Regular
+        } else {
+            continue;
+        };
+        // merge consecutive single-line comments of the same kind
+        if let Some((last_span, last_kind)) = stray.last_mut()
+            && *last_kind == kind
+            && text.starts_with("//")
+            && source[last_span.clone()].starts_with("//")
+            && source[last_span.end..offset]
+                .chars()
+                .filter(|c| !c.is_whitespace() || *c == '\n')
+                .eq(['\n'])
+        {
+            last_span.end = comment.span.end;
+            continue;
+        }
+        stray.push((comment.span, kind));
+    }
+    let mut definitions: Vec<_> = stray
+        .into_iter()
+        .map(|(span, kind)| {
+            let parent = containers
+                .iter()
+                .find(|(range, _)| range.contains(&span.start))
+                .map(|(_, parent)| parent.clone());
+            let span = TextIndex {
+                utf8: span.start,
+                ..Default::default()
+            }..TextIndex {
+                utf8: span.end,
+                ..Default::default()
+            };
+            CommentDefinition { parent, span, kind }.into()
+        })
+        .collect();
+    complete_text_ranges(source, &mut definitions);
+    definitions
+}
+
+/// Check whether a comment contains a line starting with a `NatSpec` tag
+fn contains_tags(comment: &str) -> bool {
+    comment.lines().any(|line| {
+        let line = line.trim_start_matches(|c: char| c.is_whitespace() || c == '/' || c == '*');
+        line.strip_prefix('@').is_some_and(|rest| {
+            let tag = rest.split_whitespace().next().unwrap_or_default();
+            TAGS.contains(&tag) || tag.starts_with("custom:")
+        })
+    })
+}
@@ -4,6 +4,7 @@
 use std::{
     collections::HashMap,
     io,
+    ops::Range,
     path::{Path, PathBuf},
     sync::{Arc, Mutex},
 };
@@ -17,6 +18,7 @@ use slang_solidity::{
 };
 
 use crate::{
+    definitions::normalize_type,
     definitions::{
         Attributes, Definition, Identifier, Parent, Visibility, constructor::ConstructorDefinition,
         contract::ContractDefinition, enumeration::EnumDefinition, error::ErrorDefinition,
@@ -25,7 +27,7 @@ use crate::{
         variable::VariableDeclaration,
     },
     error::{ErrorKind, Result},
-    interner::INTERNER,
+    interner::{INTERNER, Symbol},
     natspec::{NatSpec, parse_comment},
     parser::DocumentId,
     prelude::OrPanic as _,
@@ -33,7 +35,7 @@ use crate::{
     utils::{detect_solidity_version, get_latest_supported_version},
 };
 
-use super::{Parse, ParsedDocument, complete_text_ranges};
+use super::{Comment, Parse, ParsedDocument, complete_text_ranges, find_stray_comments};
 
 /// A parser that uses [`slang_solidity`] to identify source items
 #[derive(Debug, Clone, Default, bon::Builder)]
@@ -170,8 +172,11 @@ impl Parse for SlangParser {
             }
             let document_id = DocumentId::new();
             let cursor = output.create_tree_cursor();
+            let imports = extract_imports(&cursor);
+            let (stray_comments, comments) = find_comments(&cursor, &source);
             let mut definitions = SlangParser::find_items(cursor);
             complete_text_ranges(&source, &mut definitions);
+            definitions.extend(stray_comments);
             if keep_contents {
                 let mut documents = this
                     .documents
@@ -181,6 +186,8 @@ impl Parse for SlangParser {
             }
             Ok(ParsedDocument {
                 definitions,
+                imports,
+                comments,
                 id: document_id,
             })
         }
@@ -233,6 +240,7 @@ impl Extract for ConstructorDefinition {
 
         let params = extract_params(&params, NonterminalKind::Parameter);
         let natspec = extract_comment(&constructor.clone(), &[])?;
+        let reverts = extract_reverts(&constructor);
         let parent = extract_parent_name(constructor);
 
         Ok(ConstructorDefinition {
@@ -240,6 +248,7 @@ impl Extract for ConstructorDefinition {
             span,
             params,
             natspec,
+            reverts,
         }
         .into())
     }
@@ -296,6 +305,7 @@ impl Extract for ErrorDefinition {
 
         let span = find_definition_start(&err)..find_definition_end(&err);
         let name = INTERNER.get_or_intern(name.node().unparse().trim());
+        let param_types = extract_param_types(&params, NonterminalKind::ErrorParameter);
         let params = extract_identifiers(&params);
         let natspec = extract_comment(&err.clone(), &[])?;
         let parent = extract_parent_name(err);
@@ -305,6 +315,7 @@ impl Extract for ErrorDefinition {
             name,
             span,
             params,
+            param_types,
             natspec,
         }
         .into())
@@ -329,6 +340,7 @@ impl Extract for EventDefinition {
 
         let span = find_definition_start(&event)..find_definition_end(&event);
         let name = INTERNER.get_or_intern(name.node().unparse().trim());
+        let param_types = extract_param_types(&params, NonterminalKind::EventParameter);
         let params = extract_params(&params, NonterminalKind::EventParameter);
         let natspec = extract_comment(&event.clone(), &[])?;
         let parent = extract_parent_name(event);
@@ -338,6 +350,7 @@ impl Extract for EventDefinition {
             name,
             span,
             params,
+            param_types,
             natspec,
         }
         .into())
@@ -378,11 +391,13 @@ impl Extract for FunctionDefinition {
             .map_or_else(|| attributes.text_range().end.into(), find_definition_end);
         let span = span_start..span_end;
         let name = INTERNER.get_or_intern(name.node().unparse().trim());
+        let param_types = extract_param_types(&params, NonterminalKind::Parameter);
         let params = extract_params(&params, NonterminalKind::Parameter);
         let returns = returns
             .map(|r| extract_params(&r, NonterminalKind::Parameter))
             .unwrap_or_default();
         let natspec = extract_comment(&func.clone(), &returns)?;
+        let reverts = extract_reverts(&func);
         let parent = extract_parent_name(func);
 
         Ok(FunctionDefinition {
@@ -390,9 +405,12 @@ impl Extract for FunctionDefinition {
             name,
             span,
             params,
+            param_types,
             returns,
             natspec,
             attributes: extract_attributes(&attributes),
+            overrides: extract_overrides(&attributes),
+            reverts,
         }
         .into())
     }
@@ -427,6 +445,7 @@ impl Extract for ModifierDefinition {
             .unwrap_or_default();
 
         let natspec = extract_comment(&modifier.clone(), &[])?;
+        let reverts = extract_reverts(&modifier);
         let parent = extract_parent_name(modifier);
 
         Ok(ModifierDefinition {
@@ -436,6 +455,7 @@ impl Extract for ModifierDefinition {
             params,
             natspec,
             attributes: extract_attributes(&attr),
+            reverts,
         }
         .into())
     }
@@ -459,6 +479,7 @@ impl Extract for StructDefinition {
 
         let span = find_definition_start(&structure)..find_definition_end(&structure);
         let name = INTERNER.get_or_intern(name.node().unparse().trim());
+        let member_types = extract_param_types(&members, NonterminalKind::StructMember);
         let members = extract_struct_members(&members)?;
         let natspec = extract_comment(&structure.clone(), &[])?;
         let parent = extract_parent_name(structure);
@@ -468,6 +489,7 @@ impl Extract for StructDefinition {
             name,
             span,
             members,
+            member_types,
             natspec,
         }
         .into())
@@ -478,6 +500,7 @@ impl Extract for VariableDeclaration {
     fn query() -> Query {
         Query::create(
             "@variable [StateVariableDefinition
+            @variable_type type_name:[TypeName]
             @variable_attr attributes:[StateVariableAttributes]
             @variable_name name:[Identifier]
         ]",
@@ -487,6 +510,7 @@ impl Extract for VariableDeclaration {
 
     fn extract(m: QueryMatch) -> Result<Definition> {
         let variable = capture(&m, "variable")?;
+        let type_name = capture(&m, "variable_type")?;
         let attributes = capture(&m, "variable_attr")?;
         let name = capture(&m, "variable_name")?;
 
@@ -499,8 +523,10 @@ impl Extract for VariableDeclaration {
             parent,
             name,
             span,
+            type_name: normalize_type(&unparse_without_trivia(&type_name)),
             natspec,
             attributes: extract_attributes(&attributes),
+            overrides: extract_overrides(&attributes),
         }
         .into())
     }
@@ -529,10 +555,12 @@ impl Extract for ContractDefinition {
         let span = span_start..span_end;
         let name = INTERNER.get_or_intern(name.node().unparse().trim());
         let natspec = extract_comment(&contract.clone(), &[])?;
+        let bases = extract_bases(&contract);
 
         Ok(ContractDefinition {
             name,
             span,
+            bases,
             natspec,
         }
         .into())
@@ -562,10 +590,12 @@ impl Extract for InterfaceDefinition {
         let span = span_start..span_end;
         let name = INTERNER.get_or_intern(name.node().unparse().trim());
         let natspec = extract_comment(&iface.clone(), &[])?;
+        let bases = extract_bases(&iface);
 
         Ok(InterfaceDefinition {
             name,
             span,
+            bases,
             natspec,
         }
         .into())
@@ -652,32 +682,100 @@ pub fn extract_params(cursor: &Cursor, kind: NonterminalKind) -> Vec<Identifier>
     out
 }
 
+/// Extract the normalized parameter types from a function-like source item.
+///
+/// The node kind that holds the `TypeName` (`Parameter`, `EventParameter`) must be provided with `kind`.
+#[must_use]
+pub fn extract_param_types(cursor: &Cursor, kind: NonterminalKind) -> Vec<String> {
+    let mut cursor = cursor.spawn();
+    let mut out = Vec::new();
+    while cursor.go_to_next_nonterminal_with_kind(kind) {
+        let mut sub_cursor = cursor.spawn();
+        if sub_cursor.go_to_next_nonterminal_with_kind(NonterminalKind::TypeName) {
+            out.push(normalize_type(&unparse_without_trivia(&sub_cursor)));
+        } else {
+            out.push(String::new());
+        }
+    }
+    out
+}
+
+/// Retrieve the source code of a node without its trivia (whitespace and comments)
+///
+/// Consecutive words (e.g. `address payable`) are separated by a space.
+fn unparse_without_trivia(cursor: &Cursor) -> String {
+    let is_word = |c: char| c.is_alphanumeric() || c == '_' || c == '$';
+    let mut cursor = cursor.spawn();
+    let mut out = String::new();
+    while cursor.go_to_next_terminal() {
+        if cursor.node().is_trivia() {
+            continue;
+        }
+        let text = cursor.node().unparse();
+        if out.ends_with(is_word) && text.starts_with(is_word) {
+            out.push(' ');
+        }
+        out.push_str(&text);
+    }
+    out
+}
+
 /// Extract and parse the [`NatSpec`] comment information, if any
+///
+/// Consecutive single-line comments (without empty lines in between) are parsed as a single comment, so that lines
+/// without a tag continue the description of the previous tag.
 pub fn extract_comment(cursor: &Cursor, returns: &[Identifier]) -> Result<Option<NatSpec>> {
     let mut cursor = cursor.spawn();
-    let mut items = Vec::new();
+    // the node type to differentiate multiline from single line, the cursor at the (first) comment node, the line
+    // numbers of the start and end of the comment, and the text of the comment
+    let mut comments: Vec<(String, Cursor, usize, usize, String)> = Vec::new();
+    // the whitespace following a single-line comment, to merge it with the next one if they are on consecutive lines
+    let mut gap: Option<String> = None;
     while cursor.go_to_next() {
         if cursor.node().is_terminal_with_kinds(&[
             TerminalKind::MultiLineNatSpecComment,
             TerminalKind::SingleLineNatSpecComment,
         ]) {
             let comment = &cursor.node().unparse();
-            let mut trimmed = comment.trim_start();
+            let trimmed = comment.trim_start();
             if trimmed.starts_with("////") || trimmed.starts_with("/***") {
                 // avoid a parsing error in those cases, we simply ignore those as if they were non-NatSpec comments
+                gap = None;
                 continue;
             }
-            items.push((
-                cursor.node().kind().to_string(), // the node type to differentiate multiline from single line
-                cursor.text_range().start.line, // the line number to remove unwanted single-line comments
-                parse_comment(&mut trimmed)
-                    .map_err(|e| ErrorKind::NatspecParsingError {
-                        parent: extract_parent_name(cursor.clone()),
-                        span: textrange(cursor.text_range()),
-                        message: e.to_string(),
-                    })?
-                    .populate_returns(returns),
-            ));
+            let kind = cursor.node().kind().to_string();
+            let range = cursor.text_range();
+            let is_single_line = kind == "SingleLineNatSpecComment";
+            match (gap.take(), comments.last_mut()) {
+                (Some(whitespace), Some((last_kind, _, _, end_line, text)))
+                    if is_single_line
+                        && last_kind == "SingleLineNatSpecComment"
+                        && whitespace.matches('\n').count() == 1 =>
+                {
+                    text.push_str(&whitespace);
+                    text.push_str(trimmed);
+                    *end_line = range.end.line;
+                }
+                _ => {
+                    comments.push((
+                        kind,
+                        cursor.clone(),
+                        range.start.line,
+                        range.end.line,
+                        trimmed.to_string(),
+                    ));
+                }
+            }
+            if is_single_line {
+                gap = Some(String::new());
+            }
+        } else if cursor
+            .node()
+            .is_terminal_with_kinds(&[TerminalKind::Whitespace, TerminalKind::EndOfLine])
+        {
+            if let Some(whitespace) = &mut gap {
+                whitespace.push_str(&cursor.node().unparse());
+            }
         } else if cursor.node().is_terminal_with_kinds(&[
             TerminalKind::ContractKeyword,
             TerminalKind::InterfaceKeyword,
@@ -695,10 +793,34 @@ pub fn extract_comment(cursor: &Cursor, returns: &[Identifier]) -> Result<Option
         {
             // anything after this node should be ignored, because we enter the item's body
             break;
+        } else if cursor.node().is_terminal() {
+            gap = None;
         }
     }
-    if let Some("MultiLineNatSpecComment") = items.last().map(|(kind, _, _)| kind.as_str())
-        && let Some((_, _, natspec)) = items.pop()
+    parse_comments(comments, returns)
+}
+
+/// Parse the comments found by [`extract_comment`] and keep the ones which are attached to the source item
+///
+/// If the last comment is multiline, it's the only one kept. Otherwise, the single-line comments up to an empty line
+/// or a multiline comment are kept.
+fn parse_comments(
+    comments: Vec<(String, Cursor, usize, usize, String)>,
+    returns: &[Identifier],
+) -> Result<Option<NatSpec>> {
+    let mut items = Vec::with_capacity(comments.len());
+    for (kind, comment_cursor, start_line, end_line, text) in comments {
+        let natspec = parse_comment(&mut text.as_str())
+            .map_err(|e| ErrorKind::NatspecParsingError {
+                parent: extract_parent_name(comment_cursor.clone()),
+                span: textrange(comment_cursor.text_range()),
+                message: e.to_string(),
+            })?
+            .populate_returns(returns);
+        items.push((kind, start_line, end_line, natspec));
+    }
+    if let Some("MultiLineNatSpecComment") = items.last().map(|(kind, _, _, _)| kind.as_str())
+        && let Some((_, _, _, natspec)) = items.pop()
     {
         // if the last comment is multiline, we ignore all previous comments
         return Ok(Some(natspec));
@@ -707,9 +829,9 @@ pub fn extract_comment(cursor: &Cursor, returns: &[Identifier]) -> Result<Option
     // we need to take the comments (in reverse) up to an empty line or a multiline comment (exclusive)
     let mut res = Vec::new();
     let mut iter = items.into_iter().rev().peekable();
-    while let Some((_, item_line, item)) = iter.next() {
+    while let Some((_, item_line, _, item)) = iter.next() {
         res.push(item);
-        if let Some((next_kind, next_line, _)) = iter.peek()
+        if let Some((next_kind, _, next_line, _)) = iter.peek()
             && (next_kind == "MultiLineNatSpecComment" || *next_line < item_line - 1)
         {
             // the next comments up should be ignored
@@ -728,6 +850,177 @@ pub fn extract_comment(cursor: &Cursor, returns: &[Identifier]) -> Result<Option
     )))
 }
 
+/// Extract the names of the bases from the inheritance specifier of a contract or interface node
+///
+/// For qualified names (e.g. `Lib.Base`), only the last identifier is kept.
+#[must_use]
+pub fn extract_bases(cursor: &Cursor) -> Vec<Symbol> {
+    let mut cursor = cursor.spawn();
+    let mut out = Vec::new();
+    if !cursor.go_to_next_nonterminal_with_kind(NonterminalKind::InheritanceSpecifier) {
+        return out;
+    }
+    let mut cursor = cursor.spawn();
+    while cursor.go_to_next_nonterminal_with_kind(NonterminalKind::IdentifierPath) {
+        if cursor.label().to_string() != "type_name" {
+            continue;
+        }
+        if let Some(name) = cursor.node().unparse().split('.').next_back() {
+            out.push(INTERNER.get_or_intern(name.trim()));
+        }
+    }
+    out
+}
+
+/// Extract the paths of the `import` directives from a CST node
+#[must_use]
+pub fn extract_imports(cursor: &Cursor) -> Vec<String> {
+    let mut cursor = cursor.spawn();
+    let mut out = Vec::new();
+    while cursor.go_to_next_nonterminal_with_kind(NonterminalKind::ImportDirective) {
+        let mut directive = cursor.spawn();
+        if directive.go_to_next_terminal_with_kinds(&[
+            TerminalKind::DoubleQuotedStringLiteral,
+            TerminalKind::SingleQuotedStringLiteral,
+        ]) {
+            let literal = directive.node().unparse();
+            out.push(literal.trim().trim_matches(['"', '\'']).to_string());
+        }
+    }
+    out
+}
+
+/// Find the comments which look like documentation but are not part of the [`NatSpec`] of any source item, from a
+/// root CST node
+///
+/// The doc-comments which are attached to an item are the same as the ones used by [`extract_comment`]. The comments
+/// inside of items other than contracts, interfaces and libraries are ignored.
+///
+/// The span of all the regular comments (`//` and `/* */`) is also returned, see [`ParsedDocument::comments`].
+#[must_use]
+pub fn find_comments(cursor: &Cursor, source: &str) -> (Vec<Definition>, Vec<Range<usize>>) {
+    let mut cursor = cursor.spawn();
+    let mut comments = Vec::new();
+    let mut containers = Vec::new();
+    let mut attached = Vec::new();
+    let mut skipped = Vec::new();
+    while cursor.go_to_next() {
+        if cursor.node().is_terminal_with_kinds(&[
+            TerminalKind::SingleLineComment,
+            TerminalKind::MultiLineComment,
+            TerminalKind::SingleLineNatSpecComment,
+            TerminalKind::MultiLineNatSpecComment,
+        ]) {
+            let range = cursor.text_range();
+            comments.push(Comment {
+                span: range.start.utf8..range.end.utf8,
+                doc: is_natspec(&cursor),
+            });
+        } else if cursor.node().is_nonterminal_with_kinds(&[
+            NonterminalKind::ContractDefinition,
+            NonterminalKind::InterfaceDefinition,
+            NonterminalKind::LibraryDefinition,
+        ]) {
+            let (docs_start, item_start) = find_attached_comments(&cursor);
+            if let Some(docs_start) = docs_start {
+                attached.push(docs_start..item_start);
+            }
+            let mut child = cursor.clone();
+            if child.go_to_first_child()
+                && let Some(parent) = extract_parent_name(child)
+            {
+                containers.push((item_start..cursor.text_range().end.utf8, parent));
+            }
+        } else if cursor.node().is_nonterminal_with_kinds(&[
+            NonterminalKind::ConstructorDefinition,
+            NonterminalKind::EnumDefinition,
+            NonterminalKind::ErrorDefinition,
+            NonterminalKind::EventDefinition,
+            NonterminalKind::FunctionDefinition,
+            NonterminalKind::ModifierDefinition,
+            NonterminalKind::StructDefinition,
+            NonterminalKind::StateVariableDefinition,
+            NonterminalKind::ConstantDefinition,
+            NonterminalKind::ReceiveFunctionDefinition,
+            NonterminalKind::FallbackFunctionDefinition,
+            NonterminalKind::UnnamedFunctionDefinition,
+        ]) {
+            let (docs_start, item_start) = find_attached_comments(&cursor);
+            if let Some(docs_start) = docs_start {
+                attached.push(docs_start..item_start);
+            }
+            skipped.push(item_start..cursor.text_range().end.utf8);
+        }
+    }
+    let regular = comments
+        .iter()
+        .filter(|c| !c.doc)
+        .map(|c| c.span.clone())
+        .collect();
+    (
+        find_stray_comments(source, &containers, comments, attached, skipped),
+        regular,
+    )
+}
+
+/// Find the start offset of the doc-comments which are attached to a source item, if any, and the start offset of
+/// the item itself (without its leading trivia)
+///
+/// The comments are selected like in [`parse_comments`].
+fn find_attached_comments(cursor: &Cursor) -> (Option<usize>, usize) {
+    let mut item_start = cursor.text_range().start.utf8;
+    let mut cursor = cursor.spawn();
+    // the start offset, whether it's multiline, and the line numbers of the start and end of each doc-comment
+    let mut docs: Vec<(usize, bool, usize, usize)> = Vec::new();
+    while cursor.go_to_next() {
+        if !cursor.node().is_terminal() {
+            continue;
+        }
+        let range = cursor.text_range();
+        if !cursor.node().is_trivia() {
+            item_start = range.start.utf8;
+            break;
+        }
+        if is_natspec(&cursor) {
+            let multiline = cursor
+                .node()
+                .is_terminal_with_kind(TerminalKind::MultiLineNatSpecComment);
+            docs.push((
+                range.start.utf8,
+                multiline,
+                range.start.line,
+                range.end.line,
+            ));
+        }
+    }
+    let Some((mut docs_start, multiline, mut start_line, _)) = docs.pop() else {
+        return (None, item_start);
+    };
+    if !multiline {
+        // take the single-line comments up to an empty line or a multiline comment
+        while let Some((start, false, line, end_line)) = docs.pop()
+            && end_line + 1 == start_line
+        {
+            docs_start = start;
+            start_line = line;
+        }
+    }
+    (Some(docs_start), item_start)
+}
+
+/// Check whether a CST node is a `NatSpec` comment (`////` and `/***` are regular comments)
+fn is_natspec(cursor: &Cursor) -> bool {
+    if !cursor.node().is_terminal_with_kinds(&[
+        TerminalKind::SingleLineNatSpecComment,
+        TerminalKind::MultiLineNatSpecComment,
+    ]) {
+        return false;
+    }
+    let comment = cursor.node().unparse();
+    let comment = comment.trim_start();
+    !comment.starts_with("////") && !comment.starts_with("/***")
+}
+
 /// Extract identifiers from a CST node, filtered by label equal to `name`
 #[must_use]
 pub fn extract_identifiers(cursor: &Cursor) -> Vec<Identifier> {
@@ -774,6 +1067,39 @@ pub fn extract_attributes(cursor: &Cursor) -> Attributes {
     out
 }
 
+/// Extract the names of the bases listed in the `override(...)` specifier of a function or state variable, if any
+#[must_use]
+pub fn extract_overrides(cursor: &Cursor) -> Vec<Symbol> {
+    let mut cursor = cursor.spawn();
+    let mut out = Vec::new();
+    if !cursor.go_to_next_nonterminal_with_kind(NonterminalKind::OverrideSpecifier) {
+        return out;
+    }
+    let mut cursor = cursor.spawn();
+    while cursor.go_to_next_nonterminal_with_kind(NonterminalKind::IdentifierPath) {
+        if let Some(name) = cursor.node().unparse().split('.').next_back() {
+            out.push(INTERNER.get_or_intern(name.trim()));
+        }
+    }
+    out
+}
+
+/// Extract the paths of the errors used by the `revert` statements of a function, modifier or constructor
+#[must_use]
+pub fn extract_reverts(cursor: &Cursor) -> Vec<String> {
+    let mut cursor = cursor.spawn();
+    let mut out = Vec::new();
+    while cursor.go_to_next_nonterminal_with_kind(NonterminalKind::RevertStatement) {
+        let mut path = cursor.spawn();
+        if path.go_to_next_nonterminal_with_kind(NonterminalKind::IdentifierPath) {
+            let path = path.node().unparse();
+            let segments: Vec<_> = path.split('.').map(str::trim).collect();
+            out.push(segments.join("."));
+        }
+    }
+    out
+}
+
 /// Find the parent's name (contract, interface, library), if any
 #[must_use]
 pub fn extract_parent_name(mut cursor: Cursor) -> Option<Parent> {
@@ -992,6 +1318,16 @@ mod tests {
         };
     }
 
+    fn text_index(utf8: usize, line: u32, col: u32) -> TextIndex {
+        TextIndex {
+            utf8,
+            line,
+            col_utf8: col,
+            col_utf16: col,
+            col_utf32: col,
+        }
+    }
+
     #[expect(clippy::cast_possible_truncation)]
     fn single_line_textrange(range: Range<usize>) -> TextRange {
         TextIndex {
@@ -1024,6 +1360,65 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_parse_param_types_and_overrides() {
+        let cursor = parse_file(
+            "contract C {
+                event Foo(address indexed from, uint);
+                function bar(uint[] calldata a, Lib.Data memory, address payable) external onlyOwner override(IA, Lib.IB) {}
+            }",
+        );
+        let items = SlangParser::find_items(cursor);
+        let parent = Some(Parent::Contract("C"));
+        assert_eq!(
+            find_event("Foo", parent.clone(), &items).param_types,
+            vec!["address", "uint256"]
+        );
+        let bar = find_function("bar", parent, &items);
+        assert_eq!(
+            bar.param_types,
+            vec!["uint256[]", "Data", "address payable"]
+        );
+        assert_eq!(
+            bar.overrides,
+            vec![INTERNER.get_or_intern("IA"), INTERNER.get_or_intern("IB")]
+        );
+    }
+
+    #[test]
+    fn test_parse_bases() {
+        let cursor = parse_file(
+            "interface IA {}
+            interface IB is IA {}
+            contract C is IB, Lib.D(1) {}",
+        );
+        let items = SlangParser::find_items(cursor);
+        assert_eq!(
+            find_interface("IB", &items).bases,
+            vec![INTERNER.get_or_intern("IA")]
+        );
+        assert_eq!(find_interface("IA", &items).bases, vec![]);
+        assert_eq!(
+            find_contract("C", &items).bases,
+            vec![INTERNER.get_or_intern("IB"), INTERNER.get_or_intern("D")]
+        );
+    }
+
+    #[test]
+    fn test_parse_imports() {
+        let cursor = parse_file(
+            "import './A.sol';
+            import {B} from \"lib/B.sol\";
+            import * as C from '../C.sol';
+            import 'D.sol' as D;
+            contract E {}",
+        );
+        assert_eq!(
+            extract_imports(&cursor),
+            vec!["./A.sol", "lib/B.sol", "../C.sol", "D.sol"]
+        );
+    }
+
     #[test]
     fn test_parse_interface() {
         let cursor = parse_file(include_str!("../../test-data/InterfaceSample.sol"));
@@ -1076,7 +1471,8 @@ mod tests {
                 NatSpecItem {
                     kind: NatSpecKind::Dev,
                     comment: "Dev comment for the function".to_string(),
-                    span: single_line_textrange(4..37)
+                    span: text_index(adjust_offset_windows!(34, 1), 1, 6)
+                        ..text_index(adjust_offset_windows!(67, 1), 1, 39)
                 }
             ]
         );
@@ -1138,7 +1534,8 @@ mod tests {
                         name: INTERNER.get_or_intern("_param1")
                     },
                     comment: "The only parameter".to_string(),
-                    span: single_line_textrange(4..37)
+                    span: text_index(adjust_offset_windows!(50, 1), 1, 6)
+                        ..text_index(adjust_offset_windows!(83, 1), 1, 39)
                 },
             ]
         );
@@ -1179,21 +1576,24 @@ mod tests {
                 NatSpecItem {
                     kind: NatSpecKind::Dev,
                     comment: "Dev comment for the private function".to_string(),
-                    span: single_line_textrange(4..45)
+                    span: text_index(adjust_offset_windows!(37, 1), 1, 6)
+                        ..text_index(adjust_offset_windows!(78, 1), 1, 47)
                 },
                 NatSpecItem {
                     kind: NatSpecKind::Param {
                         name: INTERNER.get_or_intern("_paramName")
                     },
                     comment: "The parameter name".to_string(),
-                    span: single_line_textrange(4..40)
+                    span: text_index(adjust_offset_windows!(85, 2), 2, 6)
+                        ..text_index(adjust_offset_windows!(121, 2), 2, 42)
                 },
                 NatSpecItem {
                     kind: NatSpecKind::Return {
                         name: Some(INTERNER.get_or_intern("_returned"))
                     },
                     comment: "The returned value".to_string(),
-                    span: single_line_textrange(4..40)
+                    span: text_index(adjust_offset_windows!(128, 3), 3, 6)
+                        ..text_index(adjust_offset_windows!(164, 3), 3, 42)
                 }
             ]
         );
@@ -1210,23 +1610,11 @@ mod tests {
         );
         assert_eq!(
             item.natspec.as_ref().unwrap().items,
-            vec![
-                NatSpecItem {
-                    kind: NatSpecKind::Notice,
-                    comment: "Some internal stuff".to_string(),
-                    span: single_line_textrange(4..31)
-                },
-                NatSpecItem {
-                    kind: NatSpecKind::Notice,
-                    comment: "Separate line".to_string(),
-                    span: single_line_textrange(12..25)
-                },
-                NatSpecItem {
-                    kind: NatSpecKind::Notice,
-                    comment: "Third one".to_string(),
-                    span: single_line_textrange(12..21)
-                },
-            ]
+            vec![NatSpecItem {
+                kind: NatSpecKind::Notice,
+                comment: "Some internal stuff\nSeparate line\nThird one".to_string(),
+                span: text_index(4, 0, 4)..text_index(adjust_offset_windows!(83, 2), 2, 23)
+            }]
         );
     }
 
@@ -1250,7 +1638,8 @@ mod tests {
                 NatSpecItem {
                     kind: NatSpecKind::Notice,
                     comment: "Separate line".to_string(),
-                    span: single_line_textrange(4..25)
+                    span: text_index(adjust_offset_windows!(38, 1), 1, 6)
+                        ..text_index(adjust_offset_windows!(59, 1), 1, 27)
                 },
             ]
         );
@@ -1316,19 +1705,22 @@ mod tests {
                         name: INTERNER.get_or_intern("a")
                     },
                     comment: "The first variable".to_string(),
-                    span: single_line_textrange(4..32)
+                    span: text_index(adjust_offset_windows!(63, 1), 1, 6)
+                        ..text_index(adjust_offset_windows!(91, 1), 1, 34)
                 },
                 NatSpecItem {
                     kind: NatSpecKind::Param {
                         name: INTERNER.get_or_intern("b")
                     },
                     comment: "The second variable".to_string(),
-                    span: single_line_textrange(4..33)
+                    span: text_index(adjust_offset_windows!(98, 2), 2, 6)
+                        ..text_index(adjust_offset_windows!(127, 2), 2, 35)
                 },
                 NatSpecItem {
                     kind: NatSpecKind::Dev,
                     comment: "This is definitely a struct".to_string(),
-                    span: single_line_textrange(4..36)
+                    span: text_index(adjust_offset_windows!(134, 3), 3, 6)
+                        ..text_index(adjust_offset_windows!(166, 3), 3, 38)
                 },
             ]
         );
@@ -1354,12 +1746,14 @@ mod tests {
                 NatSpecItem {
                     kind: NatSpecKind::Dev,
                     comment: "Natspec for the return value is missing".to_string(),
-                    span: single_line_textrange(4..48)
+                    span: text_index(adjust_offset_windows!(51, 1), 1, 6)
+                        ..text_index(adjust_offset_windows!(95, 1), 1, 50)
                 },
                 NatSpecItem {
                     kind: NatSpecKind::Return { name: None },
                     comment: "The returned value".to_string(),
-                    span: single_line_textrange(4..30)
+                    span: text_index(adjust_offset_windows!(102, 2), 2, 6)
+                        ..text_index(adjust_offset_windows!(128, 2), 2, 32)
                 },
             ]
         );
@@ -1487,7 +1881,8 @@ mod tests {
                 NatSpecItem {
                     kind: NatSpecKind::Dev,
                     comment: "Providing context".to_string(),
-                    span: single_line_textrange(4..26)
+                    span: text_index(adjust_offset_windows!(34, 1), 1, 6)
+                        ..text_index(adjust_offset_windows!(56, 1), 1, 28)
                 }
             ]
         );
@@ -1610,14 +2005,16 @@ mod tests {
                         name: INTERNER.get_or_intern("_paramName")
                     },
                     comment: "The parameter name".to_string(),
-                    span: single_line_textrange(4..45)
+                    span: text_index(adjust_offset_windows!(39, 1), 1, 6)
+                        ..text_index(adjust_offset_windows!(80, 1), 1, 47)
                 },
                 NatSpecItem {
                     kind: NatSpecKind::Return {
                         name: Some(INTERNER.get_or_intern("_returned"))
                     },
                     comment: "The returned value".to_string(),
-                    span: single_line_textrange(4..48)
+                    span: text_index(adjust_offset_windows!(87, 2), 2, 6)
+                        ..text_index(adjust_offset_windows!(131, 2), 2, 50)
                 },
             ]
         );
@@ -1655,7 +2052,8 @@ mod tests {
                 NatSpecItem {
                     kind: NatSpecKind::Dev,
                     comment: "What have I done".to_string(),
-                    span: single_line_textrange(4..30)
+                    span: text_index(adjust_offset_windows!(33, 1), 1, 9)
+                        ..text_index(adjust_offset_windows!(59, 1), 1, 35)
                 }
             ]
         );
@@ -1681,12 +2079,14 @@ mod tests {
                 NatSpecItem {
                     kind: NatSpecKind::Return { name: None },
                     comment: String::new(),
-                    span: single_line_textrange(4..18)
+                    span: text_index(adjust_offset_windows!(64, 1), 1, 6)
+                        ..text_index(adjust_offset_windows!(78, 1), 1, 20)
                 },
                 NatSpecItem {
                     kind: NatSpecKind::Return { name: None },
                     comment: String::new(),
-                    span: single_line_textrange(4..11)
+                    span: text_index(adjust_offset_windows!(85, 2), 2, 6)
+                        ..text_index(adjust_offset_windows!(92, 2), 2, 13)
                 },
             ]
         );
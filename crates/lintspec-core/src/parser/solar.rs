@@ -4,17 +4,17 @@
 use std::{
     collections::HashMap,
     io,
-    ops::ControlFlow,
+    ops::{ControlFlow, Range},
     path::{Path, PathBuf},
     str::FromStr as _,
     sync::{Arc, Mutex},
 };
 
 use solar_parse::{
-    Parser,
+    Cursor, Parser,
     ast::{
-        ContractKind, DocComments, FunctionKind, Item, ItemContract, ItemKind, ParameterList, Span,
-        Spanned, VariableDefinition,
+        CommentKind, ContractKind, DocComments, FunctionKind, Item, ItemContract, ItemKind,
+        Override, ParameterList, Span, Spanned, Stmt, StmtKind, VariableDefinition,
         interface::{
             Session,
             source_map::{FileName, SourceMap},
@@ -22,9 +22,11 @@ use solar_parse::{
         visit::Visit,
     },
     interface::{ColorChoice, source_map::SourceFile},
+    lexer::token::RawTokenKind,
 };
 
 use crate::{
+    definitions::normalize_type,
     definitions::{
         Attributes, Definition, Identifier, Parent, Visibility, constructor::ConstructorDefinition,
         contract::ContractDefinition, enumeration::EnumDefinition, error::ErrorDefinition,
@@ -33,11 +35,13 @@ use crate::{
         variable::VariableDeclaration,
     },
     error::{ErrorKind, Result},
-    interner::INTERNER,
+    interner::{INTERNER, Symbol},
     natspec::{NatSpec, parse_comment},
-    parser::{DocumentId, Parse, ParsedDocument, complete_text_ranges},
+    parser::{
+        Comment, DocumentId, Parse, ParsedDocument, complete_text_ranges, find_stray_comments,
+    },
     prelude::OrPanic as _,
-    textindex::{TextIndex, TextRange},
+    textindex::{TextIndex, TextRange, compute_indices},
 };
 
 type Documents = Vec<(DocumentId, Arc<SourceFile>)>;
@@ -99,7 +103,7 @@ impl Parse for SolarParser {
                     err,
                 })?;
 
-            let mut definitions = this
+            let (mut definitions, imports, containers, attached, skipped) = this
                 .sess
                 .enter_sequential(|| -> solar_parse::interface::Result<_> {
                     let arena = solar_parse::ast::Arena::new();
@@ -110,7 +114,13 @@ impl Parse for SolarParser {
                     let mut visitor = LintspecVisitor::new(&this.sess);
 
                     let _ = visitor.visit_source_unit(&ast);
-                    Ok(visitor.definitions)
+                    Ok((
+                        visitor.definitions,
+                        visitor.imports,
+                        visitor.containers,
+                        visitor.attached,
+                        visitor.skipped,
+                    ))
                 })
                 .map_err(|_| {
                     let message = match this.sess.emitted_errors() {
@@ -133,8 +143,33 @@ impl Parse for SolarParser {
                 documents.push((document_id, Arc::clone(&source_file)));
             }
             complete_text_ranges(&source_file.src, &mut definitions);
+            let comments: Vec<_> = Cursor::new(&source_file.src)
+                .with_position()
+                .filter_map(|(pos, token)| match token.kind {
+                    RawTokenKind::LineComment { is_doc }
+                    | RawTokenKind::BlockComment { is_doc, .. } => Some(Comment {
+                        span: pos..pos + token.len as usize,
+                        doc: is_doc,
+                    }),
+                    _ => None,
+                })
+                .collect();
+            let regular_comments = comments
+                .iter()
+                .filter(|c| !c.doc)
+                .map(|c| c.span.clone())
+                .collect();
+            definitions.extend(find_stray_comments(
+                &source_file.src,
+                &containers,
+                comments,
+                attached,
+                skipped,
+            ));
             Ok(ParsedDocument {
                 definitions,
+                imports,
+                comments: regular_comments,
                 id: document_id,
             })
         }
@@ -173,6 +208,13 @@ impl Parse for SolarParser {
 pub struct LintspecVisitor<'ast> {
     current_parent: Option<Parent>,
     definitions: Vec<Definition>,
+    imports: Vec<String>,
+    /// The utf-8 byte offsets of the contracts, interfaces and libraries (without their doc-comments)
+    containers: Vec<(Range<usize>, Parent)>,
+    /// The utf-8 byte offsets of the doc-comments which are attached to an item
+    attached: Vec<Range<usize>>,
+    /// The utf-8 byte offsets of the items in which comments are not checked
+    skipped: Vec<Range<usize>>,
     sess: &'ast Session,
 }
 
@@ -182,6 +224,10 @@ impl<'ast> LintspecVisitor<'ast> {
         Self {
             current_parent: None,
             definitions: Vec::default(),
+            imports: Vec::default(),
+            containers: Vec::default(),
+            attached: Vec::default(),
+            skipped: Vec::default(),
             sess,
         }
     }
@@ -197,6 +243,38 @@ impl<'ast> LintspecVisitor<'ast> {
         &self.definitions
     }
 
+    /// Retrieve the paths of the `import` directives, as written in the source
+    ///
+    /// This is empty until the AST has been visited with [`LintspecVisitor::visit_source_unit`].
+    #[must_use]
+    pub fn imports(&self) -> &Vec<String> {
+        &self.imports
+    }
+
+    /// Find the span of the doc-comments which are attached to an item, like the compiler does
+    ///
+    /// If the last doc-comment is multiline, it's the only one attached. Otherwise, the single-line doc-comments up to
+    /// an empty line or a multiline doc-comment are attached.
+    fn attached_docs(&self, docs: &DocComments) -> Option<Span> {
+        let (last, previous) = docs.split_last()?;
+        let mut span = last.span;
+        if last.kind == CommentKind::Line {
+            for doc in previous.iter().rev() {
+                if doc.kind != CommentKind::Line
+                    || !snippet(self, Span::new(doc.span.hi(), span.lo())).is_ok_and(|gap| {
+                        gap.chars()
+                            .filter(|c| !c.is_whitespace() || *c == '\n')
+                            .eq(['\n'])
+                    })
+                {
+                    break;
+                }
+                span = span.with_lo(doc.span.lo());
+            }
+        }
+        Some(span)
+    }
+
     /// Convert a [`Span`] to a pair of utf8 offsets as a [`TextRange`]
     ///
     /// Only the utf8 offset of the [`TextRange`] is initially populated, the rest being filled via [`complete_text_ranges`] to avoid duplicate work.
@@ -226,6 +304,24 @@ impl<'ast> Visit<'ast> for LintspecVisitor<'ast> {
 
     /// Visit an item and extract definitions from it, using the corresponding trait
     fn visit_item(&mut self, item: &'ast Item<'ast>) -> ControlFlow<Self::BreakValue> {
+        // pragmas, imports, `using` directives and UDVTs don't accept `NatSpec`, their doc-comments are stray comments
+        if !matches!(
+            item.kind,
+            ItemKind::Pragma(_) | ItemKind::Import(_) | ItemKind::Using(_) | ItemKind::Udvt(_)
+        ) {
+            let span = self.span_to_textrange(item.span);
+            if let Some(docs_span) = self.attached_docs(&item.docs) {
+                let docs = self.span_to_textrange(docs_span);
+                self.attached.push(docs.start.utf8..span.start.utf8);
+            }
+            // the comments inside of contracts are checked, but not the ones inside of function bodies, structs, etc.
+            if let ItemKind::Contract(item_contract) = &item.kind {
+                self.containers
+                    .push((span.start.utf8..span.end.utf8, item_contract.into()));
+            } else {
+                self.skipped.push(span.start.utf8..span.end.utf8);
+            }
+        }
         match &item.kind {
             ItemKind::Contract(item_contract) => {
                 if let Some(def) = item_contract.extract_definition(item, self) {
@@ -263,7 +359,10 @@ impl<'ast> Visit<'ast> for LintspecVisitor<'ast> {
                     self.definitions.push(def);
                 }
             }
-            ItemKind::Pragma(_) | ItemKind::Import(_) | ItemKind::Using(_) | ItemKind::Udvt(_) => {}
+            ItemKind::Import(import) => {
+                self.imports.push(import.path.value.as_str().to_string());
+            }
+            ItemKind::Pragma(_) | ItemKind::Using(_) | ItemKind::Udvt(_) => {}
         }
 
         ControlFlow::Continue(())
@@ -330,16 +429,24 @@ impl Extract for &solar_parse::ast::ItemContract<'_> {
             Err(e) => return Some(Definition::NatspecParsingError(e.into_inner())),
         };
 
+        let bases = self
+            .bases
+            .iter()
+            .map(|b| INTERNER.get_or_intern(b.name.last().as_str()))
+            .collect();
+
         Some(match self.kind {
             ContractKind::Contract | ContractKind::AbstractContract => ContractDefinition {
                 name,
                 span,
+                bases,
                 natspec,
             }
             .into(),
             ContractKind::Interface => InterfaceDefinition {
                 name,
                 span,
+                bases,
                 natspec,
             }
             .into(),
@@ -356,6 +463,7 @@ impl Extract for &solar_parse::ast::ItemContract<'_> {
 impl Extract for &solar_parse::ast::ItemFunction<'_> {
     fn extract_definition(self, item: &Item, visitor: &mut LintspecVisitor) -> Option<Definition> {
         let params = variable_definitions_to_identifiers(Some(&self.header.parameters), visitor);
+        let param_types = variable_definitions_to_types(&self.header.parameters, visitor);
 
         let returns = variable_definitions_to_identifiers(self.header.returns.as_ref(), visitor);
         let (natspec, span) = match extract_natspec(&item.docs, visitor, &returns) {
@@ -372,6 +480,11 @@ impl Extract for &solar_parse::ast::ItemFunction<'_> {
             Err(e) => return Some(Definition::NatspecParsingError(e.into_inner())),
         };
 
+        let mut reverts = Vec::new();
+        if let Some(body) = &self.body {
+            revert_paths(body, &mut reverts);
+        }
+
         match self.kind {
             FunctionKind::Constructor => Some(
                 ConstructorDefinition {
@@ -379,6 +492,7 @@ impl Extract for &solar_parse::ast::ItemFunction<'_> {
                     span,
                     params,
                     natspec,
+                    reverts,
                 }
                 .into(),
             ),
@@ -395,6 +509,7 @@ impl Extract for &solar_parse::ast::ItemFunction<'_> {
                         visibility: self.header.visibility.into(),
                         r#override: self.header.override_.is_some(),
                     },
+                    reverts,
                 }
                 .into(),
             ),
@@ -409,9 +524,12 @@ impl Extract for &solar_parse::ast::ItemFunction<'_> {
                         visibility: self.header.visibility.into(),
                         r#override: self.header.override_.is_some(),
                     },
+                    overrides: override_names(self.header.override_.as_ref()),
                     span,
                     params,
+                    param_types,
                     natspec,
+                    reverts,
                 }
                 .into(),
             ),
@@ -445,8 +563,12 @@ impl Extract for &solar_parse::ast::VariableDefinition<'_> {
                 parent: visitor.current_parent.clone(),
                 name: INTERNER.get_or_intern(self.name.as_ref().map_or("variable", |n| n.as_str())),
                 span,
+                type_name: snippet(visitor, self.ty.span)
+                    .map(|ty| normalize_type(&ty))
+                    .unwrap_or_default(),
                 natspec,
                 attributes,
+                overrides: override_names(self.override_.as_ref()),
             }
             .into(),
         )
@@ -467,6 +589,15 @@ impl Extract for &solar_parse::ast::ItemStruct<'_> {
                 span: visitor.span_to_textrange(m.span),
             })
             .collect();
+        let member_types = self
+            .fields
+            .iter()
+            .map(|m| {
+                snippet(visitor, m.ty.span)
+                    .map(|ty| normalize_type(&ty))
+                    .unwrap_or_default()
+            })
+            .collect();
 
         let (natspec, span) = match extract_natspec(&item.docs, visitor, &[]) {
             Ok(extracted) => extracted.map_or_else(
@@ -487,6 +618,7 @@ impl Extract for &solar_parse::ast::ItemStruct<'_> {
                 name,
                 span,
                 members,
+                member_types,
                 natspec,
             }
             .into(),
@@ -534,6 +666,7 @@ impl Extract for &solar_parse::ast::ItemEnum<'_> {
 impl Extract for &solar_parse::ast::ItemError<'_> {
     fn extract_definition(self, item: &Item, visitor: &mut LintspecVisitor) -> Option<Definition> {
         let params = variable_definitions_to_identifiers(Some(&self.parameters), visitor);
+        let param_types = variable_definitions_to_types(&self.parameters, visitor);
 
         let (natspec, span) = match extract_natspec(&item.docs, visitor, &[]) {
             Ok(extracted) => extracted.map_or_else(
@@ -554,6 +687,7 @@ impl Extract for &solar_parse::ast::ItemError<'_> {
                 span,
                 name: INTERNER.get_or_intern(self.name.as_str()),
                 params,
+                param_types,
                 natspec,
             }
             .into(),
@@ -564,6 +698,7 @@ impl Extract for &solar_parse::ast::ItemError<'_> {
 impl Extract for &solar_parse::ast::ItemEvent<'_> {
     fn extract_definition(self, item: &Item, visitor: &mut LintspecVisitor) -> Option<Definition> {
         let params = variable_definitions_to_identifiers(Some(&self.parameters), visitor);
+        let param_types = variable_definitions_to_types(&self.parameters, visitor);
 
         let (natspec, span) = match extract_natspec(&item.docs, visitor, &[]) {
             Ok(extracted) => extracted.map_or_else(
@@ -584,6 +719,7 @@ impl Extract for &solar_parse::ast::ItemEvent<'_> {
                 name: INTERNER.get_or_intern(self.name.as_str()),
                 span,
                 params,
+                param_types,
                 natspec,
             }
             .into(),
@@ -631,6 +767,49 @@ impl From<Option<&solar_parse::ast::Visibility>> for Visibility {
     }
 }
 
+/// Extract the names of the bases listed in an `override(...)` specifier
+fn override_names(override_: Option<&Override>) -> Vec<Symbol> {
+    override_
+        .map(|o| {
+            o.paths
+                .iter()
+                .map(|p| INTERNER.get_or_intern(p.last().as_str()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Collect the paths of the errors used by the `revert` statements of a list of statements, recursively
+fn revert_paths(stmts: &[Stmt], out: &mut Vec<String>) {
+    for stmt in stmts {
+        match &stmt.kind {
+            StmtKind::Revert(path, _) => out.push(path.to_string()),
+            StmtKind::Block(block) | StmtKind::UncheckedBlock(block) => revert_paths(block, out),
+            StmtKind::DoWhile(body, _) | StmtKind::While(_, body) => {
+                revert_paths(std::slice::from_ref(&**body), out);
+            }
+            StmtKind::For { init, body, .. } => {
+                if let Some(init) = init {
+                    revert_paths(std::slice::from_ref(&**init), out);
+                }
+                revert_paths(std::slice::from_ref(&**body), out);
+            }
+            StmtKind::If(_, then, otherwise) => {
+                revert_paths(std::slice::from_ref(&**then), out);
+                if let Some(otherwise) = otherwise {
+                    revert_paths(std::slice::from_ref(&**otherwise), out);
+                }
+            }
+            StmtKind::Try(try_) => {
+                for clause in try_.clauses.iter() {
+                    revert_paths(&clause.block, out);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
 /// Convert a list of [`VariableDefinition`] (used for fn params or returns) into an [`Identifier`]
 fn variable_definitions_to_identifiers(
     variable_definitions: Option<&ParameterList>,
@@ -659,7 +838,26 @@ fn variable_definitions_to_identifiers(
         .collect()
 }
 
+/// Extract the normalized types of a list of [`VariableDefinition`] (used for fn or event params)
+fn variable_definitions_to_types(
+    variable_definitions: &ParameterList,
+    visitor: &LintspecVisitor,
+) -> Vec<String> {
+    variable_definitions
+        .iter()
+        .map(|v: &VariableDefinition<'_>| {
+            snippet(visitor, v.ty.span)
+                .map(|ty| normalize_type(&ty))
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
 /// Convert solar's [`DocComments`] into a [`NatSpec`] and [`Span`]
+///
+/// Consecutive single-line comments (without empty lines in between) are parsed as a single comment, so that lines
+/// without a tag continue the description of the previous tag. The spans of the [`NatSpec`] items are relative to the
+/// start of the first comment.
 fn extract_natspec(
     docs: &DocComments,
     visitor: &mut LintspecVisitor,
@@ -668,38 +866,69 @@ fn extract_natspec(
     if docs.is_empty() {
         return Ok(None);
     }
-    let mut combined = NatSpec::default();
-
+    let docs_span = docs.span();
+    let mut groups: Vec<(CommentKind, Span)> = Vec::new();
     for doc in docs.iter() {
-        let snippet = visitor
-            .sess
-            .source_map()
-            .span_to_snippet(doc.span)
-            .map_err(|e| {
-                // there should only be one file in the source map
-                let path = visitor.sess.source_map().files().first().map_or(
-                    PathBuf::from("<stdin>"),
-                    |f| {
-                        PathBuf::from_str(&f.name.display().to_string())
-                            .unwrap_or(PathBuf::from("<unsupported path>"))
-                    },
-                );
-                ErrorKind::ParsingError {
-                    path,
-                    loc: visitor.span_to_textrange(doc.span).start,
-                    message: format!("{e:?}"),
-                }
-            })?;
+        if let Some((CommentKind::Line, group_span)) = groups.last_mut()
+            && doc.kind == CommentKind::Line
+            && snippet(visitor, Span::new(group_span.hi(), doc.span.lo()))?
+                .chars()
+                .filter(|c| !c.is_whitespace() || *c == '\n')
+                .eq(['\n'])
+        {
+            *group_span = group_span.with_hi(doc.span.hi());
+        } else {
+            groups.push((doc.kind, doc.span));
+        }
+    }
 
-        let mut parsed = parse_comment(&mut snippet.as_str())
+    let mut combined = NatSpec::default();
+    for (_, group_span) in groups {
+        let mut parsed = parse_comment(&mut snippet(visitor, group_span)?.as_str())
             .map_err(|e| ErrorKind::NatspecParsingError {
                 parent: visitor.current_parent.clone(),
-                span: visitor.span_to_textrange(doc.span),
+                span: visitor.span_to_textrange(group_span),
                 message: e.to_string(),
             })?
             .populate_returns(returns);
+        if group_span.lo() > docs_span.lo() {
+            // make the spans relative to the start of the first comment
+            let before = snippet(visitor, Span::new(docs_span.lo(), group_span.lo()))?;
+            if let Some(origin) = compute_indices(&before, &[before.len()]).pop() {
+                for item in &mut parsed.items {
+                    item.span = item.span.start.offset_by(origin)..item.span.end.offset_by(origin);
+                }
+            }
+        }
         combined.append(&mut parsed);
     }
 
-    Ok(Some((combined, docs.span())))
+    Ok(Some((combined, docs_span)))
+}
+
+/// Retrieve the source code corresponding to a [`Span`]
+fn snippet(visitor: &LintspecVisitor, span: Span) -> Result<String> {
+    visitor
+        .sess
+        .source_map()
+        .span_to_snippet(span)
+        .map_err(|e| {
+            // there should only be one file in the source map
+            let path =
+                visitor
+                    .sess
+                    .source_map()
+                    .files()
+                    .first()
+                    .map_or(PathBuf::from("<stdin>"), |f| {
+                        PathBuf::from_str(&f.name.display().to_string())
+                            .unwrap_or(PathBuf::from("<unsupported path>"))
+                    });
+            ErrorKind::ParsingError {
+                path,
+                loc: visitor.span_to_textrange(span).start,
+                message: format!("{e:?}"),
+            }
+            .into()
+        })
 }
@@ -0,0 +1,612 @@
+//! Cross-file project model
+//!
+//! Each file is linted on its own, but some checks need to know about the contracts defined in other files, for
+//! instance to validate the target of an `@inheritdoc` tag when the base contract is imported. A [`Project`] follows
+//! the `import` directives of a source file and gathers the contracts and interfaces which are reachable from it.
+//!
+//! Import paths are resolved like `forge` does:
+//! - relative paths (starting with `./` or `../`) are relative to the directory of the importing file;
+//! - other paths are first remapped with the remappings from `remappings.txt` and `foundry.toml`, and the automatic
+//!   remappings for the folders in the library directories (e.g. `forge-std/=lib/forge-std/src/`);
+//! - the remapped path is looked up in the project root, then the original path is looked up in the project root and
+//!   in the library directories.
+use std::{
+    collections::{HashMap, HashSet},
+    env, fs,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, OnceLock},
+};
+
+use figment::{
+    Figment,
+    providers::{Format as _, Toml},
+};
+use serde::Deserialize;
+
+use crate::{
+    definitions::Definition, inheritance::Inheritance, parser::Parse, prelude::OrPanic as _,
+};
+
+/// An import remapping, written as `[context:]prefix=path`
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Remapping {
+    /// The path prefix of the importing files for which the remapping applies, or `None` for all files
+    pub context: Option<String>,
+
+    /// The prefix of the import paths which are remapped
+    pub prefix: String,
+
+    /// The replacement for the prefix, relative to the project root
+    pub path: String,
+}
+
+impl Remapping {
+    /// Parse a remapping from its textual representation
+    ///
+    /// Returns `None` if the remapping is malformed.
+    #[must_use]
+    pub fn parse(input: &str) -> Option<Self> {
+        let (lhs, path) = input.trim().split_once('=')?;
+        let (context, prefix) = match lhs.split_once(':') {
+            Some((context, prefix)) => (Some(context), prefix),
+            None => (None, lhs),
+        };
+        if prefix.is_empty() {
+            return None;
+        }
+        Some(Self {
+            context: context.filter(|c| !c.is_empty()).map(String::from),
+            prefix: prefix.to_string(),
+            path: path.to_string(),
+        })
+    }
+}
+
+/// A source file which was imported, parsed on demand
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct SourceUnit {
+    /// The canonical path to the file
+    pub path: PathBuf,
+
+    /// The canonical paths of the files imported by this file, for the imports which could be resolved
+    pub imports: Vec<PathBuf>,
+
+    /// The definitions found in the file
+    pub definitions: Vec<Definition>,
+}
+
+/// The subset of the `foundry.toml` profile which is relevant to resolve imports
+#[derive(Debug, Default, Deserialize)]
+struct FoundryProfile {
+    #[serde(default)]
+    remappings: Vec<String>,
+
+    #[serde(default)]
+    libs: Vec<PathBuf>,
+}
+
+/// The root directory and the settings of a project, which are relevant to resolve imports
+#[derive(Debug)]
+struct Settings {
+    /// The root directory of the project, to which the remappings and library directories are relative
+    root: PathBuf,
+
+    /// The import remappings
+    remappings: Vec<Remapping>,
+
+    /// The library directories, relative to the root
+    libs: Vec<PathBuf>,
+}
+
+impl Settings {
+    /// Read the remappings and library directories from the `foundry.toml` and `remappings.txt` files (if any)
+    fn load(root: PathBuf) -> Self {
+        let profile = read_foundry_profile(&root);
+        let mut remappings: Vec<_> = fs::read_to_string(root.join("remappings.txt"))
+            .unwrap_or_default()
+            .lines()
+            .chain(profile.remappings.iter().map(String::as_str))
+            .filter_map(Remapping::parse)
+            .collect();
+        let libs = if profile.libs.is_empty() {
+            vec![PathBuf::from("lib")]
+        } else {
+            profile.libs
+        };
+        // each folder in the library directories gets a remapping, unless one was provided for the same prefix
+        for lib in &libs {
+            let Ok(entries) = fs::read_dir(root.join(lib)) else {
+                continue;
+            };
+            let mut auto: Vec<_> = entries
+                .filter_map(|e| {
+                    let entry = e.ok()?;
+                    if !entry.file_type().ok()?.is_dir() {
+                        return None;
+                    }
+                    let name = entry.file_name().to_string_lossy().to_string();
+                    let prefix = format!("{name}/");
+                    if remappings.iter().any(|r| r.prefix == prefix) {
+                        return None;
+                    }
+                    let dir = lib.join(&name);
+                    let path = if root.join(&dir).join("src").is_dir() {
+                        dir.join("src")
+                    } else {
+                        dir
+                    };
+                    Some(Remapping {
+                        context: None,
+                        prefix,
+                        path: format!("{}/", path.to_string_lossy()),
+                    })
+                })
+                .collect();
+            auto.sort_unstable_by(|a, b| a.prefix.cmp(&b.prefix));
+            remappings.extend(auto);
+        }
+        Self {
+            root,
+            remappings,
+            libs,
+        }
+    }
+}
+
+/// A Solidity project, used to resolve imports and to retrieve the definitions from other files
+///
+/// Creating a project is cheap: the root directory and the settings are only loaded when an import needs to be
+/// resolved, and the imported files are parsed the first time they are needed and cached. The project reflects the
+/// state of the filesystem at that time, unless a file is invalidated with [`Project::invalidate`]. It can be shared
+/// between threads.
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct Project {
+    /// The directory from which the project is loaded
+    dir: PathBuf,
+
+    /// Whether the root must be searched in the ancestors of the directory
+    discover: bool,
+
+    /// The settings, loaded on first use
+    settings: OnceLock<Settings>,
+
+    /// The source units which were already parsed (`None` if the file couldn't be read or parsed)
+    units: Mutex<HashMap<PathBuf, Option<Arc<SourceUnit>>>>,
+}
+
+impl Project {
+    /// Find the project containing a directory
+    ///
+    /// The root of the project is the closest ancestor (including the directory itself) which contains a
+    /// `foundry.toml` or `remappings.txt` file. If there is none, the directory is used as the root.
+    #[must_use]
+    pub fn discover(dir: impl AsRef<Path>) -> Self {
+        Self {
+            dir: dir.as_ref().to_path_buf(),
+            discover: true,
+            settings: OnceLock::new(),
+            units: Mutex::default(),
+        }
+    }
+
+    /// Create a project with the given root, reading the remappings and library directories from the `foundry.toml`
+    /// and `remappings.txt` files (if any)
+    ///
+    /// The profile can be selected with the `FOUNDRY_PROFILE` environment variable. The library directories default to
+    /// `lib`.
+    #[must_use]
+    pub fn load(root: impl AsRef<Path>) -> Self {
+        Self {
+            dir: root.as_ref().to_path_buf(),
+            discover: false,
+            settings: OnceLock::new(),
+            units: Mutex::default(),
+        }
+    }
+
+    /// Retrieve the settings, finding the root and reading the configuration files the first time
+    fn settings(&self) -> &Settings {
+        self.settings.get_or_init(|| {
+            let dir = dunce::canonicalize(&self.dir).unwrap_or_else(|_| self.dir.clone());
+            let root = if self.discover {
+                dir.ancestors()
+                    .find(|d| {
+                        d.join("foundry.toml").is_file() || d.join("remappings.txt").is_file()
+                    })
+                    .unwrap_or(&dir)
+                    .to_path_buf()
+            } else {
+                dir
+            };
+            Settings::load(root)
+        })
+    }
+
+    /// The root directory of the project
+    #[must_use]
+    pub fn root(&self) -> &Path {
+        &self.settings().root
+    }
+
+    /// The import remappings of the project
+    #[must_use]
+    pub fn remappings(&self) -> &[Remapping] {
+        &self.settings().remappings
+    }
+
+    /// Forget the parsed contents of a file, so that it's read again the next time it's imported
+    ///
+    /// This is needed when a file is modified, for instance in an editor.
+    pub fn invalidate(&self, path: impl AsRef<Path>) {
+        let path =
+            dunce::canonicalize(path.as_ref()).unwrap_or_else(|_| path.as_ref().to_path_buf());
+        self.units
+            .lock()
+            .or_panic("mutex should not be poisoned")
+            .remove(&path);
+    }
+
+    /// Resolve the path of an `import` directive found in the `importing` file
+    ///
+    /// Returns the canonical path to the imported file, or `None` if it can't be found.
+    #[must_use]
+    pub fn resolve_import(&self, importing: impl AsRef<Path>, import: &str) -> Option<PathBuf> {
+        let importing = importing.as_ref();
+        if import.starts_with("./") || import.starts_with("../") {
+            let dir = importing.parent()?;
+            return dunce::canonicalize(dir.join(import))
+                .ok()
+                .filter(|p| p.is_file());
+        }
+        let settings = self.settings();
+        let remapped = self
+            .remap(importing, import)
+            .map(|remapped| settings.root.join(remapped));
+        remapped
+            .into_iter()
+            .chain(std::iter::once(settings.root.join(import)))
+            .chain(
+                settings
+                    .libs
+                    .iter()
+                    .map(|l| settings.root.join(l).join(import)),
+            )
+            .find_map(|candidate| dunce::canonicalize(candidate).ok().filter(|p| p.is_file()))
+    }
+
+    /// Apply the most specific remapping to an import path
+    ///
+    /// The remapping with the longest context wins, then the one with the longest prefix.
+    fn remap(&self, importing: &Path, import: &str) -> Option<String> {
+        let settings = self.settings();
+        let importing = dunce::canonicalize(importing).unwrap_or_else(|_| importing.to_path_buf());
+        let importing = importing
+            .strip_prefix(&settings.root)
+            .unwrap_or(&importing)
+            .to_string_lossy()
+            .replace('\\', "/");
+        settings
+            .remappings
+            .iter()
+            .filter(|r| {
+                import.starts_with(&r.prefix)
+                    && r.context
+                        .as_ref()
+                        .is_none_or(|c| importing.starts_with(c.as_str()))
+            })
+            .max_by_key(|r| (r.context.as_ref().map_or(0, String::len), r.prefix.len()))
+            .map(|r| format!("{}{}", r.path, &import[r.prefix.len()..]))
+    }
+
+    /// Retrieve a source unit from the cache, or parse it
+    ///
+    /// The path should be canonical. Returns `None` if the file can't be read or parsed.
+    pub fn unit(&self, mut parser: impl Parse, path: &Path) -> Option<Arc<SourceUnit>> {
+        if let Some(unit) = self
+            .units
+            .lock()
+            .or_panic("mutex should not be poisoned")
+            .get(path)
+        {
+            return unit.clone();
+        }
+        // the lock is not held while parsing, so that other threads can make progress
+        let unit = fs::File::open(path)
+            .ok()
+            .and_then(|file| parser.parse_document(file, Some(path), false).ok())
+            .map(|document| {
+                Arc::new(SourceUnit {
+                    path: path.to_path_buf(),
+                    imports: document
+                        .imports
+                        .iter()
+                        .filter_map(|i| self.resolve_import(path, i))
+                        .collect(),
+                    definitions: document.definitions,
+                })
+            });
+        self.units
+            .lock()
+            .or_panic("mutex should not be poisoned")
+            .insert(path.to_path_buf(), unit.clone());
+        unit
+    }
+
+    /// Retrieve the source units which are directly or transitively imported by a file
+    ///
+    /// The `imports` are the paths of the file's `import` directives, as written in the source. Imports which can't be
+    /// resolved or parsed are ignored.
+    pub fn imported_units(
+        &self,
+        parser: &impl Parse,
+        path: impl AsRef<Path>,
+        imports: &[String],
+    ) -> Vec<Arc<SourceUnit>> {
+        let mut out = Vec::new();
+        let mut visited = HashSet::new();
+        let mut stack: Vec<_> = imports
+            .iter()
+            .filter_map(|i| self.resolve_import(&path, i))
+            .collect();
+        while let Some(current) = stack.pop() {
+            if !visited.insert(current.clone()) {
+                continue;
+            }
+            if let Some(unit) = self.unit(parser.clone(), &current) {
+                stack.extend(unit.imports.iter().cloned());
+                out.push(unit);
+            }
+        }
+        out
+    }
+
+    /// Record the bases of the contracts and interfaces which are imported by a file
+    ///
+    /// The definitions of the file itself should be added to the result with [`Inheritance::extend`].
+    pub fn inheritance(
+        &self,
+        parser: &impl Parse,
+        path: impl AsRef<Path>,
+        imports: &[String],
+    ) -> Inheritance {
+        let mut inheritance = Inheritance::default();
+        for unit in self.imported_units(parser, path, imports) {
+            inheritance.extend(&unit.definitions);
+        }
+        inheritance
+    }
+}
+
+/// Read the remappings and library directories from the `foundry.toml` file in the root directory
+///
+/// The settings from the `default` profile are overridden by the ones of the profile selected with the
+/// `FOUNDRY_PROFILE` environment variable.
+fn read_foundry_profile(root: &Path) -> FoundryProfile {
+    let path = root.join("foundry.toml");
+    if !path.is_file() {
+        return FoundryProfile::default();
+    }
+    let figment = Figment::from(Toml::file(path));
+    let mut profile: FoundryProfile = figment.extract_inner("profile.default").unwrap_or_default();
+    if let Ok(name) = env::var("FOUNDRY_PROFILE")
+        && name != "default"
+        && let Ok(selected) = figment.extract_inner::<FoundryProfile>(&format!("profile.{name}"))
+    {
+        if !selected.remappings.is_empty() {
+            profile.remappings = selected.remappings;
+        }
+        if !selected.libs.is_empty() {
+            profile.libs = selected.libs;
+        }
+    }
+    profile
+}
+
+#[cfg(test)]
+#[cfg(feature = "solar")]
+mod tests {
+    use similar_asserts::assert_eq;
+    use temp_dir::TempDir;
+
+    use crate::{
+        interner::INTERNER,
+        lint::{ValidationOptions, lint_source_with_project},
+        parser::solar::SolarParser,
+    };
+
+    use super::*;
+
+    fn write(dir: &TempDir, path: &str, contents: &str) {
+        let path = dir.path().join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+
+    fn canonical(dir: &TempDir, path: &str) -> PathBuf {
+        dunce::canonicalize(dir.path().join(path)).unwrap()
+    }
+
+    #[test]
+    fn test_parse_remapping() {
+        assert_eq!(
+            Remapping::parse("forge-std/=lib/forge-std/src/"),
+            Some(Remapping {
+                context: None,
+                prefix: "forge-std/".to_string(),
+                path: "lib/forge-std/src/".to_string()
+            })
+        );
+        assert_eq!(
+            Remapping::parse("src/:@oz/=lib/oz/contracts/\n"),
+            Some(Remapping {
+                context: Some("src/".to_string()),
+                prefix: "@oz/".to_string(),
+                path: "lib/oz/contracts/".to_string()
+            })
+        );
+        assert_eq!(Remapping::parse("foo"), None);
+        assert_eq!(Remapping::parse("=lib/"), None);
+    }
+
+    #[test]
+    fn test_load() {
+        let dir = TempDir::new().unwrap();
+        write(&dir, "remappings.txt", "@oz/=lib/oz/contracts/\n");
+        write(
+            &dir,
+            "foundry.toml",
+            "[profile.default]\nremappings = ['solady/=dependencies/solady/src/']\n",
+        );
+        write(&dir, "lib/forge-std/src/Test.sol", "");
+        write(&dir, "lib/oz/contracts/Ownable.sol", "");
+        write(&dir, "lib/other/Other.sol", "");
+        let project = Project::discover(dir.path().join("lib"));
+        assert_eq!(project.root(), canonical(&dir, ""));
+        let prefixes: Vec<_> = project
+            .remappings()
+            .iter()
+            .map(|r| (r.prefix.as_str(), r.path.as_str()))
+            .collect();
+        assert_eq!(
+            prefixes,
+            vec![
+                ("@oz/", "lib/oz/contracts/"),
+                ("solady/", "dependencies/solady/src/"),
+                ("forge-std/", "lib/forge-std/src/"),
+                ("other/", "lib/other/"),
+                ("oz/", "lib/oz/"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_resolve_import() {
+        let dir = TempDir::new().unwrap();
+        write(
+            &dir,
+            "remappings.txt",
+            "@oz/=lib/oz/contracts/\ntest/:@oz/=lib/oz-test/\n",
+        );
+        write(&dir, "src/A.sol", "");
+        write(&dir, "src/sub/B.sol", "");
+        write(&dir, "test/C.sol", "");
+        write(&dir, "lib/oz/contracts/Ownable.sol", "");
+        write(&dir, "lib/oz-test/Ownable.sol", "");
+        write(&dir, "lib/forge-std/src/Test.sol", "");
+        let project = Project::load(dir.path());
+        let importing = dir.path().join("src/sub/B.sol");
+        assert_eq!(
+            project.resolve_import(&importing, "../A.sol"),
+            Some(canonical(&dir, "src/A.sol"))
+        );
+        assert_eq!(
+            project.resolve_import(&importing, "./B.sol"),
+            Some(canonical(&dir, "src/sub/B.sol"))
+        );
+        assert_eq!(
+            project.resolve_import(&importing, "src/A.sol"),
+            Some(canonical(&dir, "src/A.sol"))
+        );
+        assert_eq!(
+            project.resolve_import(&importing, "@oz/Ownable.sol"),
+            Some(canonical(&dir, "lib/oz/contracts/Ownable.sol"))
+        );
+        assert_eq!(
+            project.resolve_import(dir.path().join("test/C.sol"), "@oz/Ownable.sol"),
+            Some(canonical(&dir, "lib/oz-test/Ownable.sol"))
+        );
+        assert_eq!(
+            project.resolve_import(&importing, "forge-std/Test.sol"),
+            Some(canonical(&dir, "lib/forge-std/src/Test.sol"))
+        );
+        assert_eq!(
+            project.resolve_import(&importing, "forge-std/src/Test.sol"),
+            Some(canonical(&dir, "lib/forge-std/src/Test.sol"))
+        );
+        assert_eq!(project.resolve_import(&importing, "./Missing.sol"), None);
+    }
+
+    #[test]
+    fn test_inheritance() {
+        let dir = TempDir::new().unwrap();
+        write(&dir, "src/IA.sol", "interface IA {}");
+        write(
+            &dir,
+            "src/B.sol",
+            "import {IA} from './IA.sol';\nimport './C.sol';\nabstract contract B is IA {}",
+        );
+        // circular import
+        write(&dir, "src/C.sol", "import './B.sol';\ncontract C is B {}");
+        let project = Project::load(dir.path());
+        let parser = SolarParser::default();
+        let units =
+            project.imported_units(&parser, dir.path().join("src/D.sol"), &["src/C.sol".into()]);
+        assert_eq!(units.len(), 3);
+        let inheritance =
+            project.inheritance(&parser, dir.path().join("src/D.sol"), &["src/C.sol".into()]);
+        let sym = |name: &str| INTERNER.get_or_intern(name);
+        assert_eq!(inheritance.is_base(sym("C"), sym("IA")), Some(true));
+        assert_eq!(inheritance.is_base(sym("B"), sym("C")), Some(false));
+    }
+
+    #[test]
+    fn test_lazy_imports() {
+        let dir = TempDir::new().unwrap();
+        write(
+            &dir,
+            "src/Base.sol",
+            "interface IBase {\n/// @notice Foo\nfunction foo() external;\n}",
+        );
+        let project = Project::load(dir.path());
+        let options = ValidationOptions::default();
+        // the contract doesn't inherit from an imported one, the imports are not needed
+        let source =
+            "import './Base.sol';\ncontract C {\n/// @notice Foo\nfunction foo() external {}\n}";
+        lint_source_with_project(
+            SolarParser::default(),
+            dir.path().join("src/C.sol"),
+            source,
+            &options,
+            &project,
+            false,
+        )
+        .unwrap();
+        assert!(project.units.lock().unwrap().is_empty());
+
+        let source = "import './Base.sol';\ncontract C is IBase {\n/// @inheritdoc IBase\nfunction foo() external {}\n}";
+        let diags = lint_source_with_project(
+            SolarParser::default(),
+            dir.path().join("src/C.sol"),
+            source,
+            &options,
+            &project,
+            false,
+        )
+        .unwrap();
+        assert!(diags.is_none(), "{diags:#?}");
+        assert_eq!(project.units.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_invalidate() {
+        let dir = TempDir::new().unwrap();
+        write(&dir, "src/IA.sol", "interface IA {}");
+        let project = Project::discover(dir.path().join("src"));
+        let parser = SolarParser::default();
+        let path = dir.path().join("src/B.sol");
+        let sym = |name: &str| INTERNER.get_or_intern(name);
+        let inheritance = project.inheritance(&parser, &path, &["./IA.sol".into()]);
+        assert_eq!(inheritance.is_base(sym("IA"), sym("IC")), Some(false));
+
+        // the cached contents are used until the file is invalidated
+        write(&dir, "src/IA.sol", "interface IA is IC {}");
+        let inheritance = project.inheritance(&parser, &path, &["./IA.sol".into()]);
+        assert_eq!(inheritance.is_base(sym("IA"), sym("IC")), Some(false));
+        project.invalidate(dir.path().join("src/IA.sol"));
+        let inheritance = project.inheritance(&SolarParser::default(), &path, &["./IA.sol".into()]);
+        assert_eq!(inheritance.is_base(sym("IA"), sym("IC")), Some(true));
+    }
+}
@@ -0,0 +1,539 @@
+//! Conversion of diagnostics into the SARIF format
+//!
+//! The [Static Analysis Results Interchange Format](https://docs.oasis-open.org/sarif/sarif/v2.1.0/sarif-v2.1.0.html)
+//! is a standard JSON format for the output of static analysis tools, which is understood by code scanning services
+//! like GitHub code scanning. The [`SarifLog::new`] function converts a list of [`FileDiagnostics`] into a SARIF log.
+use std::{collections::BTreeMap, fmt::Write as _, path::Path};
+
+use serde::Serialize;
+
+use crate::{
+    config::Severity,
+    definitions::ItemType,
+    lint::{FileDiagnostics, Rule},
+    textindex::TextIndex,
+};
+
+/// The URL of the SARIF 2.1.0 JSON schema
+pub const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// The SARIF version of the generated logs
+pub const SARIF_VERSION: &str = "2.1.0";
+
+/// The name of the base URI which represents the root directory of the analyzed sources
+pub const SRCROOT: &str = "%SRCROOT%";
+
+/// The top-level SARIF log object
+#[derive(Debug, Clone, Serialize)]
+#[non_exhaustive]
+pub struct SarifLog {
+    /// The URI of the JSON schema for this log
+    #[serde(rename = "$schema")]
+    pub schema: &'static str,
+
+    /// The SARIF version
+    pub version: &'static str,
+
+    /// The runs contained in this log (lintspec always produces a single run)
+    pub runs: Vec<Run>,
+}
+
+/// A single invocation of the tool
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct Run {
+    /// Information about the tool
+    pub tool: Tool,
+
+    /// The base URIs used to resolve relative artifact locations
+    pub original_uri_base_ids: BTreeMap<&'static str, ArtifactLocation>,
+
+    /// The results (diagnostics) of the run
+    pub results: Vec<SarifResult>,
+
+    /// The unit in which columns are expressed in the regions
+    pub column_kind: &'static str,
+}
+
+/// The tool which produced the run
+#[derive(Debug, Clone, Serialize)]
+#[non_exhaustive]
+pub struct Tool {
+    /// The main tool component
+    pub driver: ToolComponent,
+}
+
+/// The description of the tool
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct ToolComponent {
+    /// The name of the tool
+    pub name: &'static str,
+
+    /// The version of the tool
+    pub version: String,
+
+    /// The URL of the tool's homepage
+    pub information_uri: &'static str,
+
+    /// The rules which can be reported by the tool
+    pub rules: Vec<ReportingDescriptor>,
+}
+
+/// The description of a rule
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct ReportingDescriptor {
+    /// The stable identifier of the rule
+    pub id: Rule,
+
+    /// A short description of the rule
+    pub short_description: Message,
+}
+
+/// A SARIF message
+#[derive(Debug, Clone, Serialize)]
+#[non_exhaustive]
+pub struct Message {
+    /// The plain text message
+    pub text: String,
+}
+
+/// A single diagnostic
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct SarifResult {
+    /// The identifier of the reported rule
+    pub rule_id: Rule,
+
+    /// The index of the reported rule in the driver's rules list
+    pub rule_index: usize,
+
+    /// The severity level of the result
+    pub level: &'static str,
+
+    /// The diagnostic message
+    pub message: Message,
+
+    /// The location of the diagnostic
+    pub locations: Vec<Location>,
+}
+
+/// The location of a result
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct Location {
+    /// The location in the source file
+    pub physical_location: PhysicalLocation,
+
+    /// The source item (function, struct, etc.) related to the result
+    pub logical_locations: Vec<LogicalLocation>,
+}
+
+/// A location in a source file
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct PhysicalLocation {
+    /// The source file
+    pub artifact_location: ArtifactLocation,
+
+    /// The region of the source file
+    pub region: Region,
+}
+
+/// The location of a file, as a URI which can be relative to a base URI
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct ArtifactLocation {
+    /// The URI of the file
+    pub uri: String,
+
+    /// The name of the base URI for relative URIs
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub uri_base_id: Option<&'static str>,
+}
+
+/// A region of a source file
+///
+/// As required by SARIF, lines and columns start at 1 (unlike [`TextIndex`], which is 0-based), and columns are
+/// expressed in UTF-16 code units. The byte offset starts at 0.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct Region {
+    /// The 1-based line number of the first character of the region
+    pub start_line: u32,
+
+    /// The 1-based column number (in UTF-16 code units) of the first character of the region
+    pub start_column: u32,
+
+    /// The 1-based line number of the end of the region
+    pub end_line: u32,
+
+    /// The 1-based column number (in UTF-16 code units) of the character following the end of the region
+    ///
+    /// The end column is exclusive, a region of a single character has `end_column == start_column + 1`.
+    pub end_column: u32,
+
+    /// The 0-based utf-8 byte offset of the first character of the region from the start of the file
+    pub byte_offset: usize,
+
+    /// The length of the region in utf-8 bytes
+    pub byte_length: usize,
+}
+
+/// A named source item
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct LogicalLocation {
+    /// The name of the item
+    pub name: &'static str,
+
+    /// The name of the item, prefixed with its parent's name if any
+    pub fully_qualified_name: String,
+
+    /// The kind of item
+    pub kind: &'static str,
+}
+
+impl SarifLog {
+    /// Create a SARIF log from a list of diagnostics
+    ///
+    /// The `root_path` is used to compute the relative path of the files (relative to the `%SRCROOT%` base URI). The
+    /// relative file paths are resolved against the root path. If a file is not a child of the root path, its absolute
+    /// path is used instead. The `version` is the version of the
+    /// tool to report in the log.
+    #[must_use]
+    pub fn new(
+        diagnostics: &[FileDiagnostics],
+        root_path: impl AsRef<Path>,
+        version: impl Into<String>,
+    ) -> Self {
+        let root_path = root_path.as_ref();
+        let results = diagnostics
+            .iter()
+            .flat_map(|file_diags| {
+                let path = root_path.join(&file_diags.path);
+                let artifact_location = match path.strip_prefix(root_path) {
+                    Ok(relative_path) => ArtifactLocation {
+                        uri: path_to_uri(relative_path),
+                        uri_base_id: Some(SRCROOT),
+                    },
+                    Err(_) => ArtifactLocation {
+                        uri: file_uri(&path, false),
+                        uri_base_id: None,
+                    },
+                };
+                file_diags.items.iter().flat_map(move |item| {
+                    let logical_location = LogicalLocation {
+                        name: item.name,
+                        fully_qualified_name: match (&item.parent, item.name) {
+                            (Some(parent), "") => parent.to_string(),
+                            (Some(parent), name) => format!("{parent}.{name}"),
+                            (None, name) => name.to_string(),
+                        },
+                        kind: logical_kind(item.item_type),
+                    };
+                    let artifact_location = artifact_location.clone();
+                    item.diags.iter().map(move |diag| SarifResult {
+                        rule_id: diag.rule,
+                        rule_index: Rule::ALL
+                            .iter()
+                            .position(|r| r == &diag.rule)
+                            .unwrap_or_default(),
+                        level: level(diag.severity),
+                        message: Message {
+                            text: diag.message.clone(),
+                        },
+                        locations: vec![Location {
+                            physical_location: PhysicalLocation {
+                                artifact_location: artifact_location.clone(),
+                                region: Region::new(&diag.span.start, &diag.span.end),
+                            },
+                            logical_locations: vec![logical_location.clone()],
+                        }],
+                    })
+                })
+            })
+            .collect();
+        let mut original_uri_base_ids = BTreeMap::new();
+        original_uri_base_ids.insert(
+            SRCROOT,
+            ArtifactLocation {
+                uri: file_uri(root_path, true),
+                uri_base_id: None,
+            },
+        );
+        Self {
+            schema: SARIF_SCHEMA,
+            version: SARIF_VERSION,
+            runs: vec![Run {
+                tool: Tool {
+                    driver: ToolComponent {
+                        name: "lintspec",
+                        version: version.into(),
+                        information_uri: "https://github.com/beeb/lintspec",
+                        rules: Rule::ALL
+                            .iter()
+                            .map(|rule| ReportingDescriptor {
+                                id: *rule,
+                                short_description: Message {
+                                    text: rule.description().to_string(),
+                                },
+                            })
+                            .collect(),
+                    },
+                },
+                original_uri_base_ids,
+                results,
+                column_kind: "utf16CodeUnits",
+            }],
+        }
+    }
+}
+
+impl Region {
+    /// Create a region from the start and end [`TextIndex`] of a span
+    fn new(start: &TextIndex, end: &TextIndex) -> Self {
+        Self {
+            start_line: start.line + 1,
+            start_column: start.col_utf16 + 1,
+            end_line: end.line + 1,
+            end_column: end.col_utf16 + 1,
+            byte_offset: start.utf8,
+            byte_length: end.utf8 - start.utf8,
+        }
+    }
+}
+
+/// Map a severity to the corresponding SARIF level
+fn level(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Error => "error",
+        Severity::Warning => "warning",
+        Severity::Info => "note",
+    }
+}
+
+/// Map an item type to one of the well-known SARIF logical location kinds
+fn logical_kind(item_type: ItemType) -> &'static str {
+    match item_type {
+        ItemType::Contract
+        | ItemType::Interface
+        | ItemType::Library
+        | ItemType::Enum
+        | ItemType::Struct => "type",
+        ItemType::Constructor
+        | ItemType::Modifier
+        | ItemType::PrivateFunction
+        | ItemType::InternalFunction
+        | ItemType::PublicFunction
+        | ItemType::ExternalFunction => "function",
+        ItemType::Error | ItemType::Event => "member",
+        ItemType::PrivateVariable | ItemType::InternalVariable | ItemType::PublicVariable => {
+            "variable"
+        }
+        ItemType::ParsingError | ItemType::Comment => "object",
+    }
+}
+
+/// Convert a relative path into a relative URI reference (forward slashes, percent-encoded)
+fn path_to_uri(path: &Path) -> String {
+    let mut uri = String::new();
+    for (i, component) in path.components().enumerate() {
+        if i > 0 {
+            uri.push('/');
+        }
+        percent_encode(&component.as_os_str().to_string_lossy(), &mut uri);
+    }
+    uri
+}
+
+/// Convert an absolute path into a `file://` URI
+///
+/// If `dir` is true, the URI ends with a slash, as required for base URIs.
+fn file_uri(path: &Path, dir: bool) -> String {
+    let path = path.to_string_lossy().replace('\\', "/");
+    let mut uri = String::from("file://");
+    if !path.starts_with('/') {
+        // windows paths like `C:/foo`
+        uri.push('/');
+    }
+    percent_encode(&path, &mut uri);
+    if dir && !uri.ends_with('/') {
+        uri.push('/');
+    }
+    uri
+}
+
+/// Percent-encode the characters which are not allowed in a URI path
+fn percent_encode(input: &str, out: &mut String) {
+    for byte in input.bytes() {
+        match byte {
+            b'A'..=b'Z'
+            | b'a'..=b'z'
+            | b'0'..=b'9'
+            | b'-'
+            | b'.'
+            | b'_'
+            | b'~'
+            | b'/'
+            | b':'
+            | b'@' => out.push(byte as char),
+            _ => {
+                let _ = write!(out, "%{byte:02X}");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use similar_asserts::assert_eq;
+
+    use crate::{
+        definitions::Parent,
+        lint::{Diagnostic, ItemDiagnostics},
+        parser::DocumentId,
+    };
+
+    use super::*;
+
+    fn index(utf8: usize, line: u32, col: u32) -> TextIndex {
+        TextIndex {
+            utf8,
+            line,
+            col_utf8: col,
+            col_utf16: col,
+            col_utf32: col,
+        }
+    }
+
+    #[test]
+    fn test_sarif_log() {
+        let diags = vec![FileDiagnostics {
+            path: PathBuf::from("/project/src/My File.sol"),
+            document_id: DocumentId::new(),
+            items: vec![
+                ItemDiagnostics::builder()
+                    .parent(Parent::Contract("Foo"))
+                    .item_type(ItemType::ExternalFunction)
+                    .name("bar")
+                    .span(index(10, 1, 4)..index(50, 1, 44))
+                    .diags(vec![Diagnostic::new(
+                        Rule::MissingParam,
+                        index(30, 1, 24)..index(36, 1, 30),
+                        "@param a is missing".to_string(),
+                    )])
+                    .build(),
+            ],
+        }];
+        let log = SarifLog::new(&diags, "/project", "1.0.0");
+        let value = serde_json::to_value(&log).unwrap();
+        assert_eq!(value["version"], "2.1.0");
+        let run = &value["runs"][0];
+        assert_eq!(run["tool"]["driver"]["name"], "lintspec");
+        assert_eq!(run["tool"]["driver"]["version"], "1.0.0");
+        assert_eq!(
+            run["tool"]["driver"]["rules"].as_array().unwrap().len(),
+            Rule::ALL.len()
+        );
+        assert_eq!(
+            run["originalUriBaseIds"]["%SRCROOT%"]["uri"],
+            "file:///project/"
+        );
+        let result = &run["results"][0];
+        assert_eq!(result["ruleId"], "missing-param");
+        assert_eq!(result["ruleIndex"], 0);
+        assert_eq!(run["tool"]["driver"]["rules"][0]["id"], "missing-param");
+        assert_eq!(result["level"], "error");
+        assert_eq!(result["message"]["text"], "@param a is missing");
+        let location = &result["locations"][0];
+        assert_eq!(
+            location["physicalLocation"]["artifactLocation"]["uri"],
+            "src/My%20File.sol"
+        );
+        assert_eq!(
+            location["physicalLocation"]["artifactLocation"]["uriBaseId"],
+            "%SRCROOT%"
+        );
+        let region = &location["physicalLocation"]["region"];
+        assert_eq!(region["startLine"], 2);
+        assert_eq!(region["startColumn"], 25);
+        assert_eq!(region["endLine"], 2);
+        assert_eq!(region["endColumn"], 31);
+        assert_eq!(region["byteOffset"], 30);
+        assert_eq!(region["byteLength"], 6);
+        assert_eq!(
+            location["logicalLocations"][0]["fullyQualifiedName"],
+            "Foo.bar"
+        );
+        assert_eq!(location["logicalLocations"][0]["kind"], "function");
+    }
+
+    #[test]
+    fn test_sarif_outside_root() {
+        let diags = vec![FileDiagnostics {
+            path: PathBuf::from("/other/Foo.sol"),
+            document_id: DocumentId::new(),
+            items: vec![
+                ItemDiagnostics::builder()
+                    .item_type(ItemType::Contract)
+                    .name("Foo")
+                    .span(index(0, 0, 0)..index(10, 0, 10))
+                    .diags(vec![Diagnostic {
+                        severity: Severity::Warning,
+                        ..Diagnostic::new(
+                            Rule::MissingTitle,
+                            index(0, 0, 0)..index(10, 0, 10),
+                            "@title is missing",
+                        )
+                    }])
+                    .build(),
+            ],
+        }];
+        let log = SarifLog::new(&diags, "/project", "1.0.0");
+        let value = serde_json::to_value(&log).unwrap();
+        let artifact =
+            &value["runs"][0]["results"][0]["locations"][0]["physicalLocation"]["artifactLocation"];
+        assert_eq!(artifact["uri"], "file:///other/Foo.sol");
+        assert_eq!(value["runs"][0]["results"][0]["level"], "warning");
+        assert!(artifact.get("uriBaseId").is_none());
+    }
+
+    #[test]
+    fn test_sarif_relative_path() {
+        let diags = vec![FileDiagnostics {
+            path: PathBuf::from("src/Foo.sol"),
+            document_id: DocumentId::new(),
+            items: vec![
+                ItemDiagnostics::builder()
+                    .item_type(ItemType::Contract)
+                    .name("Foo")
+                    .span(index(0, 0, 0)..index(10, 0, 10))
+                    .diags(vec![Diagnostic::new(
+                        Rule::MissingTitle,
+                        index(0, 0, 0)..index(10, 0, 10),
+                        "@title is missing",
+                    )])
+                    .build(),
+            ],
+        }];
+        let log = SarifLog::new(&diags, "/project", "1.0.0");
+        let value = serde_json::to_value(&log).unwrap();
+        let artifact =
+            &value["runs"][0]["results"][0]["locations"][0]["physicalLocation"]["artifactLocation"];
+        assert_eq!(artifact["uri"], "src/Foo.sol");
+        assert_eq!(artifact["uriBaseId"], "%SRCROOT%");
+    }
+}
@@ -0,0 +1,424 @@
+//! Inline suppression comments
+//!
+//! Diagnostics can be silenced with comments in the source code. Each directive can optionally be followed by a list
+//! of rule identifiers (separated by spaces or commas) to only suppress diagnostics for those rules. If no rule is
+//! provided, all rules are suppressed.
+//!
+//! - `// lintspec-disable-next-line [rule...]`: suppress diagnostics starting on the next line
+//! - `// lintspec-disable-next-item [rule...]`: suppress diagnostics for the next source item (function, struct, etc.)
+//! - `// lintspec-disable-start [rule...]` and `// lintspec-disable-end`: suppress diagnostics starting between the
+//!   two comments (or until the end of the file if there is no end comment)
+//! - `// lintspec-disable-file [rule...]`: suppress diagnostics in the whole file
+//!
+//! The directives can also be written inside of block comments (`/* lintspec-disable-file */`), but not in
+//! doc-comments. Unknown rule identifiers are reported with the `unknown-rule` rule.
+use std::ops::Range;
+
+use crate::{
+    definitions::ItemType,
+    lint::{Diagnostic, ItemDiagnostics, Rule},
+    natspec::edit_distance,
+    textindex::{TextRange, compute_indices},
+};
+
+/// The prefix shared by all directives
+const DIRECTIVE_PREFIX: &str = "lintspec-disable";
+
+/// A set of rules targeted by a directive
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RuleSet {
+    /// All rules
+    All,
+
+    /// A specific list of rules
+    Some(Vec<Rule>),
+}
+
+impl RuleSet {
+    /// Check if the rule set contains a rule
+    #[must_use]
+    pub fn contains(&self, rule: Rule) -> bool {
+        match self {
+            RuleSet::All => true,
+            RuleSet::Some(rules) => rules.contains(&rule),
+        }
+    }
+
+    /// Parse a list of rules separated by spaces or commas
+    ///
+    /// Unknown rule identifiers are not part of the set, their byte offsets (relative to the start of the input) are
+    /// returned alongside it.
+    fn parse(input: &str) -> (Self, Vec<Range<usize>>) {
+        let mut rules = Vec::new();
+        let mut unknown = Vec::new();
+        for word in input
+            .split(|c: char| c.is_whitespace() || c == ',')
+            .filter(|w| !w.is_empty())
+        {
+            if let Ok(rule) = word.parse() {
+                rules.push(rule);
+            } else {
+                let start = word.as_ptr().addr() - input.as_ptr().addr();
+                unknown.push(start..start + word.len());
+            }
+        }
+        if rules.is_empty() && unknown.is_empty() {
+            return (RuleSet::All, unknown);
+        }
+        (RuleSet::Some(rules), unknown)
+    }
+}
+
+/// The suppression directives found in a source file
+///
+/// All offsets are utf-8 byte offsets from the start of the file.
+#[derive(Debug, Clone, Default)]
+pub struct Suppressions {
+    /// Rules suppressed in the whole file
+    file: Vec<RuleSet>,
+
+    /// Rules suppressed for diagnostics starting in a byte range (next line or start/end blocks)
+    ranges: Vec<(Range<usize>, RuleSet)>,
+
+    /// Rules suppressed for the next item after a byte offset
+    next_items: Vec<(usize, RuleSet)>,
+
+    /// The span of the comments containing unknown rule identifiers, with the span of those identifiers
+    unknown: Vec<(Range<usize>, Vec<Range<usize>>)>,
+}
+
+impl Suppressions {
+    /// Find all the suppression directives in a source file
+    ///
+    /// The `comments` are the spans of the regular comments found by the parser (see
+    /// [`ParsedDocument::comments`][crate::parser::ParsedDocument::comments]), sorted by start offset. Text which
+    /// looks like a directive outside of a comment (e.g. in a string literal) is ignored.
+    #[must_use]
+    pub fn new(source: &str, comments: &[Range<usize>]) -> Self {
+        let mut res = Self::default();
+        if !source.contains(DIRECTIVE_PREFIX) {
+            return res;
+        }
+        let mut open_blocks: Vec<(usize, RuleSet)> = Vec::new();
+        for comment in comments {
+            let Some((directive, rules, unknown)) =
+                source.get(comment.clone()).and_then(parse_directive)
+            else {
+                continue;
+            };
+            if !unknown.is_empty() {
+                res.unknown.push((
+                    comment.clone(),
+                    unknown
+                        .into_iter()
+                        .map(|r| comment.start + r.start..comment.start + r.end)
+                        .collect(),
+                ));
+            }
+            // the end of the line containing the end of the comment, including the line feed
+            let line_end = source[comment.end..]
+                .find('\n')
+                .map_or(source.len(), |i| comment.end + i + 1);
+            match directive {
+                "next-line" => {
+                    let next_line_end = source[line_end..]
+                        .find('\n')
+                        .map_or(source.len(), |i| line_end + i);
+                    res.ranges.push((line_end..next_line_end, rules));
+                }
+                "next-item" => res.next_items.push((line_end, rules)),
+                "start" => open_blocks.push((line_end, rules)),
+                "end" => {
+                    if let Some((start, rules)) = open_blocks.pop() {
+                        // the start of the line containing the directive
+                        let line_start = source[..comment.start].rfind('\n').map_or(0, |i| i + 1);
+                        res.ranges.push((start..line_start, rules));
+                    }
+                }
+                "file" => res.file.push(rules),
+                _ => {}
+            }
+        }
+        // blocks which were not closed extend until the end of the file
+        for (start, rules) in open_blocks {
+            res.ranges.push((start..source.len(), rules));
+        }
+        res
+    }
+
+    /// Check whether no directive was found
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.file.is_empty() && self.ranges.is_empty() && self.next_items.is_empty()
+    }
+
+    /// Check whether a diagnostic should be suppressed
+    ///
+    /// The `item_start` is the start offset of the diagnostic's source item, and `diag_start` is the start offset of
+    /// the diagnostic itself. The `item_spans` contains the span of all the items in the file (with or without
+    /// diagnostics), sorted by start offset. It is used to identify the item targeted by a
+    /// `lintspec-disable-next-item` directive, which is the item following the directive, or the item whose span
+    /// includes the directive (e.g. if the directive is placed between the `NatSpec` and the declaration).
+    #[must_use]
+    pub fn is_suppressed(
+        &self,
+        rule: Rule,
+        item_start: usize,
+        diag_start: usize,
+        item_spans: &[Range<usize>],
+    ) -> bool {
+        if self.file.iter().any(|r| r.contains(rule)) {
+            return true;
+        }
+        if self
+            .ranges
+            .iter()
+            .any(|(range, r)| range.contains(&diag_start) && r.contains(rule))
+        {
+            return true;
+        }
+        self.next_items.iter().any(|(offset, r)| {
+            item_spans
+                .iter()
+                .find(|span| span.end > *offset)
+                .is_some_and(|span| span.start == item_start)
+                && r.contains(rule)
+        })
+    }
+
+    /// Generate a diagnostic for each unknown rule identifier in the directives
+    ///
+    /// The diagnostics of a directive are grouped under an item of type [`ItemType::Comment`], and the message
+    /// suggests the closest rule, if any.
+    #[must_use]
+    pub fn unknown_rules(&self, source: &str) -> Vec<ItemDiagnostics> {
+        if self.unknown.is_empty() {
+            return Vec::new();
+        }
+        let mut offsets: Vec<_> = self
+            .unknown
+            .iter()
+            .flat_map(|(comment, words)| {
+                [comment.start, comment.end]
+                    .into_iter()
+                    .chain(words.iter().flat_map(|w| [w.start, w.end]))
+            })
+            .collect();
+        offsets.sort_unstable();
+        offsets.dedup();
+        let indices = compute_indices(source, &offsets);
+        let range = |span: &Range<usize>| -> TextRange {
+            let index = |offset: usize| indices[offsets.binary_search(&offset).unwrap_or_default()];
+            index(span.start)..index(span.end)
+        };
+        self.unknown
+            .iter()
+            .map(|(comment, words)| ItemDiagnostics {
+                parent: None,
+                item_type: ItemType::Comment,
+                name: "",
+                span: range(comment),
+                diags: words
+                    .iter()
+                    .map(|word| {
+                        let word_str = &source[word.clone()];
+                        let message = match suggest_rule(word_str) {
+                            Some(suggestion) => {
+                                format!("unknown rule `{word_str}`, did you mean `{suggestion}`?")
+                            }
+                            None => format!("unknown rule `{word_str}`"),
+                        };
+                        Diagnostic::new(Rule::UnknownRule, range(word), message)
+                    })
+                    .collect(),
+            })
+            .collect()
+    }
+}
+
+/// Parse a comment and extract the directive name (without prefix) and the list of rules, if any
+///
+/// The directive must be the first thing in a regular comment (not a doc-comment). The byte offsets of the unknown
+/// rule identifiers are relative to the start of the comment.
+fn parse_directive(comment: &str) -> Option<(&str, RuleSet, Vec<Range<usize>>)> {
+    let body = comment
+        .strip_prefix("//")
+        .or_else(|| comment.strip_prefix("/*"))?;
+    if body.starts_with(['/', '*']) {
+        // doc-comment
+        return None;
+    }
+    let body = body.strip_suffix("*/").unwrap_or(body);
+    let rest = body
+        .trim_start()
+        .strip_prefix(DIRECTIVE_PREFIX)?
+        .strip_prefix('-')?
+        .trim_end();
+    let (directive, rules) = rest
+        .split_once(char::is_whitespace)
+        .unwrap_or((rest, &rest[rest.len()..]));
+    let (rules_set, mut unknown) = RuleSet::parse(rules);
+    let rules_start = rules.as_ptr().addr() - comment.as_ptr().addr();
+    for range in &mut unknown {
+        *range = rules_start + range.start..rules_start + range.end;
+    }
+    Some((directive, rules_set, unknown))
+}
+
+/// Suggest a rule for an unknown rule identifier, based on the edit distance
+fn suggest_rule(word: &str) -> Option<Rule> {
+    let word = word.to_lowercase();
+    Rule::ALL
+        .iter()
+        .map(|r| (edit_distance(&word, &r.to_string()), *r))
+        .filter(|(distance, _)| *distance <= 3)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, r)| r)
+}
+
+#[cfg(test)]
+mod tests {
+    use similar_asserts::assert_eq;
+
+    use super::*;
+
+    /// Find the regular comments in a source without a parser (doesn't handle comment markers in string literals)
+    fn comments(source: &str) -> Vec<Range<usize>> {
+        let mut res = Vec::new();
+        let mut offset = 0;
+        while let Some(idx) = source[offset..].find(['/']) {
+            let start = offset + idx;
+            let rest = &source[start..];
+            let end = if rest.starts_with("//") {
+                rest.find('\n').map_or(source.len(), |i| start + i)
+            } else if rest.starts_with("/*") {
+                rest.find("*/").map_or(source.len(), |i| start + i + 2)
+            } else {
+                offset = start + 1;
+                continue;
+            };
+            if !rest.starts_with("///") && !rest.starts_with("/**") {
+                res.push(start..end);
+            }
+            offset = end;
+        }
+        res
+    }
+
+    #[test]
+    fn test_parse_directive() {
+        assert_eq!(
+            parse_directive("// lintspec-disable-next-line"),
+            Some(("next-line", RuleSet::All, vec![]))
+        );
+        assert_eq!(
+            parse_directive("//lintspec-disable-next-item missing-param, missing-dev"),
+            Some((
+                "next-item",
+                RuleSet::Some(vec![Rule::MissingParam, Rule::MissingDev]),
+                vec![]
+            ))
+        );
+        assert_eq!(
+            parse_directive("/* lintspec-disable-file missing-title */"),
+            Some(("file", RuleSet::Some(vec![Rule::MissingTitle]), vec![]))
+        );
+        assert_eq!(
+            parse_directive("/*\n    lintspec-disable-start\n*/"),
+            Some(("start", RuleSet::All, vec![]))
+        );
+        assert_eq!(parse_directive("/// lintspec-disable-file"), None);
+        assert_eq!(parse_directive("/** lintspec-disable-file */"), None);
+        assert_eq!(parse_directive("// @notice lintspec-disable-file"), None);
+        assert_eq!(parse_directive("// lintspec-disable"), None);
+    }
+
+    #[test]
+    fn test_unknown_rules() {
+        let comment = "// lintspec-disable-next-line missing-parm, foo missing-dev";
+        assert_eq!(
+            parse_directive(comment),
+            Some((
+                "next-line",
+                RuleSet::Some(vec![Rule::MissingDev]),
+                vec![30..42, 44..47]
+            ))
+        );
+        // only unknown rules, nothing is suppressed
+        let (_, rules, unknown) = parse_directive("// lintspec-disable-file foo").unwrap();
+        assert_eq!(rules, RuleSet::Some(vec![]));
+        assert_eq!(unknown.len(), 1);
+        assert_eq!(unknown[0], 25..28);
+        assert_eq!(suggest_rule("missing-parm"), Some(Rule::MissingParam));
+        assert_eq!(suggest_rule("Missing-Notice"), Some(Rule::MissingNotice));
+        assert_eq!(suggest_rule("foo"), None);
+
+        let source = format!("contract Foo {{\n    {comment}\n}}\n");
+        let s = Suppressions::new(&source, &comments(&source));
+        let items = s.unknown_rules(&source);
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].item_type, ItemType::Comment);
+        assert_eq!(items[0].span.start.utf8, 19);
+        let diags = &items[0].diags;
+        assert_eq!(diags.len(), 2);
+        assert_eq!(diags[0].rule, Rule::UnknownRule);
+        assert_eq!(diags[0].span.start.line, 1);
+        assert_eq!(diags[0].span.start.col_utf8, 34);
+        assert_eq!(
+            diags[0].message,
+            "unknown rule `missing-parm`, did you mean `missing-param`?"
+        );
+        assert_eq!(diags[1].message, "unknown rule `foo`");
+    }
+
+    #[test]
+    fn test_next_line() {
+        let source = "// lintspec-disable-next-line missing-notice\nfoo\nbar\n";
+        let s = Suppressions::new(source, &comments(source));
+        let spans = [45..48, 49..52];
+        assert!(s.is_suppressed(Rule::MissingNotice, 45, 45, &spans));
+        assert!(!s.is_suppressed(Rule::MissingDev, 45, 45, &spans));
+        assert!(!s.is_suppressed(Rule::MissingNotice, 49, 49, &spans));
+    }
+
+    #[test]
+    fn test_next_item() {
+        let source = "foo\n// lintspec-disable-next-item\n\nbar\nbaz\n";
+        let s = Suppressions::new(source, &comments(source));
+        let spans = [0..3, 35..38, 39..42];
+        assert!(!s.is_suppressed(Rule::MissingNotice, 0, 0, &spans));
+        assert!(s.is_suppressed(Rule::MissingNotice, 35, 37, &spans));
+        assert!(!s.is_suppressed(Rule::MissingNotice, 39, 39, &spans));
+        // the directive is inside of the item's span
+        let spans = [0..38, 39..42];
+        assert!(s.is_suppressed(Rule::MissingNotice, 0, 0, &spans));
+        assert!(!s.is_suppressed(Rule::MissingNotice, 39, 39, &spans));
+    }
+
+    #[test]
+    fn test_blocks() {
+        let source = "a\n// lintspec-disable-start\nb\n// lintspec-disable-end\nc\n// lintspec-disable-start extra-param\nd\n";
+        let s = Suppressions::new(source, &comments(source));
+        assert!(!s.is_suppressed(Rule::MissingNotice, 0, 0, &[]));
+        assert!(s.is_suppressed(Rule::MissingNotice, 28, 28, &[]));
+        assert!(!s.is_suppressed(Rule::MissingNotice, 54, 54, &[]));
+        assert!(s.is_suppressed(Rule::ExtraParam, 95, 95, &[]));
+        assert!(!s.is_suppressed(Rule::MissingParam, 95, 95, &[]));
+    }
+
+    #[test]
+    fn test_file() {
+        let source = "// lintspec-disable-file\ncontract Foo {}\n";
+        let s = Suppressions::new(source, &comments(source));
+        assert!(s.is_suppressed(Rule::MissingTitle, 25, 25, &[]));
+        let source = "contract Foo {}\n";
+        assert!(Suppressions::new(source, &comments(source)).is_empty());
+    }
+
+    #[test]
+    fn test_not_a_comment() {
+        // the directive is only recognized in the comments found by the parser
+        let source = "string constant A = \"// lintspec-disable-file\";\n";
+        assert!(Suppressions::new(source, &[]).is_empty());
+    }
+}
@@ -5,7 +5,7 @@
 use std::{fmt, ops::Range};
 
 use derive_more::Add;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use wide::{CmpEq as _, CmpLt as _, i8x32};
 use zerocopy::transmute_ref;
 
@@ -17,7 +17,7 @@ pub type TextRange = Range<TextIndex>;
 /// A position inside of the source code
 ///
 /// Lines and columns start at 0.
-#[derive(Default, Hash, Copy, Clone, PartialEq, Eq, Debug, Serialize, Add)]
+#[derive(Default, Hash, Copy, Clone, PartialEq, Eq, Debug, Serialize, Deserialize, Add)]
 pub struct TextIndex {
     /// Byte offset from the start of the document
     pub utf8: usize,
@@ -132,6 +132,22 @@ impl TextIndex {
             }
         }
     }
+
+    /// Convert an index which is relative to `origin` into an index with the same reference as `origin`.
+    ///
+    /// The columns are only offset by the origin's columns if the index is on the first line.
+    #[must_use]
+    pub fn offset_by(self, origin: TextIndex) -> TextIndex {
+        if self.line == 0 {
+            origin + self
+        } else {
+            TextIndex {
+                utf8: origin.utf8 + self.utf8,
+                line: origin.line + self.line,
+                ..self
+            }
+        }
+    }
 }
 
 impl fmt::Display for TextIndex {
@@ -486,6 +502,51 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_offset_by() {
+        let origin = TextIndex {
+            utf8: 10,
+            line: 2,
+            col_utf8: 4,
+            col_utf16: 4,
+            col_utf32: 4,
+        };
+        let same_line = TextIndex {
+            utf8: 3,
+            line: 0,
+            col_utf8: 3,
+            col_utf16: 3,
+            col_utf32: 3,
+        };
+        assert_eq!(
+            same_line.offset_by(origin),
+            TextIndex {
+                utf8: 13,
+                line: 2,
+                col_utf8: 7,
+                col_utf16: 7,
+                col_utf32: 7,
+            }
+        );
+        let next_line = TextIndex {
+            utf8: 20,
+            line: 1,
+            col_utf8: 6,
+            col_utf16: 6,
+            col_utf32: 6,
+        };
+        assert_eq!(
+            next_line.offset_by(origin),
+            TextIndex {
+                utf8: 30,
+                line: 3,
+                col_utf8: 6,
+                col_utf16: 6,
+                col_utf32: 6,
+            }
+        );
+    }
+
     #[test]
     fn test_compute_indices_simple() {
         let source = "hello world";
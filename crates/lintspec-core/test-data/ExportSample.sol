@@ -0,0 +1,69 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.20;
+
+/// @title Errors shared by the token
+library TokenErrors {
+    /// @notice The amount is too large
+    /// @dev Raised by the library
+    /// @param amount The amount
+    error InvalidAmount(uint256 amount);
+}
+
+/// @title A token
+/// @author lintspec
+/// @notice Transfers tokens
+/// @dev Only used as a fixture
+/// @custom:security-contact security@example.com
+contract Token {
+    /// @notice The amount is invalid
+    /// @dev Raised by the token
+    /// @param amount The invalid amount
+    error InvalidAmount(uint256 amount);
+
+    /// @notice The balance of each account
+    /// @dev Updated on transfer
+    /// @return The balance
+    mapping(address => uint256) public balanceOf;
+
+    /// @notice The name of the token
+    string public name;
+
+    /// @dev The total supply
+    uint256 internal supply;
+
+    /// @notice Transfer tokens
+    /// @param to The recipient
+    /// @param amount The amount
+    /// @return Whether the transfer succeeded
+    function transfer(address to, uint256 amount) external returns (bool) {
+        if (amount > supply) {
+            revert InvalidAmount(amount);
+        }
+        if (amount > balanceOf[msg.sender]) {
+            revert TokenErrors.InvalidAmount(amount);
+        }
+        balanceOf[msg.sender] -= amount;
+        balanceOf[to] += amount;
+        return true;
+    }
+
+    /// @notice Transfer tokens with some data
+    /// @param to The recipient
+    /// @param amount The amount
+    /// @param data The data
+    /// @return success Whether the transfer succeeded
+    /// @custom:experimental The data is ignored
+    function transfer(address to, uint256 amount, bytes calldata data) external returns (bool success) {
+        success = this.transfer(to, amount);
+        data;
+    }
+
+    /// @notice The balances of two accounts
+    /// @param a The first account
+    /// @param b The second account
+    /// @return The balance of the first account
+    /// @return second The balance of the second account
+    function balances(address a, address b) external view returns (uint256, uint256 second) {
+        return (balanceOf[a], balanceOf[b]);
+    }
+}
@@ -0,0 +1,42 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.0;
+
+/// @notice This comment is not attached to anything
+
+/// @title StrayComments
+/// @notice A contract with stray comments
+contract StrayComments {
+    /// @notice The using directive can't be documented
+    using Math for uint256;
+
+    // @notice A regular comment is ignored by the compiler
+    // @param a The param
+    function regular(uint256 a) external {}
+
+    /* @dev Block comments too */
+    /// @notice A documented variable
+    uint256 public documented;
+
+    /// @notice Not attached
+    /**
+     * @notice A function
+     */
+    function twice() external {}
+
+    /// @notice Separated from the variable by an empty line
+    /// @dev It's still attached to it
+
+    uint256 public separated;
+
+    function body() external {
+        /// @solidity memory-safe-assembly
+        assembly {}
+        // @dev The comments inside of functions are not checked
+    }
+
+    // A regular comment which mentions @notice in the middle of a line is fine
+
+    /// @notice At the end of the contract
+}
+
+library Math {}
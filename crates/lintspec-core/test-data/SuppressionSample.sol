@@ -0,0 +1,30 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.0;
+
+contract SuppressionSample {
+    // lintspec-disable-next-item
+    function undocumented(uint256 a) external returns (uint256) {}
+
+    /// @notice Documented function without params docs
+    // lintspec-disable-next-line missing-param
+    function nextLine(uint256 a, uint256 b) external {}
+
+    /// @notice Only some rules are disabled for this item
+    // lintspec-disable-next-item missing-inheritdoc
+    function someRules(uint256 a) external {}
+
+    // lintspec-disable-start missing-notice
+    uint256 internal first;
+
+    struct Foo {
+        uint256 a;
+    }
+    // lintspec-disable-end
+
+    uint256 internal second;
+
+    /* lintspec-disable-next-item */
+    event Transfer(address from, address to);
+
+    error NotSuppressed(uint256 code);
+}
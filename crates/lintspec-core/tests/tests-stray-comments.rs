@@ -0,0 +1,28 @@
+#![cfg(feature = "solar")]
+use lintspec_core::lint::ValidationOptions;
+
+mod common;
+use common::*;
+
+#[test]
+fn test_stray_comments() {
+    insta::assert_snapshot!(snapshot_content(
+        "./test-data/StrayComments.sol",
+        &ValidationOptions::builder()
+            .inheritdoc(false)
+            .stray_comments(true)
+            .build(),
+        true,
+        true
+    ));
+}
+
+#[test]
+fn test_disabled() {
+    insta::assert_snapshot!(snapshot_content(
+        "./test-data/StrayComments.sol",
+        &ValidationOptions::builder().inheritdoc(false).build(),
+        true,
+        true
+    ));
+}
@@ -0,0 +1,25 @@
+#![cfg(feature = "solar")]
+use lintspec_core::lint::ValidationOptions;
+
+mod common;
+use common::*;
+
+#[test]
+fn test_suppression() {
+    insta::assert_snapshot!(snapshot_content(
+        "./test-data/SuppressionSample.sol",
+        &ValidationOptions::default(),
+        true,
+        true
+    ));
+}
+
+#[test]
+fn test_no_inheritdoc() {
+    insta::assert_snapshot!(snapshot_content(
+        "./test-data/SuppressionSample.sol",
+        &ValidationOptions::builder().inheritdoc(false).build(),
+        true,
+        true
+    ));
+}
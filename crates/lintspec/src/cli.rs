@@ -1,26 +1,40 @@
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, HashMap},
     env,
     error::Error,
     fs::{self, File},
-    io,
+    io::{self, Read as _},
     num::NonZero,
     path::{Path, PathBuf},
     sync::Arc,
     thread::available_parallelism,
 };
 
-use clap::{Parser, Subcommand};
+use clap::{Parser, Subcommand, ValueEnum as _};
 use clap_complete::Shell;
 use miette::{LabeledSpan, MietteDiagnostic, NamedSource};
 use rayon::iter::{IntoParallelRefIterator as _, ParallelIterator};
+use similar::TextDiff;
 
 use lintspec_core::{
-    config::{Config, Req},
-    definitions::{ContractType, ItemType},
+    baseline::{Baseline, BaselineEntry},
+    cache::{CACHE_DIR, Cache},
+    changes::ChangedLines,
+    config::{Config, OutputFormat, Req, Severity},
+    coverage::{Coverage, CoverageFormat, CoverageReport, FileCoverage, file_coverage},
+    definitions::{ContractType, Definition, ItemType},
+    error::ErrorKind,
+    export::DocsExport,
     files::find_sol_files,
-    lint::{FileDiagnostics, ItemDiagnostics, ValidationOptions, lint},
-    parser::Parse as _,
+    fix::apply_fixes,
+    lint::{
+        FileDiagnostics, ItemDiagnostics, ValidationOptions, lint_source_with_project,
+        lint_with_project,
+    },
+    markdown,
+    parser::Parse,
+    project::Project,
+    sarif::SarifLog,
 };
 
 #[cfg(feature = "slang")]
@@ -40,6 +54,82 @@ pub enum Commands {
     /// Create a `.lintspec.toml` config file with default values
     Init,
 
+    /// List all the rules with their description
+    Rules,
+
+    /// Record the current diagnostics in a baseline file
+    ///
+    /// The diagnostics recorded in the baseline are not reported when linting with `--baseline`.
+    Baseline {
+        /// One or more paths to files and folders to analyze, in addition to the ones given before the command
+        #[arg(name = "PATH", value_hint = clap::ValueHint::AnyPath)]
+        paths: Vec<PathBuf>,
+
+        /// The path of the baseline file to write
+        #[arg(
+            short,
+            long,
+            value_hint = clap::ValueHint::FilePath,
+            default_value = ".lintspec-baseline.json"
+        )]
+        write: PathBuf,
+    },
+
+    /// Report the share of items which are fully documented
+    ///
+    /// An item is documented if its `NatSpec` doesn't produce any diagnostic with the current configuration.
+    Coverage {
+        /// One or more paths to files and folders to analyze, in addition to the ones given before the command
+        #[arg(name = "PATH", value_hint = clap::ValueHint::AnyPath)]
+        paths: Vec<PathBuf>,
+
+        /// Format of the coverage report
+        ///
+        /// The `badge` format produces the JSON data for a shields.io endpoint badge.
+        #[arg(long, value_enum, default_value_t)]
+        format: CoverageFormat,
+
+        /// Exit with code 1 if the total coverage is below this percentage
+        #[arg(long, value_name = "PERCENT")]
+        min_coverage: Option<f64>,
+    },
+
+    /// Export the `NatSpec` of each contract as JSON, in the `userdoc` and `devdoc` format of the compiler
+    ///
+    /// By default, the documentation of all the contracts is written to the output file or stdout, keyed by file and
+    /// contract name.
+    Export {
+        /// One or more paths to files and folders to analyze, in addition to the ones given before the command
+        #[arg(name = "PATH", value_hint = clap::ValueHint::AnyPath)]
+        paths: Vec<PathBuf>,
+
+        /// Write the `<Contract>.userdoc.json` and `<Contract>.devdoc.json` files of each contract in this directory
+        ///
+        /// The files are grouped in a sub-folder named after the source file, e.g. `<DIR>/Vault.sol/Vault.devdoc.json`.
+        #[arg(long, value_name = "DIR", value_hint = clap::ValueHint::DirPath)]
+        out_dir: Option<PathBuf>,
+    },
+
+    /// Render the `NatSpec` of each contract as a Markdown page, with an index page
+    Docs {
+        /// One or more paths to files and folders to analyze, in addition to the ones given before the command
+        #[arg(name = "PATH", value_hint = clap::ValueHint::AnyPath)]
+        paths: Vec<PathBuf>,
+
+        /// The output directory for the pages
+        #[arg(short, long, value_hint = clap::ValueHint::DirPath, default_value = "docs")]
+        out: PathBuf,
+    },
+
+    /// Manage the cache of the diagnostics
+    Cache {
+        #[command(subcommand)]
+        command: CacheCommand,
+    },
+
+    /// Start a language server (LSP) communicating over stdin/stdout
+    Lsp,
+
     /// Generate shell completion scripts
     Completions {
         /// The flavor of shell for which to generate the completion script
@@ -52,14 +142,32 @@ pub enum Commands {
     },
 }
 
+#[derive(Subcommand, Debug, Clone)]
+pub enum CacheCommand {
+    /// Remove the cache directory (`.lintspec-cache`)
+    Clean,
+}
+
 #[derive(Parser, Debug, Clone)]
 #[command(version = VERSION, about, long_about = None)]
 #[non_exhaustive]
 pub struct Args {
     /// One or more paths to files and folders to analyze
+    ///
+    /// Use `-` to read the Solidity source from stdin.
     #[arg(name = "PATH", value_hint = clap::ValueHint::AnyPath)]
     pub paths: Vec<PathBuf>,
 
+    /// Read the Solidity source from stdin instead of files
+    ///
+    /// When used with `--fix`, the fixed source is written to stdout and the diagnostics are written to stderr.
+    #[arg(long)]
+    pub stdin: bool,
+
+    /// Path of the file read from stdin, used in the output and to match the configuration
+    #[arg(long, value_hint = clap::ValueHint::FilePath)]
+    pub stdin_filename: Option<PathBuf>,
+
     /// Path to a file or folder to exclude (can be used more than once)
     ///
     /// To exclude paths based on a pattern, use a `.nsignore` file (same syntax as `.gitignore`).
@@ -99,12 +207,63 @@ pub struct Args {
     #[arg(long, num_args = 0..=1, default_missing_value = "true")]
     pub notice_or_dev: Option<bool>,
 
+    /// Report the doc-comments which are not attached to any item, and the regular comments with `NatSpec` tags
+    ///
+    /// Can be set with `--stray-comments` (means true), `--stray-comments=true` or `--stray-comments=false`.
+    #[arg(long, num_args = 0..=1, default_missing_value = "true")]
+    pub stray_comments: Option<bool>,
+
     /// Number of parallel workers/threads, or 0 to use the number of logical cores
     ///
     /// Defaults to 4 for a good balance of parallelism and synchronization overhead.
     #[arg(short = 'n', long, name = "THREADS")]
     pub parallel: Option<usize>,
 
+    /// Automatically fix the diagnostics which can be fixed
+    ///
+    /// Missing `@param` and `@return` tags are inserted with a placeholder description, using the existing comment
+    /// style if any. Extra `@param` and `@return` tags are removed (or renamed if they match an undocumented param).
+    ///
+    /// Can be set with `--fix` (means true), `--fix=true` or `--fix=false`.
+    #[arg(long, num_args = 0..=1, default_missing_value = "true")]
+    pub fix: Option<bool>,
+
+    /// Watch the files for changes and lint them again, printing only the updated diagnostics
+    ///
+    /// A full re-scan of the paths only happens when files are added or removed.
+    #[arg(long)]
+    pub watch: bool,
+
+    /// Print a unified diff of the fixes to stdout instead of modifying the files (requires `--fix`)
+    ///
+    /// Can be set with `--dry-run` (means true), `--dry-run=true` or `--dry-run=false`.
+    #[arg(long, num_args = 0..=1, default_missing_value = "true")]
+    pub dry_run: Option<bool>,
+
+    /// Path to a baseline file, the diagnostics recorded in it are not reported
+    ///
+    /// Create or update the baseline with `lintspec baseline`. The entries which don't match any diagnostic anymore
+    /// are listed, so that they can be removed from the baseline.
+    #[arg(long, value_hint = clap::ValueHint::FilePath)]
+    pub baseline: Option<PathBuf>,
+
+    /// Only report the diagnostics for the items which changed since a git ref (e.g. `origin/main`)
+    ///
+    /// The working tree is compared with the ref, and untracked files are considered entirely changed. An item is
+    /// reported if its declaration or one of its diagnostics overlaps a changed line. With `--fix`, only the changed
+    /// items are fixed. This option has no effect when reading from stdin.
+    #[arg(long, value_name = "REF")]
+    pub changed_since: Option<String>,
+
+    /// Store the diagnostics of each file in a cache, so that the files which didn't change are not parsed again
+    ///
+    /// The diagnostics are stored in the `.lintspec-cache` folder, and the files which didn't change since the last run
+    /// (along with the files they import) are not parsed again.
+    ///
+    /// Can be set with `--cache` (means true), `--cache=true` or `--cache=false`.
+    #[arg(long, num_args = 0..=1, default_missing_value = "true")]
+    pub cache: Option<bool>,
+
     /// Skip the detection of the Solidity version from pragma statements and use the latest supported version.
     ///
     /// This is useful to speed up parsing slightly, or if the Solidity version is newer than the latest version
@@ -221,13 +380,21 @@ pub struct Args {
 
     /// Output diagnostics in JSON format
     ///
+    /// This is a shorthand for `--format json`.
+    ///
     /// Can be set with `--json` (means true), `--json=true` or `--json=false`.
-    #[arg(long, num_args = 0..=1, default_missing_value = "true")]
+    #[arg(long, num_args = 0..=1, default_missing_value = "true", conflicts_with = "format")]
     pub json: Option<bool>,
 
+    /// Output format for the diagnostics
+    ///
+    /// The `sarif` format produces a SARIF 2.1.0 log which can be uploaded to code scanning services.
+    #[arg(long, value_enum)]
+    pub format: Option<OutputFormat>,
+
     /// Compact output
     ///
-    /// If combined with `--json`, the output is minified.
+    /// If combined with `--format json` or `--format sarif`, the output is minified.
     ///
     /// Can be set with `--compact` (means true), `--compact=true` or `--compact=false`.
     #[arg(long, num_args = 0..=1, default_missing_value = "true")]
@@ -323,7 +490,7 @@ macro_rules! cli_rule_override {
                 ItemType::PrivateVariable => $config.variables.private.$tag = $req,
                 ItemType::InternalVariable => $config.variables.internal.$tag = $req,
                 ItemType::PublicVariable => $config.variables.public.$tag = $req,
-                ItemType::ParsingError => {}
+                ItemType::ParsingError | ItemType::Comment => {}
             }
         }
     };
@@ -339,8 +506,31 @@ pub fn read_config(args: Args) -> Result<Config, Box<figment::Error>> {
     if let Some(par) = args.parallel {
         config.lintspec.parallel = par;
     }
-    config.lintspec.paths.extend(args.paths);
+    let stdin_path = Path::new("-");
+    config.lintspec.stdin = args.stdin || args.paths.iter().any(|p| p == stdin_path);
+    config
+        .lintspec
+        .paths
+        .extend(args.paths.into_iter().filter(|p| p != stdin_path));
+    if let Some(stdin_filename) = args.stdin_filename {
+        config.lintspec.stdin_filename = Some(stdin_filename);
+    }
     config.lintspec.exclude.extend(args.exclude);
+    if let Some(fix) = args.fix {
+        config.lintspec.fix = fix;
+    }
+    if let Some(dry_run) = args.dry_run {
+        config.lintspec.dry_run = dry_run;
+    }
+    if let Some(baseline) = args.baseline {
+        config.lintspec.baseline = Some(baseline);
+    }
+    if let Some(changed_since) = args.changed_since {
+        config.lintspec.changed_since = Some(changed_since);
+    }
+    if let Some(cache) = args.cache {
+        config.lintspec.cache = cache;
+    }
     // output
     if let Some(out) = args.out {
         config.output.out = Some(out);
@@ -348,6 +538,11 @@ pub fn read_config(args: Args) -> Result<Config, Box<figment::Error>> {
     if let Some(json) = args.json {
         config.output.json = json;
     }
+    if let Some(format) = args.format {
+        // the `json` shorthand would take precedence over the format from the CLI
+        config.output.json = false;
+        config.output.format = format;
+    }
     if let Some(compact) = args.compact {
         config.output.compact = compact;
     }
@@ -375,6 +570,9 @@ pub fn read_config(args: Args) -> Result<Config, Box<figment::Error>> {
     if let Some(notice_or_dev) = args.notice_or_dev {
         config.lintspec.notice_or_dev = notice_or_dev;
     }
+    if let Some(stray_comments) = args.stray_comments {
+        config.lintspec.stray_comments = stray_comments;
+    }
 
     cli_rule_override!(config, args.title_ignored, title, Req::Ignored);
     cli_rule_override!(config, args.title_required, title, Req::Required);
@@ -400,74 +598,165 @@ pub fn read_config(args: Args) -> Result<Config, Box<figment::Error>> {
 
 /// The result of running the tool
 pub enum RunResult {
+    /// No diagnostic was emitted
     NoDiagnostics,
-    SomeDiagnostics,
+
+    /// Some diagnostics were emitted, but none of them has the `error` severity
+    SomeWarnings,
+
+    /// At least one diagnostic with the `error` severity was emitted
+    SomeErrors,
 }
 
 /// Run lintspec
 #[expect(clippy::too_many_lines)]
 pub fn run(config: &Config) -> Result<RunResult, Box<dyn Error>> {
-    // identify Solidity files to parse
-    let paths = find_sol_files(
-        &config.lintspec.paths,
-        &config.lintspec.exclude,
-        config.output.sort,
-        config.lintspec.parallel,
-    )?;
-    if paths.is_empty() {
-        return Err(String::from("no Solidity file found, nothing to analyze").into());
-    }
-
-    // lint all the requested Solidity files
     let options: ValidationOptions = config.into();
+    let format = config.output.output_format();
+    let keep_contents = format == OutputFormat::Text && !config.output.compact;
+    let cwd = dunce::canonicalize(env::current_dir()?)?;
 
-    #[cfg_attr(all(feature = "slang", feature = "slang"), expect(unused_variables))]
-    #[cfg(feature = "solar")]
-    let parser = SolarParser::default();
-
-    #[cfg(feature = "slang")]
-    let parser = SlangParser::builder()
-        .skip_version_detection(config.lintspec.skip_version_detection)
-        .build();
+    let parser = new_parser(config);
+    let mut fix_parser = None;
+    // used to resolve the imports, so that the contracts from other files are known
+    let project = Project::discover(&cwd);
 
-    let threads = if config.lintspec.parallel == 0 {
-        available_parallelism().map_or(1, NonZero::get)
+    let (mut diagnostics, linted) = if config.lintspec.stdin {
+        // lint the source from stdin
+        let path = config
+            .lintspec
+            .stdin_filename
+            .clone()
+            .unwrap_or_else(|| PathBuf::from("<stdin>"));
+        let mut source = String::new();
+        io::stdin()
+            .read_to_string(&mut source)
+            .map_err(|err| ErrorKind::IOError {
+                path: path.clone(),
+                err,
+            })?;
+        let mut diagnostics: Vec<_> = lint_source_with_project(
+            parser.clone(),
+            &path,
+            &source,
+            &options,
+            &project,
+            keep_contents,
+        )?
+        .into_iter()
+        .collect();
+        // apply the fixes and print the fixed source to stdout, then lint it again to report the remaining problems
+        if config.lintspec.fix {
+            let new_source = diagnostics
+                .first()
+                .and_then(|d| fix_source(&source, d))
+                .unwrap_or_else(|| source.clone());
+            if config.lintspec.dry_run {
+                print_diff(&path, &cwd, &source, &new_source);
+                retain_unfixable(&mut diagnostics);
+            } else {
+                print!("{new_source}");
+                if new_source != source {
+                    let parser = new_parser(config);
+                    diagnostics = lint_source_with_project(
+                        parser.clone(),
+                        &path,
+                        &new_source,
+                        &options,
+                        &project,
+                        keep_contents,
+                    )?
+                    .into_iter()
+                    .collect();
+                    fix_parser = Some(parser);
+                }
+            }
+        }
+        (diagnostics, vec![path])
     } else {
-        config.lintspec.parallel
+        // identify Solidity files to parse, only keeping the ones which changed if requested
+        let mut paths = find_paths(config)?;
+        let changed = config
+            .lintspec
+            .changed_since
+            .as_deref()
+            .map(|git_ref| ChangedLines::from_git(git_ref, &cwd))
+            .transpose()?;
+        if let Some(changed) = &changed {
+            paths.retain(|p| changed.contains_file(p));
+        }
+
+        // lint all the requested Solidity files
+        let threads = init_threads(config);
+        let cache = open_cache(config, &options);
+        let mut diagnostics = lint_files(
+            &parser,
+            &paths,
+            &options,
+            &project,
+            cache.as_ref(),
+            keep_contents,
+            threads,
+        )?;
+
+        // apply the fixes, then lint the modified files again to report the remaining problems
+        if config.lintspec.fix {
+            let fixed = if let Some(changed) = &changed {
+                // only the changed items are fixed
+                let mut changed_diags = diagnostics.clone();
+                changed.filter(&mut changed_diags);
+                fix_files(&changed_diags, &cwd, config.lintspec.dry_run)?
+            } else {
+                fix_files(&diagnostics, &cwd, config.lintspec.dry_run)?
+            };
+            if config.lintspec.dry_run {
+                retain_unfixable(&mut diagnostics);
+            } else if !fixed.is_empty() {
+                // the parser caches sources by path, so we need a new one to read the modified files
+                let parser = new_parser(config);
+                let cache = open_cache(config, &options);
+                let relinted = lint_files(
+                    &parser,
+                    &fixed,
+                    &options,
+                    &project,
+                    cache.as_ref(),
+                    keep_contents,
+                    threads,
+                )?;
+                fix_parser = Some(parser);
+                diagnostics.retain(|f| !fixed.contains(&f.path));
+                diagnostics.extend(relinted);
+                if config.output.sort {
+                    diagnostics.sort_unstable_by(|a, b| a.path.cmp(&b.path));
+                }
+            }
+        }
+        (diagnostics, paths)
     };
-    let diagnostics = if threads == 1 {
-        paths
+
+    // hide the diagnostics which are recorded in the baseline
+    let fixed_entries: Vec<BaselineEntry> = match &config.lintspec.baseline {
+        Some(path) => Baseline::read(path)?
+            .filter(&mut diagnostics, &linted, &cwd)
             .into_iter()
-            .filter_map(|p| {
-                lint(
-                    parser.clone(),
-                    p,
-                    &options,
-                    !config.output.compact && !config.output.json,
-                )
-                .transpose()
-            })
-            .collect::<Result<Vec<_>, _>>()?
-    } else {
-        rayon::ThreadPoolBuilder::new()
-            .num_threads(threads)
-            .build_global()
-            .ok();
-        paths
-            .par_iter()
-            .filter_map(|p| {
-                lint(
-                    parser.clone(),
-                    p,
-                    &options,
-                    !config.output.compact && !config.output.json,
-                )
-                .transpose()
-            })
-            .collect::<Result<Vec<_>, _>>()?
+            .cloned()
+            .collect(),
+        None => Vec::new(),
     };
+    // only report the items which changed since the git ref
+    if let Some(git_ref) = &config.lintspec.changed_since
+        && !config.lintspec.stdin
+    {
+        // the changed lines are retrieved again, because the fixes might have modified the files
+        ChangedLines::from_git(git_ref, &cwd)?.filter(&mut diagnostics);
+    }
+    // when the fixed source from stdin is written to stdout, the diagnostics must go elsewhere
+    let stdout_taken = config.lintspec.stdin && config.lintspec.fix && !config.lintspec.dry_run;
 
     // check if we should output to file or to stderr/stdout
+    let output_to_stderr = config.output.out.is_none()
+        && (!(diagnostics.is_empty() || config.output.stdout) || stdout_taken);
     let mut output_file: Box<dyn std::io::Write> = match &config.output.out {
         Some(path) => {
             let _ = miette::set_hook(Box::new(|_| {
@@ -485,24 +774,55 @@ pub fn run(config: &Config) -> Result<RunResult, Box<dyn Error>> {
                     .create(true)
                     .write(true)
                     .open(path)
-                    .map_err(|err| lintspec_core::error::ErrorKind::IOError {
+                    .map_err(|err| ErrorKind::IOError {
                         path: path.clone(),
                         err,
                     })?,
             )
         }
         None => {
-            if diagnostics.is_empty() || config.output.stdout {
-                Box::new(std::io::stdout())
-            } else {
+            if output_to_stderr {
                 Box::new(std::io::stderr())
+            } else {
+                Box::new(std::io::stdout())
             }
         }
     };
 
+    // the fixed baseline entries are listed along with the text output, or on stderr if it doesn't hold the output
+    if !fixed_entries.is_empty() {
+        if format == OutputFormat::Text {
+            print_fixed_entries(&mut output_file, &fixed_entries)?;
+        } else if !output_to_stderr {
+            print_fixed_entries(&mut io::stderr(), &fixed_entries)?;
+        }
+    }
+
+    let result = if diagnostics.is_empty() {
+        RunResult::NoDiagnostics
+    } else if diagnostics
+        .iter()
+        .any(|f| f.items.iter().any(|i| i.severity().is_error()))
+    {
+        RunResult::SomeErrors
+    } else {
+        RunResult::SomeWarnings
+    };
+
+    // the SARIF log is always produced, even if empty
+    if format == OutputFormat::Sarif {
+        let log = SarifLog::new(&diagnostics, &cwd, VERSION);
+        if config.output.compact {
+            writeln!(&mut output_file, "{}", serde_json::to_string(&log)?)?;
+        } else {
+            writeln!(&mut output_file, "{}", serde_json::to_string_pretty(&log)?)?;
+        }
+        return Ok(result);
+    }
+
     // no issue was found
     if diagnostics.is_empty() {
-        if config.output.json {
+        if format == OutputFormat::Json {
             writeln!(&mut output_file, "[]")?;
         } else {
             writeln!(&mut output_file, "No issue found")?;
@@ -511,7 +831,7 @@ pub fn run(config: &Config) -> Result<RunResult, Box<dyn Error>> {
     }
 
     // some issues were found, output according to the desired format (json/text, pretty/compact)
-    if config.output.json {
+    if format == OutputFormat::Json {
         if config.output.compact {
             writeln!(&mut output_file, "{}", serde_json::to_string(&diagnostics)?)?;
         } else {
@@ -522,15 +842,22 @@ pub fn run(config: &Config) -> Result<RunResult, Box<dyn Error>> {
             )?;
         }
     } else {
-        let cwd = dunce::canonicalize(env::current_dir()?)?;
         let mut contents = if cfg!(any(feature = "slang", feature = "solar")) {
             // all other clones have been dropped
-            parser.get_sources()?
+            let mut contents = parser.get_sources()?;
+            if let Some(fix_parser) = fix_parser {
+                contents.extend(fix_parser.get_sources()?);
+            }
+            contents
         } else {
             HashMap::default()
         };
         for file_diags in diagnostics {
-            let source = contents.remove(&file_diags.document_id).unwrap_or_default();
+            // the files whose diagnostics come from the cache were not parsed
+            let source = contents
+                .remove(&file_diags.document_id)
+                .or_else(|| fs::read_to_string(&file_diags.path).ok())
+                .unwrap_or_default();
             print_reports(
                 &mut output_file,
                 &cwd,
@@ -540,7 +867,440 @@ pub fn run(config: &Config) -> Result<RunResult, Box<dyn Error>> {
             )?;
         }
     }
-    Ok(RunResult::SomeDiagnostics)
+    Ok(result)
+}
+
+/// Lint the files and record the diagnostics in a baseline file
+///
+/// The paths in the baseline are relative to the current directory. Returns the number of recorded diagnostics.
+pub fn write_baseline(config: &Config, path: impl AsRef<Path>) -> Result<usize, Box<dyn Error>> {
+    if config.lintspec.stdin {
+        return Err(String::from("a baseline cannot be recorded from stdin").into());
+    }
+    let options: ValidationOptions = config.into();
+    let cwd = dunce::canonicalize(env::current_dir()?)?;
+    let parser = new_parser(config);
+    let project = Project::discover(&cwd);
+    let paths = find_paths(config)?;
+    let threads = init_threads(config);
+    let cache = open_cache(config, &options);
+    let diagnostics = lint_files(
+        &parser,
+        &paths,
+        &options,
+        &project,
+        cache.as_ref(),
+        false,
+        threads,
+    )?;
+    let baseline = Baseline::new(&diagnostics, &cwd);
+    baseline.write(path)?;
+    Ok(baseline.entries.len())
+}
+
+/// Compute the documentation coverage of the files and print the report
+///
+/// The report is written to the output file if any, or to stdout. Returns `false` if the total coverage is below the
+/// minimum percentage.
+pub fn coverage(
+    config: &Config,
+    format: CoverageFormat,
+    min_coverage: Option<f64>,
+) -> Result<bool, Box<dyn Error>> {
+    let options: ValidationOptions = config.into();
+    let cwd = dunce::canonicalize(env::current_dir()?)?;
+    let parser = new_parser(config);
+    let project = Project::discover(&cwd);
+    let paths = find_paths(config)?;
+    let threads = init_threads(config);
+    let mut files: Vec<FileCoverage> = if threads == 1 {
+        paths
+            .iter()
+            .map(|p| file_coverage(parser.clone(), p, &options, Some(&project)))
+            .collect::<Result<_, _>>()?
+    } else {
+        paths
+            .par_iter()
+            .map(|p| file_coverage(parser.clone(), p, &options, Some(&project)))
+            .collect::<Result<_, _>>()?
+    };
+    files.sort_unstable_by(|a, b| a.path.cmp(&b.path));
+    let report = CoverageReport::new(&files, &cwd);
+
+    let mut output_file: Box<dyn std::io::Write> = match &config.output.out {
+        Some(path) => Box::new(File::create(path).map_err(|err| ErrorKind::IOError {
+            path: path.clone(),
+            err,
+        })?),
+        None => Box::new(std::io::stdout()),
+    };
+    match format {
+        CoverageFormat::Text => print_coverage(&mut output_file, &report)?,
+        CoverageFormat::Json if config.output.compact => {
+            writeln!(&mut output_file, "{}", serde_json::to_string(&report)?)?;
+        }
+        CoverageFormat::Json => {
+            writeln!(
+                &mut output_file,
+                "{}",
+                serde_json::to_string_pretty(&report)?
+            )?;
+        }
+        CoverageFormat::Badge => {
+            writeln!(
+                &mut output_file,
+                "{}",
+                serde_json::to_string(&report.badge())?
+            )?;
+        }
+    }
+
+    if let Some(min_coverage) = min_coverage
+        && report.percent < min_coverage
+    {
+        eprintln!(
+            "Documentation coverage of {:.2}% is below the minimum of {min_coverage}%",
+            report.percent
+        );
+        return Ok(false);
+    }
+    Ok(true)
+}
+
+/// Export the `NatSpec` of the contracts as solc-compatible userdoc and devdoc JSON
+///
+/// If `out_dir` is provided, one file is written per contract and per kind of documentation. Otherwise, the
+/// documentation of all the contracts is written to the output file if any, or to stdout. Returns the number of
+/// exported contracts.
+pub fn export(config: &Config, out_dir: Option<&Path>) -> Result<usize, Box<dyn Error>> {
+    let cwd = dunce::canonicalize(env::current_dir()?)?;
+    let files = parse_files(config)?;
+    let export = DocsExport::new(&files, &cwd);
+    let count = export.contracts.values().map(BTreeMap::len).sum();
+
+    let to_json = |value: serde_json::Value| {
+        if config.output.compact {
+            serde_json::to_string(&value)
+        } else {
+            serde_json::to_string_pretty(&value)
+        }
+    };
+
+    let Some(out_dir) = out_dir else {
+        let mut output_file: Box<dyn std::io::Write> = match &config.output.out {
+            Some(path) => Box::new(File::create(path).map_err(|err| ErrorKind::IOError {
+                path: path.clone(),
+                err,
+            })?),
+            None => Box::new(std::io::stdout()),
+        };
+        writeln!(
+            &mut output_file,
+            "{}",
+            to_json(serde_json::to_value(&export)?)?
+        )?;
+        return Ok(count);
+    };
+    for (path, contracts) in &export.contracts {
+        let dir = out_dir.join(Path::new(path).file_name().unwrap_or_default());
+        fs::create_dir_all(&dir).map_err(|err| ErrorKind::IOError {
+            path: dir.clone(),
+            err,
+        })?;
+        for (name, docs) in contracts {
+            for (kind, value) in [
+                ("userdoc", serde_json::to_value(&docs.userdoc)?),
+                ("devdoc", serde_json::to_value(&docs.devdoc)?),
+            ] {
+                let path = dir.join(format!("{name}.{kind}.json"));
+                fs::write(&path, to_json(value)? + "\n").map_err(|err| ErrorKind::IOError {
+                    path: path.clone(),
+                    err,
+                })?;
+            }
+        }
+    }
+    Ok(count)
+}
+
+/// Render the `NatSpec` of the contracts as Markdown pages in the output directory
+///
+/// Returns the number of written pages, including the index page.
+pub fn docs(config: &Config, out_dir: impl AsRef<Path>) -> Result<usize, Box<dyn Error>> {
+    let out_dir = out_dir.as_ref();
+    let cwd = dunce::canonicalize(env::current_dir()?)?;
+    let files = parse_files(config)?;
+    let pages = markdown::render(&files, &cwd);
+    fs::create_dir_all(out_dir).map_err(|err| ErrorKind::IOError {
+        path: out_dir.to_path_buf(),
+        err,
+    })?;
+    for page in &pages {
+        let path = out_dir.join(&page.path);
+        fs::write(&path, &page.contents).map_err(|err| ErrorKind::IOError {
+            path: path.clone(),
+            err,
+        })?;
+    }
+    Ok(pages.len())
+}
+
+/// Print a coverage report as a table, with the coverage per file and contract, then per item type
+fn print_coverage(f: &mut impl io::Write, report: &CoverageReport) -> Result<(), io::Error> {
+    let item_types: Vec<_> = report
+        .item_types
+        .iter()
+        .map(|(item_type, coverage)| {
+            let name = item_type
+                .to_possible_value()
+                .map_or_else(|| item_type.to_string(), |v| v.get_name().to_string());
+            (name, *coverage)
+        })
+        .collect();
+    let width = report
+        .files
+        .iter()
+        .map(|file| file.path.len())
+        .chain(
+            report
+                .files
+                .iter()
+                .flat_map(|file| &file.contracts)
+                .map(|c| c.name.len() + 2),
+        )
+        .chain(item_types.iter().map(|(name, _)| name.len()))
+        .max()
+        .unwrap_or_default()
+        .max("Total".len());
+    let row = |f: &mut dyn io::Write, name: &str, coverage: &Coverage| {
+        writeln!(
+            f,
+            "{name:width$}  {:>12}  {:>7.2}%",
+            format!("{}/{}", coverage.documented, coverage.total),
+            coverage.percent()
+        )
+    };
+    writeln!(
+        f,
+        "{:width$}  {:>12}  {:>8}",
+        "File", "Documented", "Coverage"
+    )?;
+    for file in &report.files {
+        row(f, &file.path, &file.coverage)?;
+        for contract in &file.contracts {
+            row(f, &format!("  {}", contract.name), &contract.coverage)?;
+        }
+    }
+    writeln!(f)?;
+    writeln!(
+        f,
+        "{:width$}  {:>12}  {:>8}",
+        "Item type", "Documented", "Coverage"
+    )?;
+    for (name, coverage) in &item_types {
+        row(f, name, coverage)?;
+    }
+    writeln!(f)?;
+    row(f, "Total", &report.total)
+}
+
+/// The definitions found in a file
+type FileDefinitions = (PathBuf, Vec<Definition>);
+
+/// Parse the Solidity files to analyze and return their definitions, sorted by path
+fn parse_files(config: &Config) -> Result<Vec<FileDefinitions>, Box<dyn Error>> {
+    let parser = new_parser(config);
+    let paths = find_paths(config)?;
+    let threads = init_threads(config);
+    let parse = |path: &PathBuf| {
+        let file = File::open(path).map_err(|err| ErrorKind::IOError {
+            path: path.clone(),
+            err,
+        })?;
+        let document = parser.clone().parse_document(file, Some(path), false)?;
+        Ok::<_, lintspec_core::error::Error>((path.clone(), document.definitions))
+    };
+    let mut files: Vec<_> = if threads == 1 {
+        paths.iter().map(parse).collect::<Result<_, _>>()?
+    } else {
+        paths.par_iter().map(parse).collect::<Result<_, _>>()?
+    };
+    files.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+    Ok(files)
+}
+
+/// Find the Solidity files to analyze, returning an error if there are none
+pub(crate) fn find_paths(config: &Config) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    let paths = find_sol_files(
+        &config.lintspec.paths,
+        &config.lintspec.exclude,
+        config.output.sort,
+        config.lintspec.parallel,
+    )?;
+    if paths.is_empty() {
+        return Err(String::from("no Solidity file found, nothing to analyze").into());
+    }
+    Ok(paths)
+}
+
+/// Initialize the global thread pool according to the config and return the number of threads
+pub(crate) fn init_threads(config: &Config) -> usize {
+    let threads = if config.lintspec.parallel == 0 {
+        available_parallelism().map_or(1, NonZero::get)
+    } else {
+        config.lintspec.parallel
+    };
+    if threads > 1 {
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build_global()
+            .ok();
+    }
+    threads
+}
+
+/// Create a new parser according to the enabled features
+#[cfg_attr(not(feature = "slang"), expect(unused_variables))]
+pub(crate) fn new_parser(config: &Config) -> impl Parse + Send + Sync {
+    #[cfg_attr(all(feature = "slang", feature = "slang"), expect(unused_variables))]
+    #[cfg(feature = "solar")]
+    let parser = SolarParser::default();
+
+    #[cfg(feature = "slang")]
+    let parser = SlangParser::builder()
+        .skip_version_detection(config.lintspec.skip_version_detection)
+        .build();
+
+    parser
+}
+
+/// Open the cache of the diagnostics in the current directory, unless it was disabled
+pub(crate) fn open_cache(config: &Config, options: &ValidationOptions) -> Option<Cache> {
+    config
+        .lintspec
+        .cache
+        .then(|| Cache::new(CACHE_DIR, options, VERSION))
+}
+
+/// Lint a list of files, in parallel if the number of threads is greater than 1
+///
+/// If a cache is provided, the diagnostics of the files which didn't change are retrieved from it.
+pub(crate) fn lint_files(
+    parser: &(impl Parse + Send + Sync),
+    paths: &[PathBuf],
+    options: &ValidationOptions,
+    project: &Project,
+    cache: Option<&Cache>,
+    keep_contents: bool,
+    threads: usize,
+) -> Result<Vec<FileDiagnostics>, lintspec_core::error::Error> {
+    let lint_file = |path: &PathBuf| {
+        match cache {
+            Some(cache) => cache.lint(parser.clone(), path, Some(project), keep_contents),
+            None => lint_with_project(parser.clone(), path, options, project, keep_contents),
+        }
+        .transpose()
+    };
+    if threads == 1 {
+        paths.iter().filter_map(lint_file).collect()
+    } else {
+        paths.par_iter().filter_map(lint_file).collect()
+    }
+}
+
+/// Apply the fixes contained in the diagnostics to the corresponding files
+///
+/// If `dry_run` is true, the files are not modified and a unified diff of the changes is printed to stdout instead.
+/// The root path is used to display relative paths in the diff headers.
+/// Returns the paths of the files which were modified (or would be modified, in the case of a dry run).
+pub fn fix_files(
+    diagnostics: &[FileDiagnostics],
+    root_path: impl AsRef<Path>,
+    dry_run: bool,
+) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    let mut fixed_paths = Vec::new();
+    for file_diags in diagnostics {
+        if !file_diags
+            .items
+            .iter()
+            .flat_map(|i| &i.diags)
+            .any(|d| d.fix.is_some())
+        {
+            continue;
+        }
+        let path = &file_diags.path;
+        let source = fs::read_to_string(path).map_err(|err| ErrorKind::IOError {
+            path: path.clone(),
+            err,
+        })?;
+        let Some(new_source) = fix_source(&source, file_diags) else {
+            continue;
+        };
+        if dry_run {
+            print_diff(path, root_path.as_ref(), &source, &new_source);
+        } else {
+            fs::write(path, new_source).map_err(|err| ErrorKind::IOError {
+                path: path.clone(),
+                err,
+            })?;
+        }
+        fixed_paths.push(path.clone());
+    }
+    Ok(fixed_paths)
+}
+
+/// Apply the fixes of a file's diagnostics to its source, returning `None` if the source is unchanged
+fn fix_source(source: &str, file_diags: &FileDiagnostics) -> Option<String> {
+    let new_source = apply_fixes(
+        source,
+        file_diags
+            .items
+            .iter()
+            .flat_map(|i| &i.diags)
+            .filter_map(|d| d.fix.as_ref()),
+    );
+    (new_source != source).then_some(new_source)
+}
+
+/// Print a unified diff between the original and the fixed source to stdout
+fn print_diff(path: &Path, root_path: &Path, source: &str, new_source: &str) {
+    if source == new_source {
+        return;
+    }
+    let name = path
+        .strip_prefix(root_path)
+        .unwrap_or(path)
+        .to_string_lossy();
+    print!(
+        "{}",
+        TextDiff::from_lines(source, new_source)
+            .unified_diff()
+            .header(&format!("a/{name}"), &format!("b/{name}"))
+    );
+}
+
+/// Remove the diagnostics which have a fix, as well as the items and files which don't have diagnostics anymore
+fn retain_unfixable(diagnostics: &mut Vec<FileDiagnostics>) {
+    for file_diags in diagnostics.iter_mut() {
+        for item_diags in &mut file_diags.items {
+            item_diags.diags.retain(|d| d.fix.is_none());
+        }
+        file_diags.items.retain(|i| !i.diags.is_empty());
+    }
+    diagnostics.retain(|f| !f.items.is_empty());
+}
+
+/// Print the baseline entries which don't match any diagnostic anymore
+fn print_fixed_entries(f: &mut impl io::Write, entries: &[BaselineEntry]) -> Result<(), io::Error> {
+    writeln!(
+        f,
+        "{} baseline entries were fixed and can be removed (run `lintspec baseline` to update the baseline):",
+        entries.len()
+    )?;
+    for entry in entries {
+        writeln!(f, "  {entry}")?;
+    }
+    Ok(())
 }
 
 /// Write the default configuration to a `.lintspec.toml` file in the current directory.
@@ -608,22 +1368,37 @@ fn print_report(
     source: Arc<NamedSource<String>>,
     item: ItemDiagnostics,
 ) -> Result<(), io::Error> {
-    let msg = if let Some(parent) = &item.parent {
-        format!("{} {}.{}", item.item_type, parent, item.name)
-    } else {
-        format!("{} {}", item.item_type, item.name)
+    // some items (like stray comments) have no name
+    let msg = match (&item.parent, item.name) {
+        (Some(parent), "") => format!("{} {}", item.item_type, parent),
+        (Some(parent), name) => format!("{} {}.{}", item.item_type, parent, name),
+        (None, "") => item.item_type.to_string(),
+        (None, name) => format!("{} {}", item.item_type, name),
+    };
+    let severity = match item.severity() {
+        Severity::Error => miette::Severity::Error,
+        Severity::Warning => miette::Severity::Warning,
+        Severity::Info => miette::Severity::Advice,
     };
     let labels: Vec<_> = item
         .diags
         .into_iter()
         .map(|d| {
+            let message = if d.severity.is_error() {
+                d.message
+            } else {
+                format!("{}: {}", d.severity, d.message)
+            };
             LabeledSpan::new(
-                Some(d.message),
+                Some(message),
                 d.span.start.utf8,
                 d.span.end.utf8 - d.span.start.utf8,
             )
         })
         .collect();
-    let report: miette::Report = MietteDiagnostic::new(msg).with_labels(labels).into();
+    let report: miette::Report = MietteDiagnostic::new(msg)
+        .with_severity(severity)
+        .with_labels(labels)
+        .into();
     write!(f, "{:?}", report.with_source_code(source))
 }
@@ -1,3 +1,5 @@
 #![cfg_attr(docsrs, feature(doc_cfg))]
 #![doc = include_str!(concat!("../", std::env!("CARGO_PKG_README")))]
 pub mod cli;
+pub mod lsp;
+pub mod watch;
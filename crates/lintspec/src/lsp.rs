@@ -0,0 +1,618 @@
+//! Language Server Protocol implementation
+//!
+//! The server communicates with the editor over stdin/stdout. It lints the documents when they are opened or modified
+//! and publishes the diagnostics, using the in-memory contents of the editor buffer. Code actions are offered for the
+//! diagnostics which have a fix.
+use std::{collections::HashMap, error::Error, path::PathBuf};
+
+use lsp_server::{Connection, ErrorCode, Message, Notification, Request, Response};
+use lsp_types::{
+    CodeAction, CodeActionKind, CodeActionOrCommand, CodeActionParams,
+    CodeActionProviderCapability, DiagnosticSeverity, DidChangeTextDocumentParams,
+    DidCloseTextDocumentParams, DidOpenTextDocumentParams, InitializeParams, InitializeResult,
+    NumberOrString, Position, PositionEncodingKind, PublishDiagnosticsParams, Range,
+    ServerCapabilities, ServerInfo, TextDocumentSyncCapability, TextDocumentSyncKind, TextEdit,
+    Uri, WorkspaceEdit,
+    notification::{
+        DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument, Notification as _,
+        PublishDiagnostics,
+    },
+    request::{CodeActionRequest, Request as _},
+};
+
+use lintspec_core::{
+    config::{Config, Severity},
+    lint::{Diagnostic, FileDiagnostics, ValidationOptions, lint_source_with_project},
+    project::Project,
+    textindex::{TextIndex, TextRange},
+};
+
+use crate::cli::new_parser;
+
+/// The encoding used for the character offsets in positions
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Encoding {
+    Utf8,
+    Utf16,
+    Utf32,
+}
+
+impl Encoding {
+    /// Select the first encoding in the client's list of preferred encodings, or UTF-16 which is mandatory
+    fn negotiate(params: &InitializeParams) -> Self {
+        params
+            .capabilities
+            .general
+            .as_ref()
+            .and_then(|g| g.position_encodings.as_ref())
+            .and_then(|encodings| {
+                encodings.iter().find_map(|e| match e.as_str() {
+                    "utf-8" => Some(Encoding::Utf8),
+                    "utf-16" => Some(Encoding::Utf16),
+                    "utf-32" => Some(Encoding::Utf32),
+                    _ => None,
+                })
+            })
+            .unwrap_or(Encoding::Utf16)
+    }
+
+    /// The corresponding LSP encoding kind
+    fn kind(self) -> PositionEncodingKind {
+        match self {
+            Encoding::Utf8 => PositionEncodingKind::UTF8,
+            Encoding::Utf16 => PositionEncodingKind::UTF16,
+            Encoding::Utf32 => PositionEncodingKind::UTF32,
+        }
+    }
+}
+
+/// The state of the language server
+struct Server<'a> {
+    /// The configuration, used to create the parsers
+    config: &'a Config,
+
+    /// The validation options derived from the configuration
+    options: ValidationOptions,
+
+    /// The position encoding negotiated with the client
+    encoding: Encoding,
+
+    /// The diagnostics from the last lint of each open document, used to generate the code actions
+    diagnostics: HashMap<Uri, FileDiagnostics>,
+
+    /// The projects of the open documents by directory, which cache the parsed imports between edits
+    projects: HashMap<PathBuf, Project>,
+}
+
+impl Server<'_> {
+    /// Convert a [`TextIndex`] to a position in the negotiated encoding
+    fn position(&self, index: &TextIndex) -> Position {
+        let character = match self.encoding {
+            Encoding::Utf8 => index.col_utf8,
+            Encoding::Utf16 => index.col_utf16,
+            Encoding::Utf32 => index.col_utf32,
+        };
+        Position::new(index.line, character)
+    }
+
+    /// Convert a [`TextRange`] to a range in the negotiated encoding
+    fn range(&self, span: &TextRange) -> Range {
+        Range::new(self.position(&span.start), self.position(&span.end))
+    }
+
+    /// Convert a lintspec diagnostic to a LSP diagnostic
+    fn diagnostic(&self, diag: &Diagnostic) -> lsp_types::Diagnostic {
+        lsp_types::Diagnostic {
+            range: self.range(&diag.span),
+            severity: Some(match diag.severity {
+                Severity::Error => DiagnosticSeverity::ERROR,
+                Severity::Warning => DiagnosticSeverity::WARNING,
+                Severity::Info => DiagnosticSeverity::INFORMATION,
+            }),
+            code: Some(NumberOrString::String(diag.rule.to_string())),
+            source: Some("lintspec".to_string()),
+            message: diag.message.clone(),
+            ..Default::default()
+        }
+    }
+
+    /// Lint the contents of a document and return the diagnostics to publish
+    fn lint(&mut self, uri: Uri, text: &str) -> PublishDiagnosticsParams {
+        let path = uri_to_path(&uri);
+        let (diagnostics, lsp_diags) = if text.is_empty() {
+            (None, Vec::new())
+        } else {
+            // the document might be imported by other documents, its cached contents are outdated
+            for project in self.projects.values() {
+                project.invalidate(&path);
+            }
+            let dir = path.parent().unwrap_or(&path).to_path_buf();
+            let project = self
+                .projects
+                .entry(dir)
+                .or_insert_with_key(|dir| Project::discover(dir));
+            // the parser caches the sources by path, so a new instance is needed each time
+            match lint_source_with_project(
+                new_parser(self.config),
+                &path,
+                text,
+                &self.options,
+                project,
+                false,
+            ) {
+                Ok(diagnostics) => {
+                    let lsp_diags = diagnostics
+                        .iter()
+                        .flat_map(|f| &f.items)
+                        .flat_map(|i| &i.diags)
+                        .map(|d| self.diagnostic(d))
+                        .collect();
+                    (diagnostics, lsp_diags)
+                }
+                Err(err) => (
+                    None,
+                    vec![lsp_types::Diagnostic {
+                        severity: Some(DiagnosticSeverity::ERROR),
+                        source: Some("lintspec".to_string()),
+                        message: err.to_string(),
+                        ..Default::default()
+                    }],
+                ),
+            }
+        };
+        match diagnostics {
+            Some(diagnostics) => self.diagnostics.insert(uri.clone(), diagnostics),
+            None => self.diagnostics.remove(&uri),
+        };
+        PublishDiagnosticsParams::new(uri, lsp_diags, None)
+    }
+
+    /// Handle a notification from the client, returning the diagnostics to publish if any
+    fn handle_notification(
+        &mut self,
+        notification: Notification,
+    ) -> Option<PublishDiagnosticsParams> {
+        match notification.method.as_str() {
+            DidOpenTextDocument::METHOD => {
+                let params: DidOpenTextDocumentParams =
+                    serde_json::from_value(notification.params).ok()?;
+                Some(self.lint(params.text_document.uri, &params.text_document.text))
+            }
+            DidChangeTextDocument::METHOD => {
+                let params: DidChangeTextDocumentParams =
+                    serde_json::from_value(notification.params).ok()?;
+                // with full sync, the last change contains the whole document
+                let change = params.content_changes.last()?;
+                Some(self.lint(params.text_document.uri, &change.text))
+            }
+            DidCloseTextDocument::METHOD => {
+                let params: DidCloseTextDocumentParams =
+                    serde_json::from_value(notification.params).ok()?;
+                self.diagnostics.remove(&params.text_document.uri);
+                Some(PublishDiagnosticsParams::new(
+                    params.text_document.uri,
+                    Vec::new(),
+                    None,
+                ))
+            }
+            _ => None,
+        }
+    }
+
+    /// Handle a request from the client
+    fn handle_request(&self, request: Request) -> Response {
+        match request.method.as_str() {
+            CodeActionRequest::METHOD => {
+                match serde_json::from_value::<CodeActionParams>(request.params) {
+                    Ok(params) => Response::new_ok(request.id, self.code_actions(&params)),
+                    Err(err) => Response::new_err(
+                        request.id,
+                        ErrorCode::InvalidParams as i32,
+                        err.to_string(),
+                    ),
+                }
+            }
+            _ => Response::new_err(
+                request.id,
+                ErrorCode::MethodNotFound as i32,
+                format!("unsupported request: {}", request.method),
+            ),
+        }
+    }
+
+    /// Generate the code actions for the fixable diagnostics overlapping the requested range
+    fn code_actions(&self, params: &CodeActionParams) -> Vec<CodeActionOrCommand> {
+        let uri = &params.text_document.uri;
+        let Some(diagnostics) = self.diagnostics.get(uri) else {
+            return Vec::new();
+        };
+        diagnostics
+            .items
+            .iter()
+            .flat_map(|i| &i.diags)
+            .filter_map(|diag| {
+                let fix = diag.fix.as_ref()?;
+                let range = self.range(&diag.span);
+                if range.end < params.range.start || range.start > params.range.end {
+                    return None;
+                }
+                let edits = fix
+                    .edits
+                    .iter()
+                    .map(|e| TextEdit::new(self.range(&e.span), e.text.clone()))
+                    .collect();
+                Some(CodeActionOrCommand::CodeAction(CodeAction {
+                    title: fix.description.clone(),
+                    kind: Some(CodeActionKind::QUICKFIX),
+                    diagnostics: Some(vec![self.diagnostic(diag)]),
+                    edit: Some(WorkspaceEdit::new(HashMap::from([(uri.clone(), edits)]))),
+                    is_preferred: Some(true),
+                    ..Default::default()
+                }))
+            })
+            .collect()
+    }
+}
+
+/// Convert a `file://` URI to a path
+///
+/// Other schemes (e.g. `untitled:`) are returned as-is.
+fn uri_to_path(uri: &Uri) -> PathBuf {
+    if !uri
+        .scheme()
+        .is_some_and(|s| s.as_str().eq_ignore_ascii_case("file"))
+    {
+        return PathBuf::from(uri.as_str());
+    }
+    let path = uri
+        .path()
+        .as_estr()
+        .decode()
+        .into_string()
+        .map_or_else(|_| uri.path().as_str().to_string(), |p| p.to_string());
+    // on Windows, the path looks like `/C:/foo/bar.sol`
+    match path.as_bytes() {
+        [b'/', drive, b':', ..] if drive.is_ascii_alphabetic() => PathBuf::from(&path[1..]),
+        _ => PathBuf::from(path),
+    }
+}
+
+/// Run the language server over stdin/stdout until the client requests a shutdown
+pub fn run(config: &Config) -> Result<(), Box<dyn Error>> {
+    let (connection, io_threads) = Connection::stdio();
+    serve(&connection, config)?;
+    drop(connection);
+    io_threads.join()?;
+    Ok(())
+}
+
+/// Handle the messages from a connection until the client requests a shutdown
+fn serve(connection: &Connection, config: &Config) -> Result<(), Box<dyn Error>> {
+    let (id, params) = connection.initialize_start()?;
+    let params: InitializeParams = serde_json::from_value(params)?;
+    let encoding = Encoding::negotiate(&params);
+    let result = InitializeResult {
+        capabilities: ServerCapabilities {
+            position_encoding: Some(encoding.kind()),
+            text_document_sync: Some(TextDocumentSyncCapability::Kind(TextDocumentSyncKind::FULL)),
+            code_action_provider: Some(CodeActionProviderCapability::Simple(true)),
+            ..Default::default()
+        },
+        server_info: Some(ServerInfo {
+            name: env!("CARGO_PKG_NAME").to_string(),
+            version: Some(env!("CARGO_PKG_VERSION").to_string()),
+        }),
+    };
+    connection.initialize_finish(id, serde_json::to_value(result)?)?;
+
+    let mut server = Server {
+        config,
+        options: config.into(),
+        encoding,
+        diagnostics: HashMap::new(),
+        projects: HashMap::new(),
+    };
+    for message in &connection.receiver {
+        match message {
+            Message::Request(request) => {
+                if connection.handle_shutdown(&request)? {
+                    return Ok(());
+                }
+                let response = server.handle_request(request);
+                connection.sender.send(Message::Response(response))?;
+            }
+            Message::Notification(notification) => {
+                if let Some(params) = server.handle_notification(notification) {
+                    connection
+                        .sender
+                        .send(Message::Notification(Notification::new(
+                            PublishDiagnostics::METHOD.to_string(),
+                            params,
+                        )))?;
+                }
+            }
+            Message::Response(_) => {}
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use lintspec_core::textindex::compute_indices;
+    use lsp_server::RequestId;
+    use lsp_types::{
+        ClientCapabilities, GeneralClientCapabilities, TextDocumentIdentifier, TextDocumentItem,
+        notification::{DidOpenTextDocument, Exit, Initialized},
+        request::{Initialize, Shutdown},
+    };
+
+    use super::*;
+
+    const SOURCE: &str = "contract Test {
+    /// @notice A function
+    function foo(uint256 a) internal {}
+
+    /// @notice Another function
+    function bar(uint256 b) internal {}
+}
+";
+
+    fn uri(s: &str) -> Uri {
+        s.parse().unwrap()
+    }
+
+    fn server(config: &Config, encoding: Encoding) -> Server<'_> {
+        Server {
+            config,
+            options: config.into(),
+            encoding,
+            diagnostics: HashMap::new(),
+            projects: HashMap::new(),
+        }
+    }
+
+    fn initialize_params(encodings: Option<Vec<PositionEncodingKind>>) -> InitializeParams {
+        InitializeParams {
+            capabilities: ClientCapabilities {
+                general: Some(GeneralClientCapabilities {
+                    position_encodings: encodings,
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    fn code_action_params(uri: Uri, range: Range) -> CodeActionParams {
+        CodeActionParams {
+            text_document: TextDocumentIdentifier::new(uri),
+            range,
+            context: lsp_types::CodeActionContext::default(),
+            work_done_progress_params: lsp_types::WorkDoneProgressParams::default(),
+            partial_result_params: lsp_types::PartialResultParams::default(),
+        }
+    }
+
+    #[test]
+    fn test_uri_to_path() {
+        assert_eq!(
+            uri_to_path(&uri("file:///home/user/src/Foo.sol")),
+            PathBuf::from("/home/user/src/Foo.sol")
+        );
+        assert_eq!(
+            uri_to_path(&uri("FILE:///home/user/My%20Project/Foo.sol")),
+            PathBuf::from("/home/user/My Project/Foo.sol")
+        );
+        // the leading slash before the drive letter is removed on Windows
+        assert_eq!(
+            uri_to_path(&uri("file:///C:/Users/user/Foo.sol")),
+            PathBuf::from("C:/Users/user/Foo.sol")
+        );
+        assert_eq!(
+            uri_to_path(&uri("file:///c%3A/Users/user/Foo.sol")),
+            PathBuf::from("c:/Users/user/Foo.sol")
+        );
+        // not a drive letter
+        assert_eq!(
+            uri_to_path(&uri("file:///1:/foo.sol")),
+            PathBuf::from("/1:/foo.sol")
+        );
+        // other schemes are kept as-is
+        assert_eq!(
+            uri_to_path(&uri("untitled:Untitled-1")),
+            PathBuf::from("untitled:Untitled-1")
+        );
+    }
+
+    #[test]
+    fn test_negotiate_encoding() {
+        assert_eq!(
+            Encoding::negotiate(&InitializeParams::default()),
+            Encoding::Utf16
+        );
+        assert_eq!(
+            Encoding::negotiate(&initialize_params(None)),
+            Encoding::Utf16
+        );
+        assert_eq!(
+            Encoding::negotiate(&initialize_params(Some(vec![
+                PositionEncodingKind::UTF32,
+                PositionEncodingKind::UTF8
+            ]))),
+            Encoding::Utf32
+        );
+        // unknown encodings are skipped
+        assert_eq!(
+            Encoding::negotiate(&initialize_params(Some(vec![
+                PositionEncodingKind::new("utf-7"),
+                PositionEncodingKind::UTF8
+            ]))),
+            Encoding::Utf8
+        );
+        assert_eq!(
+            Encoding::negotiate(&initialize_params(Some(vec![PositionEncodingKind::new(
+                "utf-7"
+            )]))),
+            Encoding::Utf16
+        );
+    }
+
+    #[test]
+    fn test_position() {
+        // `😀` is 4 bytes in utf-8, 2 code units in utf-16 and 1 code point in utf-32
+        let source = "a\nb😀c";
+        let index = compute_indices(source, &[source.len() - 1])[0];
+        let config = Config::default();
+        assert_eq!(
+            server(&config, Encoding::Utf8).position(&index),
+            Position::new(1, 5)
+        );
+        assert_eq!(
+            server(&config, Encoding::Utf16).position(&index),
+            Position::new(1, 3)
+        );
+        assert_eq!(
+            server(&config, Encoding::Utf32).position(&index),
+            Position::new(1, 2)
+        );
+    }
+
+    #[test]
+    fn test_code_actions() {
+        let config = Config::default();
+        let mut server = server(&config, Encoding::Utf16);
+        let test_uri = uri("file:///project/src/Test.sol");
+        let params = server.lint(test_uri.clone(), SOURCE);
+        assert_eq!(params.diagnostics.len(), 2);
+
+        let titles = |range| {
+            server
+                .code_actions(&code_action_params(test_uri.clone(), range))
+                .into_iter()
+                .map(|a| match a {
+                    CodeActionOrCommand::CodeAction(action) => action.title,
+                    CodeActionOrCommand::Command(command) => command.title,
+                })
+                .collect::<Vec<_>>()
+        };
+        // the whole document
+        assert_eq!(
+            titles(Range::new(Position::new(0, 0), Position::new(7, 0))),
+            vec!["add `@param a`", "add `@param b`"]
+        );
+        // the line of the first function only
+        assert_eq!(
+            titles(Range::new(Position::new(2, 0), Position::new(3, 0))),
+            vec!["add `@param a`"]
+        );
+        // no fixable diagnostic in the range
+        assert_eq!(
+            titles(Range::new(Position::new(0, 0), Position::new(0, 5))).len(),
+            0
+        );
+        // unknown document
+        let params = code_action_params(uri("file:///project/src/Other.sol"), Range::default());
+        assert_eq!(server.code_actions(&params).len(), 0);
+    }
+
+    #[test]
+    fn test_serve() {
+        let (server_conn, client) = Connection::memory();
+        let config = Config::default();
+        std::thread::scope(|s| {
+            let handle = s.spawn(|| serve(&server_conn, &config).map_err(|e| e.to_string()));
+            // dropped if an assertion fails, which stops the server instead of blocking forever
+            let client = client;
+
+            let params = initialize_params(Some(vec![PositionEncodingKind::UTF8]));
+            client
+                .sender
+                .send(Message::Request(Request::new(
+                    RequestId::from(1),
+                    Initialize::METHOD.to_string(),
+                    params,
+                )))
+                .unwrap();
+            let Message::Response(response) = client.receiver.recv().unwrap() else {
+                panic!("expected a response");
+            };
+            let result: InitializeResult =
+                serde_json::from_value(response.result.unwrap()).unwrap();
+            assert_eq!(
+                result.capabilities.position_encoding,
+                Some(PositionEncodingKind::UTF8)
+            );
+            client
+                .sender
+                .send(Message::Notification(Notification::new(
+                    Initialized::METHOD.to_string(),
+                    lsp_types::InitializedParams {},
+                )))
+                .unwrap();
+
+            let uri = uri("file:///project/src/Test.sol");
+            client
+                .sender
+                .send(Message::Notification(Notification::new(
+                    DidOpenTextDocument::METHOD.to_string(),
+                    DidOpenTextDocumentParams {
+                        text_document: TextDocumentItem::new(
+                            uri.clone(),
+                            "solidity".to_string(),
+                            1,
+                            SOURCE.to_string(),
+                        ),
+                    },
+                )))
+                .unwrap();
+            let Message::Notification(notification) = client.receiver.recv().unwrap() else {
+                panic!("expected a notification");
+            };
+            assert_eq!(notification.method, PublishDiagnostics::METHOD);
+            let params: PublishDiagnosticsParams =
+                serde_json::from_value(notification.params).unwrap();
+            assert_eq!(params.uri, uri);
+            let codes: Vec<_> = params.diagnostics.iter().map(|d| d.code.clone()).collect();
+            assert!(codes.contains(&Some(NumberOrString::String("missing-param".to_string()))));
+
+            client
+                .sender
+                .send(Message::Request(Request::new(
+                    RequestId::from(2),
+                    CodeActionRequest::METHOD.to_string(),
+                    code_action_params(uri, Range::new(Position::new(2, 0), Position::new(3, 0))),
+                )))
+                .unwrap();
+            let Message::Response(response) = client.receiver.recv().unwrap() else {
+                panic!("expected a response");
+            };
+            assert_eq!(response.id, RequestId::from(2));
+            let actions: Vec<CodeActionOrCommand> =
+                serde_json::from_value(response.result.unwrap()).unwrap();
+            assert_eq!(actions.len(), 1);
+
+            client
+                .sender
+                .send(Message::Request(Request::new(
+                    RequestId::from(3),
+                    Shutdown::METHOD.to_string(),
+                    (),
+                )))
+                .unwrap();
+            let Message::Response(response) = client.receiver.recv().unwrap() else {
+                panic!("expected a response");
+            };
+            assert_eq!(response.id, RequestId::from(3));
+            client
+                .sender
+                .send(Message::Notification(Notification::new(
+                    Exit::METHOD.to_string(),
+                    (),
+                )))
+                .unwrap();
+            assert_eq!(handle.join().unwrap(), Ok(()));
+        });
+    }
+}
@@ -3,8 +3,19 @@ use std::env;
 use clap::{CommandFactory as _, Parser as _};
 use clap_complete::{generate, generate_to};
 
-use lintspec::cli::{Args, Commands, RunResult, read_config, run, write_default_config};
+use lintspec::{
+    cli::{
+        Args, CacheCommand, Commands, RunResult, coverage, docs, export, read_config, run,
+        write_baseline, write_default_config,
+    },
+    lsp, watch,
+};
+use lintspec_core::{
+    cache::{CACHE_DIR, Cache},
+    lint::Rule,
+};
 
+#[expect(clippy::too_many_lines)]
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     #[cfg(not(any(feature = "slang", feature = "solar")))]
     compile_error!("no parser enabled, please enable feature `slang` or `solar`.");
@@ -12,7 +23,19 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     dotenvy::dotenv().ok(); // load .env file if present
 
     // parse config from CLI args, environment variables and the `.lintspec.toml` file.
-    let args = Args::parse();
+    let mut args = Args::parse();
+    // the subcommands which analyze files accept additional paths
+    if let Some(
+        Commands::Baseline { paths, .. }
+        | Commands::Coverage { paths, .. }
+        | Commands::Export { paths, .. }
+        | Commands::Docs { paths, .. },
+    ) = &args.command
+    {
+        args.paths.extend(paths.iter().cloned());
+    }
+    let command = args.command.clone();
+    let start_watch = args.watch;
     match &args.command {
         Some(Commands::Init) => {
             let path = write_default_config()?;
@@ -20,6 +43,27 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             println!("Exiting");
             return Ok(());
         }
+        Some(Commands::Rules) => {
+            let width = Rule::ALL
+                .iter()
+                .map(|r| r.to_string().len())
+                .max()
+                .unwrap_or_default();
+            for rule in Rule::ALL {
+                println!("{:width$}  {}", rule.to_string(), rule.description());
+            }
+            return Ok(());
+        }
+        Some(Commands::Cache {
+            command: CacheCommand::Clean,
+        }) => {
+            if Cache::clean(CACHE_DIR)? {
+                println!("The cache was removed");
+            } else {
+                println!("There is no cache to remove");
+            }
+            return Ok(());
+        }
         Some(Commands::Completions { shell, out }) => {
             let mut cli = Args::command();
             if let Some(out) = out {
@@ -34,13 +78,59 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             }
             return Ok(());
         }
-        None => {}
+        Some(
+            Commands::Baseline { .. }
+            | Commands::Coverage { .. }
+            | Commands::Export { .. }
+            | Commands::Docs { .. }
+            | Commands::Lsp,
+        )
+        | None => {}
     }
 
     let config = read_config(args)?;
+    match command {
+        Some(Commands::Lsp) => return lsp::run(&config),
+        Some(Commands::Baseline { write, .. }) => {
+            let count = write_baseline(&config, &write)?;
+            println!("{count} diagnostics were recorded in {}", write.display());
+            return Ok(());
+        }
+        Some(Commands::Coverage {
+            format,
+            min_coverage,
+            ..
+        }) => {
+            if !coverage(&config, format, min_coverage)? {
+                std::process::exit(1);
+            }
+            return Ok(());
+        }
+        Some(Commands::Export { out_dir, .. }) => {
+            let count = export(&config, out_dir.as_deref())?;
+            if let Some(out_dir) = out_dir {
+                println!(
+                    "The documentation of {count} contracts was written to {}",
+                    out_dir.display()
+                );
+            }
+            return Ok(());
+        }
+        Some(Commands::Docs { out, .. }) => {
+            let count = docs(&config, &out)?;
+            println!("{count} pages were written to {}", out.display());
+            return Ok(());
+        }
+        _ => {}
+    }
+    if start_watch {
+        return watch::run(&config);
+    }
 
     match (run(&config)?, config.output.exit_zero) {
-        (RunResult::NoDiagnostics, _) | (RunResult::SomeDiagnostics, true) => Ok(()),
-        (RunResult::SomeDiagnostics, false) => std::process::exit(1),
+        (RunResult::NoDiagnostics | RunResult::SomeWarnings, _) | (RunResult::SomeErrors, true) => {
+            Ok(())
+        }
+        (RunResult::SomeErrors, false) => std::process::exit(1),
     }
 }
@@ -0,0 +1,443 @@
+//! Watch mode for continuous linting
+//!
+//! The files are linted once, then the analyzed paths are monitored for changes. When a Solidity file is modified, this
+//! file and the analyzed files which import it (directly or transitively) are linted again and their updated
+//! diagnostics are printed. When files are added or removed (or an ignore file changes), the list of files is
+//! refreshed with the same rules as [`find_sol_files`].
+//!
+//! The same [`Project`] is used during the whole session, so that the imported files are only parsed again when they
+//! change.
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    env,
+    error::Error,
+    io::{self, Write as _},
+    path::{Path, PathBuf},
+    sync::mpsc,
+    time::Duration,
+};
+
+use notify::{
+    Event, EventKind, RecursiveMode, Watcher as _,
+    event::{ModifyKind, RenameMode},
+};
+
+use lintspec_core::{
+    baseline::Baseline,
+    changes::ChangedLines,
+    config::{Config, OutputFormat},
+    files::find_sol_files,
+    lint::ValidationOptions,
+    parser::Parse,
+    project::Project,
+};
+
+use crate::cli::{find_paths, init_threads, lint_files, new_parser, print_reports};
+
+/// The delay during which successive file system events are grouped together (e.g. when saving several files)
+const DEBOUNCE: Duration = Duration::from_millis(100);
+
+/// The names of the files which affect the list of files to analyze
+const IGNORE_FILES: [&str; 3] = [".gitignore", ".ignore", ".nsignore"];
+
+/// The names of the files which affect the resolution of the imports
+const PROJECT_FILES: [&str; 2] = ["foundry.toml", "remappings.txt"];
+
+/// The changes found in a batch of file system events
+#[derive(Debug, Default, PartialEq, Eq)]
+struct Changes {
+    /// The paths which were created, modified or removed
+    paths: BTreeSet<PathBuf>,
+
+    /// Whether the list of files to analyze must be refreshed
+    rescan: bool,
+
+    /// Whether the project settings must be loaded again
+    reload: bool,
+}
+
+impl Changes {
+    /// Classify a batch of file system events
+    ///
+    /// The errors are printed to stderr, and the events which don't modify the files (e.g. access) are ignored.
+    fn from_events(events: impl IntoIterator<Item = notify::Result<Event>>) -> Self {
+        let mut res = Self::default();
+        for event in events {
+            let event = match event {
+                Ok(event) => event,
+                Err(err) => {
+                    eprintln!("Error: {err}");
+                    continue;
+                }
+            };
+            match event.kind {
+                EventKind::Create(_)
+                | EventKind::Remove(_)
+                | EventKind::Modify(ModifyKind::Name(
+                    RenameMode::Any | RenameMode::From | RenameMode::To | RenameMode::Both,
+                )) => res.rescan = true,
+                EventKind::Modify(ModifyKind::Data(_) | ModifyKind::Any) => {}
+                _ => continue,
+            }
+            for path in event.paths {
+                if let Some(name) = path.file_name() {
+                    res.rescan |= IGNORE_FILES.iter().any(|f| name == *f);
+                    res.reload |= PROJECT_FILES.iter().any(|f| name == *f);
+                }
+                res.paths.insert(path);
+            }
+        }
+        res
+    }
+}
+
+/// Lint the files, then lint them again whenever they change, until the process is interrupted
+///
+/// The errors which occur while linting (e.g. a file which can't be parsed while it's being edited) are printed to
+/// stderr without stopping the watch.
+pub fn run(config: &Config) -> Result<(), Box<dyn Error>> {
+    if config.lintspec.stdin {
+        return Err(String::from("the watch mode cannot be used with stdin").into());
+    }
+    if config.lintspec.fix {
+        return Err(String::from("the watch mode cannot be used with --fix").into());
+    }
+    if config.output.output_format() == OutputFormat::Sarif {
+        return Err(String::from("the watch mode doesn't support the SARIF format").into());
+    }
+    let cwd = dunce::canonicalize(env::current_dir()?)?;
+    let threads = init_threads(config);
+    let mut paths: HashSet<PathBuf> = find_paths(config)?.into_iter().collect();
+
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(tx)?;
+    for path in &config.lintspec.paths {
+        let path = dunce::canonicalize(path)?;
+        // editors often replace the file when saving, so the parent folder is watched instead
+        match path.parent() {
+            Some(parent) if path.is_file() => watcher.watch(parent, RecursiveMode::NonRecursive)?,
+            _ => watcher.watch(&path, RecursiveMode::Recursive)?,
+        }
+    }
+
+    let mut project = Project::discover(&cwd);
+    let mut sorted: Vec<_> = paths.iter().cloned().collect();
+    sorted.sort_unstable();
+    lint_and_print(config, &cwd, &sorted, &project, threads);
+    eprintln!("Watching for changes...");
+
+    while let Ok(event) = rx.recv() {
+        let mut events = vec![event];
+        while let Ok(event) = rx.recv_timeout(DEBOUNCE) {
+            events.push(event);
+        }
+        let mut changes = Changes::from_events(events);
+        if changes.rescan {
+            let new_paths: HashSet<PathBuf> = match find_sol_files(
+                &config.lintspec.paths,
+                &config.lintspec.exclude,
+                false,
+                config.lintspec.parallel,
+            ) {
+                Ok(new_paths) => new_paths.into_iter().collect(),
+                Err(err) => {
+                    eprintln!("Error: {err}");
+                    continue;
+                }
+            };
+            for removed in update_paths(&mut paths, new_paths, &mut changes.paths) {
+                println!("{} was removed", relative(&removed, &cwd));
+            }
+        }
+        if changes.reload {
+            project = Project::discover(&cwd);
+        } else {
+            for path in &changes.paths {
+                project.invalidate(path);
+            }
+        }
+        let mut to_lint = importers(&project, &new_parser(config), &paths, &changes.paths);
+        to_lint.extend(changes.paths.into_iter().filter(|p| paths.contains(p)));
+        if !to_lint.is_empty() {
+            let to_lint: Vec<_> = to_lint.into_iter().collect();
+            lint_and_print(config, &cwd, &to_lint, &project, threads);
+        }
+    }
+    Ok(())
+}
+
+/// Replace the list of files to analyze with the result of a new scan
+///
+/// The new files are added to the changed paths. Returns the files which were removed, sorted.
+fn update_paths(
+    paths: &mut HashSet<PathBuf>,
+    new_paths: HashSet<PathBuf>,
+    changed: &mut BTreeSet<PathBuf>,
+) -> Vec<PathBuf> {
+    let mut removed: Vec<_> = paths.difference(&new_paths).cloned().collect();
+    removed.sort_unstable();
+    changed.extend(new_paths.difference(paths).cloned());
+    *paths = new_paths;
+    removed
+}
+
+/// Find the files to analyze which import one of the changed files, directly or transitively
+///
+/// The imports of the files are parsed with the project, which caches them until they are invalidated (so all the files
+/// are only parsed during the first call). The changed files themselves are not part of the result, unless they import
+/// another changed file.
+fn importers(
+    project: &Project,
+    parser: &impl Parse,
+    paths: &HashSet<PathBuf>,
+    changed: &BTreeSet<PathBuf>,
+) -> BTreeSet<PathBuf> {
+    let mut res = BTreeSet::new();
+    if changed.is_empty() {
+        return res;
+    }
+    // the files which import each file, among the files which are reachable from the analyzed files
+    let mut imported_by: HashMap<PathBuf, Vec<PathBuf>> = HashMap::new();
+    let mut visited = HashSet::new();
+    let mut stack: Vec<_> = paths.iter().cloned().collect();
+    while let Some(path) = stack.pop() {
+        if !visited.insert(path.clone()) {
+            continue;
+        }
+        let Some(unit) = project.unit(parser.clone(), &path) else {
+            continue;
+        };
+        for import in &unit.imports {
+            imported_by
+                .entry(import.clone())
+                .or_default()
+                .push(path.clone());
+            stack.push(import.clone());
+        }
+    }
+    let mut stack: Vec<_> = changed.iter().collect();
+    let mut visited = HashSet::new();
+    while let Some(path) = stack.pop() {
+        if !visited.insert(path) {
+            continue;
+        }
+        for importer in imported_by.get(path).into_iter().flatten() {
+            if paths.contains(importer) {
+                res.insert(importer.clone());
+            }
+            stack.push(importer);
+        }
+    }
+    res
+}
+
+/// Lint a list of files and print their diagnostics, or print the error if linting failed
+fn lint_and_print(
+    config: &Config,
+    cwd: &Path,
+    paths: &[PathBuf],
+    project: &Project,
+    threads: usize,
+) {
+    if let Err(err) = try_lint_and_print(config, cwd, paths, project, threads) {
+        eprintln!("Error: {err}");
+    }
+}
+
+/// Lint a list of files and print their diagnostics
+///
+/// With the text format, the files without diagnostics are listed so that the fixed problems are visible. With the
+/// JSON format, the diagnostics of each batch of files are printed on a single line.
+fn try_lint_and_print(
+    config: &Config,
+    cwd: &Path,
+    paths: &[PathBuf],
+    project: &Project,
+    threads: usize,
+) -> Result<(), Box<dyn Error>> {
+    let options: ValidationOptions = config.into();
+    let format = config.output.output_format();
+    let keep_contents = format == OutputFormat::Text && !config.output.compact;
+    // the parser caches the sources by path, so a new one is needed to read the modified files
+    let parser = new_parser(config);
+    let mut diagnostics = lint_files(
+        &parser,
+        paths,
+        &options,
+        project,
+        None,
+        keep_contents,
+        threads,
+    )?;
+    if let Some(path) = &config.lintspec.baseline {
+        Baseline::read(path)?.filter(&mut diagnostics, paths, cwd);
+    }
+    if let Some(git_ref) = &config.lintspec.changed_since {
+        ChangedLines::from_git(git_ref, cwd)?.filter(&mut diagnostics);
+    }
+    diagnostics.sort_unstable_by(|a, b| a.path.cmp(&b.path));
+
+    let mut stdout = io::stdout().lock();
+    if format == OutputFormat::Json {
+        writeln!(stdout, "{}", serde_json::to_string(&diagnostics)?)?;
+        return Ok(());
+    }
+    let mut contents = parser.get_sources()?;
+    for path in paths {
+        if !diagnostics.iter().any(|d| &d.path == path) {
+            writeln!(stdout, "No issue found in {}", relative(path, cwd))?;
+        }
+    }
+    for file_diags in diagnostics {
+        let source = contents.remove(&file_diags.document_id).unwrap_or_default();
+        print_reports(&mut stdout, cwd, file_diags, source, config.output.compact)?;
+    }
+    Ok(())
+}
+
+/// Display a path relative to the current directory if possible
+fn relative(path: &Path, cwd: &Path) -> String {
+    path.strip_prefix(cwd)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use notify::event::{AccessKind, CreateKind, DataChange, RemoveKind};
+    use temp_dir::TempDir;
+
+    use super::*;
+
+    fn event(kind: EventKind, path: &str) -> Event {
+        Event::new(kind).add_path(PathBuf::from(path))
+    }
+
+    fn changes(events: impl IntoIterator<Item = Event>) -> Changes {
+        Changes::from_events(events.into_iter().map(Ok))
+    }
+
+    fn set(paths: &[&Path]) -> BTreeSet<PathBuf> {
+        paths.iter().map(|p| p.to_path_buf()).collect()
+    }
+
+    #[test]
+    fn test_classify_events() {
+        let res = Changes::from_events([
+            Ok(event(
+                EventKind::Modify(ModifyKind::Data(DataChange::Content)),
+                "/p/src/A.sol",
+            )),
+            Ok(event(EventKind::Access(AccessKind::Read), "/p/src/B.sol")),
+            Err(notify::Error::generic("oops")),
+        ]);
+        assert_eq!(
+            res,
+            Changes {
+                paths: set(&[Path::new("/p/src/A.sol")]),
+                rescan: false,
+                reload: false,
+            }
+        );
+
+        let res = changes([
+            event(EventKind::Create(CreateKind::File), "/p/src/C.sol"),
+            event(
+                EventKind::Modify(ModifyKind::Data(DataChange::Any)),
+                "/p/src/A.sol",
+            ),
+        ]);
+        assert!(res.rescan);
+        assert!(!res.reload);
+        assert_eq!(res.paths.len(), 2);
+
+        let res = changes([event(EventKind::Remove(RemoveKind::File), "/p/src/A.sol")]);
+        assert!(res.rescan);
+
+        let res = changes([event(
+            EventKind::Modify(ModifyKind::Name(RenameMode::Both)),
+            "/p/src/A.sol",
+        )]);
+        assert!(res.rescan);
+
+        // modifying an ignore file refreshes the list of files
+        let res = changes([event(EventKind::Modify(ModifyKind::Any), "/p/.gitignore")]);
+        assert!(res.rescan);
+        assert!(!res.reload);
+
+        // modifying the remappings reloads the project
+        let res = changes([event(
+            EventKind::Modify(ModifyKind::Data(DataChange::Content)),
+            "/p/remappings.txt",
+        )]);
+        assert!(!res.rescan);
+        assert!(res.reload);
+    }
+
+    #[test]
+    fn test_update_paths() {
+        let a = PathBuf::from("/p/src/A.sol");
+        let b = PathBuf::from("/p/src/B.sol");
+        let c = PathBuf::from("/p/src/C.sol");
+        let mut paths = HashSet::from([a.clone(), b.clone()]);
+        let mut changed = set(&[&b]);
+        let removed = update_paths(
+            &mut paths,
+            HashSet::from([b.clone(), c.clone()]),
+            &mut changed,
+        );
+        assert_eq!(removed, vec![a]);
+        assert_eq!(paths, HashSet::from([b.clone(), c.clone()]));
+        // the new file is linted, the modified one is kept
+        assert_eq!(changed, set(&[&b, &c]));
+    }
+
+    #[test]
+    fn test_importers() {
+        let dir = TempDir::new().unwrap();
+        let root = dunce::canonicalize(dir.path()).unwrap();
+        let write = |name: &str, contents: &str| {
+            let path = root.join(name);
+            fs::write(&path, contents).unwrap();
+            path
+        };
+        // `Lib.sol` is not analyzed, but it's imported by `A.sol` and imports `C.sol`
+        let a = write("A.sol", "import \"./Lib.sol\";\ncontract A {}\n");
+        let lib = write("Lib.sol", "import \"./C.sol\";\ncontract Lib {}\n");
+        let b = write("B.sol", "import \"./A.sol\";\ncontract B {}\n");
+        let c = write("C.sol", "contract C {}\n");
+        let d = write("D.sol", "contract D {}\n");
+        let paths = HashSet::from([a.clone(), b.clone(), c.clone(), d.clone()]);
+        let project = Project::discover(&root);
+        let config = Config::default();
+        let parser = new_parser(&config);
+
+        assert_eq!(
+            importers(&project, &parser, &paths, &set(&[&c])),
+            set(&[&a, &b])
+        );
+        assert_eq!(
+            importers(&project, &parser, &paths, &set(&[&lib])),
+            set(&[&a, &b])
+        );
+        assert_eq!(importers(&project, &parser, &paths, &set(&[&b])), set(&[]));
+        assert_eq!(importers(&project, &parser, &paths, &set(&[&d])), set(&[]));
+
+        // `D.sol` now imports `C.sol`, the cached imports are only updated after invalidation
+        fs::write(&d, "import \"./C.sol\";\ncontract D {}\n").unwrap();
+        assert_eq!(
+            importers(&project, &parser, &paths, &set(&[&c])),
+            set(&[&a, &b])
+        );
+        project.invalidate(&d);
+        // the parser caches the sources by path
+        let parser = new_parser(&config);
+        assert_eq!(
+            importers(&project, &parser, &paths, &set(&[&c])),
+            set(&[&a, &b, &d])
+        );
+    }
+}